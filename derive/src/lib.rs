@@ -0,0 +1,278 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A derive macro for reading fixed-layout structs
+//! from a `bitstream_io::BitRead` stream.
+//!
+//! Deriving `BitRead` on a struct with named fields generates
+//! an inherent `from_bit_reader` method which reads each field
+//! in declaration order, as given by its attribute:
+//!
+//! * `#[bits = N]` reads an unsigned value of `N` bits, as by `read`
+//! * `#[signed(bits = N)]` reads a twos-complement value of `N` bits,
+//!   as by `read_signed`
+//!
+//! Fields of type `bool` are read as single bits
+//! and fields of type `[u8; N]` as `N` bytes, as by `read_bytes`,
+//! so they need no attribute.
+//!
+//! The generated method returns a `std::io::Result`,
+//! so the `std` feature of `bitstream-io` is required.
+//!
+//! # Example
+//! ```
+//! use std::io::Cursor;
+//! use bitstream_io::{BigEndian, BitReader};
+//! use bitstream_io_derive::BitRead;
+//!
+//! #[derive(BitRead)]
+//! struct Header {
+//!     #[bits = 3]
+//!     kind: u8,
+//!     #[signed(bits = 12)]
+//!     offset: i16,
+//!     flag: bool,
+//!     tag: [u8; 2],
+//! }
+//!
+//! let data = [0b101_11111, 0b1111111_1, b'h', b'i'];
+//! let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+//! let header = Header::from_bit_reader(&mut reader).unwrap();
+//! assert_eq!(header.kind, 5);
+//! assert_eq!(header.offset, -1);
+//! assert!(header.flag);
+//! assert_eq!(&header.tag, b"hi");
+//! ```
+
+#![warn(missing_docs)]
+#![forbid(unsafe_code)]
+
+extern crate proc_macro;
+
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
+/// Derives an inherent `from_bit_reader` method for a struct.
+///
+/// See the crate documentation for the field attributes supported.
+#[proc_macro_derive(BitRead, attributes(bits, signed))]
+pub fn derive_bit_read(input: TokenStream) -> TokenStream {
+    match derive(input) {
+        Ok(tokens) => tokens,
+        Err(message) => format!("compile_error!({:?});", message).parse().unwrap(),
+    }
+}
+
+/// How a single field is read from the stream
+enum FieldRead {
+    /// An unsigned value of the given number of bits
+    Unsigned(String),
+    /// A signed value of the given number of bits
+    Signed(String),
+    /// A single bit
+    Bit,
+    /// A byte array of the given length
+    Bytes(String),
+}
+
+struct Field {
+    name: String,
+    ty: String,
+    read: FieldRead,
+}
+
+fn derive(input: TokenStream) -> Result<TokenStream, String> {
+    let mut tokens = input.into_iter();
+
+    // skip any attributes and visibility before the struct's name
+    let name = loop {
+        match tokens.next() {
+            Some(TokenTree::Ident(ident)) => match ident.to_string().as_str() {
+                "struct" => match tokens.next() {
+                    Some(TokenTree::Ident(name)) => break name.to_string(),
+                    _ => return Err("expected struct name".into()),
+                },
+                "enum" | "union" => {
+                    return Err("BitRead can only be derived for structs".into());
+                }
+                _ => {}
+            },
+            Some(_) => {}
+            None => return Err("expected struct".into()),
+        }
+    };
+
+    let body = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => group.stream(),
+        Some(TokenTree::Punct(ref punct)) if punct.as_char() == '<' => {
+            return Err("BitRead cannot be derived for generic structs".into());
+        }
+        _ => return Err("BitRead can only be derived for structs with named fields".into()),
+    };
+
+    let fields = split_fields(body)
+        .into_iter()
+        .map(parse_field)
+        .collect::<Result<Vec<Field>, String>>()?;
+
+    let mut output = format!(
+        "impl {} {{\n\
+         /// Reads each field from the stream in declaration order\n\
+         pub fn from_bit_reader<R: ::bitstream_io::BitRead + ?Sized>(\n\
+         reader: &mut R,\n\
+         ) -> ::std::io::Result<Self> {{\n\
+         ::std::result::Result::Ok({} {{\n",
+        name, name
+    );
+    for field in fields {
+        let read = match field.read {
+            FieldRead::Unsigned(bits) => format!("reader.read::<{}>({})?", field.ty, bits),
+            FieldRead::Signed(bits) => format!("reader.read_signed::<{}>({})?", field.ty, bits),
+            FieldRead::Bit => "reader.read_bit()?".to_string(),
+            FieldRead::Bytes(len) => format!(
+                "{{ let mut bytes = [0u8; {}]; reader.read_bytes(&mut bytes)?; bytes }}",
+                len
+            ),
+        };
+        output.push_str(&format!("{}: {},\n", field.name, read));
+    }
+    output.push_str("})\n}\n}\n");
+
+    output
+        .parse()
+        .map_err(|_| "unable to generate BitRead implementation".into())
+}
+
+/// Splits a struct body on the commas between its fields,
+/// ignoring those within a type's angle brackets
+fn split_fields(body: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut fields = vec![Vec::new()];
+    let mut depth = 0;
+    for token in body {
+        match token {
+            TokenTree::Punct(ref punct) if punct.as_char() == '<' => depth += 1,
+            TokenTree::Punct(ref punct) if punct.as_char() == '>' => depth -= 1,
+            TokenTree::Punct(ref punct) if punct.as_char() == ',' && depth == 0 => {
+                fields.push(Vec::new());
+                continue;
+            }
+            _ => {}
+        }
+        fields.last_mut().unwrap().push(token);
+    }
+    fields.retain(|field| !field.is_empty());
+    fields
+}
+
+fn parse_field(tokens: Vec<TokenTree>) -> Result<Field, String> {
+    let mut tokens = tokens.into_iter().peekable();
+    let mut read = None;
+
+    // attributes, which may include doc comments
+    while let Some(TokenTree::Punct(ref punct)) = tokens.peek() {
+        if punct.as_char() != '#' {
+            break;
+        }
+        tokens.next();
+        match tokens.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => {
+                if let Some(attr) = parse_attribute(group.stream())? {
+                    read = Some(attr);
+                }
+            }
+            _ => return Err("expected attribute".into()),
+        }
+    }
+
+    // visibility
+    if let Some(TokenTree::Ident(ident)) = tokens.peek() {
+        if ident.to_string() == "pub" {
+            tokens.next();
+            if let Some(TokenTree::Group(group)) = tokens.peek() {
+                if group.delimiter() == Delimiter::Parenthesis {
+                    tokens.next();
+                }
+            }
+        }
+    }
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(ident)) => ident.to_string(),
+        _ => return Err("BitRead can only be derived for structs with named fields".into()),
+    };
+    match tokens.next() {
+        Some(TokenTree::Punct(ref punct)) if punct.as_char() == ':' => {}
+        _ => return Err(format!("expected type for field `{}`", name)),
+    }
+    let ty: Vec<TokenTree> = tokens.collect();
+
+    let read = match read {
+        Some(read) => read,
+        None => match ty.as_slice() {
+            [TokenTree::Ident(ident)] if ident.to_string() == "bool" => FieldRead::Bit,
+            [TokenTree::Group(group)] if group.delimiter() == Delimiter::Bracket => {
+                let array: Vec<TokenTree> = group.stream().into_iter().collect();
+                match array.as_slice() {
+                    [TokenTree::Ident(ident), TokenTree::Punct(punct), len @ ..]
+                        if ident.to_string() == "u8" && punct.as_char() == ';' =>
+                    {
+                        FieldRead::Bytes(stream_string(len))
+                    }
+                    _ => return Err(missing_attribute(&name)),
+                }
+            }
+            _ => return Err(missing_attribute(&name)),
+        },
+    };
+
+    Ok(Field {
+        name,
+        ty: stream_string(&ty),
+        read,
+    })
+}
+
+/// Returns how to read a field from one of its attributes,
+/// or `None` for an attribute that isn't ours
+fn parse_attribute(attr: TokenStream) -> Result<Option<FieldRead>, String> {
+    let attr: Vec<TokenTree> = attr.into_iter().collect();
+    match attr.as_slice() {
+        [TokenTree::Ident(ident), TokenTree::Punct(punct), bits @ ..]
+            if ident.to_string() == "bits" && punct.as_char() == '=' && !bits.is_empty() =>
+        {
+            Ok(Some(FieldRead::Unsigned(stream_string(bits))))
+        }
+        [TokenTree::Ident(ident), TokenTree::Group(group)] if ident.to_string() == "signed" => {
+            let args: Vec<TokenTree> = group.stream().into_iter().collect();
+            match args.as_slice() {
+                [TokenTree::Ident(ident), TokenTree::Punct(punct), bits @ ..]
+                    if ident.to_string() == "bits"
+                        && punct.as_char() == '='
+                        && !bits.is_empty() =>
+                {
+                    Ok(Some(FieldRead::Signed(stream_string(bits))))
+                }
+                _ => Err("expected #[signed(bits = N)]".into()),
+            }
+        }
+        [TokenTree::Ident(ident), ..] if ident.to_string() == "bits" => {
+            Err("expected #[bits = N]".into())
+        }
+        _ => Ok(None),
+    }
+}
+
+fn missing_attribute(name: &str) -> String {
+    format!(
+        "field `{}` needs a #[bits = N] or #[signed(bits = N)] attribute",
+        name
+    )
+}
+
+fn stream_string(tokens: &[TokenTree]) -> String {
+    tokens.iter().cloned().collect::<TokenStream>().to_string()
+}
@@ -0,0 +1,57 @@
+extern crate bitstream_io;
+extern crate bitstream_io_derive;
+use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+use bitstream_io_derive::BitRead;
+use std::io::Cursor;
+
+#[derive(BitRead, Debug, PartialEq)]
+struct Packet {
+    /// The packet's version
+    #[bits = 3]
+    version: u8,
+    #[signed(bits = 10)]
+    pub offset: i16,
+    #[bits = 19]
+    pub(crate) length: u32,
+    flag: bool,
+    #[signed(bits = 7)]
+    delta: i8,
+    tag: [u8; 3],
+}
+
+#[test]
+fn test_derive_bit_read() {
+    let actual_data: [u8; 8] = [0xB1, 0xED, 0x3B, 0xC1, 0x80, b'a', b'b', b'c'];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(
+        Packet::from_bit_reader(&mut r).unwrap(),
+        Packet {
+            version: 5,
+            offset: -451,
+            length: 0x53BC1,
+            flag: true,
+            delta: 0,
+            tag: *b"abc",
+        }
+    );
+    assert!(r.read_bit().is_err());
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    let packet = Packet::from_bit_reader(&mut r).unwrap();
+    assert_eq!(packet.version, 1);
+    assert_eq!(packet.offset, 438);
+    assert_eq!(packet.length, 0x609DF);
+    assert!(!packet.flag);
+    assert_eq!(packet.delta, -64);
+    assert_eq!(&packet.tag, b"abc");
+
+    // fields are read in order, so a short stream fails partway
+    let mut r = BitReader::endian(Cursor::new(&actual_data[0..6]), BigEndian);
+    assert!(Packet::from_bit_reader(&mut r).is_err());
+
+    // any BitRead will do, including a borrowed one
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    let mut borrowed = &mut r;
+    assert_eq!(Packet::from_bit_reader(&mut borrowed).unwrap().version, 5);
+}
@@ -13,9 +13,11 @@
 
 use super::BitQueue;
 use super::Endianness;
-use std::collections::BTreeMap;
-use std::fmt;
-use std::marker::PhantomData;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
 
 /// A compiled Huffman tree element for use with the `read_huffman` method.
 /// Returned by `compile_read_tree`.
@@ -97,6 +99,84 @@ where
     Ok(result.into_boxed_slice())
 }
 
+/// Given a list of symbol/code length pairs, assigns each symbol
+/// its canonical Huffman code, as specified by DEFLATE and JPEG.
+///
+/// Codes are assigned in order of increasing length,
+/// with symbols of the same length taking consecutive codes
+/// in the order they appear in the list.
+/// Symbols with a length of 0 are unused and receive no code.
+/// The resulting pairs may be passed to `compile_read_tree`
+/// or `compile_write_tree`.
+///
+/// # Errors
+///
+/// Returns `HuffmanTreeError::Oversubscribed` if there are
+/// more codes of some length than can be assigned.
+///
+/// ## Example
+/// ```
+/// use bitstream_io::huffman::canonical_codes;
+/// assert_eq!(
+///     canonical_codes(&[('a', 2), ('b', 1), ('c', 3), ('d', 3)]).unwrap(),
+///     vec![('b', vec![0]),
+///          ('a', vec![1, 0]),
+///          ('c', vec![1, 1, 0]),
+///          ('d', vec![1, 1, 1])]);
+/// ```
+pub fn canonical_codes<T: Clone>(
+    lengths: &[(T, u8)],
+) -> Result<Vec<(T, Vec<u8>)>, HuffmanTreeError> {
+    let mut sorted: Vec<&(T, u8)> = lengths.iter().filter(|(_, len)| *len > 0).collect();
+    sorted.sort_by_key(|(_, len)| *len);
+
+    let mut codes = Vec::with_capacity(sorted.len());
+    let mut code: Vec<u8> = Vec::new();
+    for (i, (symbol, len)) in sorted.into_iter().enumerate() {
+        if i > 0 {
+            // increment the previous code, then extend it with 0 bits
+            match code.iter().rposition(|bit| *bit == 0) {
+                Some(zero) => {
+                    code[zero] = 1;
+                    code[zero + 1..].iter_mut().for_each(|bit| *bit = 0);
+                }
+                None => return Err(HuffmanTreeError::Oversubscribed),
+            }
+        }
+        code.resize(usize::from(*len), 0);
+        codes.push((symbol.clone(), code.clone()));
+    }
+    Ok(codes)
+}
+
+/// Given a list of symbol/code length pairs, compiles a Huffman tree
+/// of canonical codes for reading, as by `canonical_codes`
+/// followed by `compile_read_tree`.
+///
+/// ## Example
+/// ```
+/// use std::io::{Read, Cursor};
+/// use bitstream_io::{BigEndian, BitReader};
+/// use bitstream_io::huffman::compile_read_tree_from_lengths;
+/// let tree = compile_read_tree_from_lengths(
+///     &[('a', 2), ('b', 1), ('c', 3), ('d', 3)]).unwrap();
+/// let data = [0b1001_1010];
+/// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+/// assert_eq!(reader.read_huffman(&tree).unwrap(), 'a');
+/// assert_eq!(reader.read_huffman(&tree).unwrap(), 'b');
+/// assert_eq!(reader.read_huffman(&tree).unwrap(), 'c');
+/// assert_eq!(reader.read_huffman(&tree).unwrap(), 'a');
+/// ```
+pub fn compile_read_tree_from_lengths<E, T>(
+    lengths: &[(T, u8)],
+) -> Result<Box<[ReadHuffmanTree<E, T>]>, HuffmanTreeError>
+where
+    E: Endianness,
+    T: Clone,
+{
+    compile_read_tree(canonical_codes(lengths)?)
+}
+
 fn compile_queue<E, T>(
     mut queue: BitQueue<E, u8>,
     tree: &FinalHuffmanTree<T>,
@@ -114,7 +194,7 @@ where
             if queue.is_empty() {
                 ReadHuffmanTree::Continue(
                     (0..256)
-                        .map(|byte| compile_queue(BitQueue::from_value(byte as u8, 8), &tree))
+                        .map(|byte| compile_queue(BitQueue::from_value(byte as u8, 8), tree))
                         .collect::<Vec<ReadHuffmanTree<E, T>>>()
                         .into_boxed_slice(),
                 )
@@ -222,6 +302,8 @@ pub enum HuffmanTreeError {
     DuplicateLeaf,
     /// A Huffman code is the prefix of some longer code
     OrphanedLeaf,
+    /// There are more codes of the given lengths than can be assigned
+    Oversubscribed,
 }
 
 impl fmt::Display for HuffmanTreeError {
@@ -231,6 +313,7 @@ impl fmt::Display for HuffmanTreeError {
             HuffmanTreeError::MissingLeaf => write!(f, "missing leaf node in specification"),
             HuffmanTreeError::DuplicateLeaf => write!(f, "duplicate leaf node in specification"),
             HuffmanTreeError::OrphanedLeaf => write!(f, "orphaned leaf node in specification"),
+            HuffmanTreeError::Oversubscribed => write!(f, "too many codes for given lengths"),
         }
     }
 }
@@ -327,4 +410,11 @@ impl<E: Endianness, T: Ord + Clone> WriteHuffmanTree<E, T> {
     pub fn get(&self, symbol: &T) -> &[(u32, u32)] {
         self.map[symbol].as_ref()
     }
+
+    /// Given symbol, returns (bits, value) pairs for writing code,
+    /// or `None` if symbol is not found.
+    #[inline]
+    pub fn try_get(&self, symbol: &T) -> Option<&[(u32, u32)]> {
+        self.map.get(symbol).map(|code| code.as_ref())
+    }
 }
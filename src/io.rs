@@ -0,0 +1,224 @@
+// Copyright 2017 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Minimal stand-ins for the parts of `std::io` this crate uses,
+//! available when the `std` feature is disabled.
+//!
+//! Streams to be wrapped by `BitReader` or `BitWriter`
+//! implement these `Read` or `Write` traits instead of the ones in `std`.
+//! Byte slices and `Vec<u8>` are supported out of the box.
+
+#![warn(missing_docs)]
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A specialized `Result` type for I/O operations.
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// The general category of an I/O error.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Data was not valid for the operation.
+    InvalidData,
+    /// A parameter was incorrect.
+    InvalidInput,
+    /// The operation was interrupted and may be retried.
+    Interrupted,
+    /// The stream ended before the operation could complete.
+    UnexpectedEof,
+    /// A write returned `Ok(0)`.
+    WriteZero,
+    /// Any other error.
+    Other,
+}
+
+/// The error type for I/O operations.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    kind: ErrorKind,
+    message: &'static str,
+}
+
+impl Error {
+    /// Builds a new error of the given kind with a fixed message.
+    #[inline]
+    pub fn new(kind: ErrorKind, message: &'static str) -> Error {
+        Error { kind, message }
+    }
+
+    /// Returns the error's category.
+    #[inline]
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl From<ErrorKind> for Error {
+    #[inline]
+    fn from(kind: ErrorKind) -> Error {
+        Error { kind, message: "" }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.message.is_empty() {
+            write!(f, "{:?}", self.kind)
+        } else {
+            f.write_str(self.message)
+        }
+    }
+}
+
+/// A source of bytes.
+pub trait Read {
+    /// Pulls some bytes into `buf`, returning how many were read.
+    /// Returning 0 indicates the end of the stream.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+    /// Reads exactly enough bytes to fill `buf`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedEof` if the stream ends first.
+    fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+        while !buf.is_empty() {
+            match self.read(buf) {
+                Ok(0) => {
+                    return Err(Error::new(
+                        ErrorKind::UnexpectedEof,
+                        "failed to fill whole buffer",
+                    ))
+                }
+                Ok(n) => buf = &mut buf[n..],
+                Err(ref err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    /// Borrows this reader.
+    #[inline]
+    fn by_ref(&mut self) -> &mut Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+/// A sink of bytes.
+pub trait Write {
+    /// Writes some bytes from `buf`, returning how many were written.
+    fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+    /// Flushes any buffered output.
+    fn flush(&mut self) -> Result<()>;
+
+    /// Writes the whole of `buf`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `WriteZero` if the stream stops accepting bytes.
+    fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+        while !buf.is_empty() {
+            match self.write(buf) {
+                Ok(0) => {
+                    return Err(Error::new(
+                        ErrorKind::WriteZero,
+                        "failed to write whole buffer",
+                    ))
+                }
+                Ok(n) => buf = &buf[n..],
+                Err(ref err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    /// Borrows this writer.
+    #[inline]
+    fn by_ref(&mut self) -> &mut Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+impl<R: Read + ?Sized> Read for &mut R {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        (**self).read(buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        (**self).read_exact(buf)
+    }
+}
+
+impl Read for &[u8] {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let amount = core::cmp::min(buf.len(), self.len());
+        let (head, tail) = self.split_at(amount);
+        buf[0..amount].copy_from_slice(head);
+        *self = tail;
+        Ok(amount)
+    }
+}
+
+impl<W: Write + ?Sized> Write for &mut W {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        (**self).write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<()> {
+        (**self).flush()
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        (**self).write_all(buf)
+    }
+}
+
+impl Write for &mut [u8] {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let amount = core::cmp::min(buf.len(), self.len());
+        let (head, tail) = core::mem::take(self).split_at_mut(amount);
+        head.copy_from_slice(&buf[0..amount]);
+        *self = tail;
+        Ok(amount)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Write for Vec<u8> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -27,21 +27,52 @@
 //! Readers and writers are also designed to work with integer
 //! types of any possible size.
 //! Many of Rust's built-in integer types are supported by default.
+//!
+//! The `std` feature is enabled by default.
+//! Disabling it builds the crate against `core` and `alloc` only,
+//! in which case wrapped streams implement the minimal `Read` and `Write`
+//! traits from this crate's `io` module instead of the ones in `std::io`.
+//!
+//! The `alloc` feature, implied by `std`, may also be disabled
+//! for targets without a heap.
+//! The readers and writers themselves hold only a wrapped stream
+//! and a few scalars, since whole bytes are pulled from the stream
+//! one at a time and at most a single partial byte is buffered.
+//! Only the `huffman` module and methods returning or filling
+//! a `Vec`, such as `read_to_vec`, require allocation.
 
 #![warn(missing_docs)]
 #![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use std::fmt::Debug;
-use std::io;
-use std::marker::PhantomData;
-use std::mem;
-use std::ops::{BitOrAssign, BitXor, Not, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub};
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use core::mem;
+use core::ops::{BitOrAssign, BitXor, Not, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub};
+
+#[cfg(feature = "alloc")]
 pub mod huffman;
+#[cfg(not(feature = "std"))]
+pub mod io;
+#[cfg(feature = "std")]
+use std::io;
 pub mod read;
 pub mod write;
-pub use read::BitReader;
-pub use write::BitWriter;
+pub use read::{
+    AccumulatorReader, BitPattern, BitRead, BitReader, BitReaderTake, Bits, ByteRead,
+    ChecksumReader, Crc16Reader, Crc32Reader, DynamicEndianBitReader, InterleavedBitReader,
+    Justify, OrderedBitReader, Overflow, ParityReader, PositionMismatch, ReaderState,
+    ReverseByteReader, RunLengthDecoder, SignedFormat, TransformReader, TruncatedRead,
+    UnexpectedValue, Unstuffer, ValueOutOfRange, ZeroPadded,
+};
+#[cfg(feature = "alloc")]
+pub use read::{FieldSpec, FrameReader, MagicMismatch, OwnedBytes, RewindReader};
+pub use write::{
+    bits_required, bits_required_signed, BitCounter, BitWrite, BitWriter, OrderedBitWriter,
+};
 
 /// This trait extends many common integer types (both unsigned and signed)
 /// with a few trivial methods so that they can be used
@@ -140,6 +171,14 @@ pub trait SignedNumeric: Numeric {
     /// Given a negative value and a certain number of bits,
     /// returns this value as a twos-complement positive number.
     fn as_unsigned(self, bits: u32) -> Self;
+
+    /// Converts a wider value to this type,
+    /// keeping only its low bits if out of range.
+    fn wrapping_from_i128(value: i128) -> Self;
+
+    /// Converts a wider value to this type,
+    /// clamping to this type's minimum or maximum if out of range.
+    fn saturating_from_i128(value: i128) -> Self;
 }
 
 macro_rules! define_signed_numeric {
@@ -157,6 +196,20 @@ macro_rules! define_signed_numeric {
             fn as_unsigned(self, bits: u32) -> Self {
                 self - (-1 << (bits - 1))
             }
+            #[inline(always)]
+            fn wrapping_from_i128(value: i128) -> Self {
+                value as $t
+            }
+            #[inline(always)]
+            fn saturating_from_i128(value: i128) -> Self {
+                if value < <$t>::MIN as i128 {
+                    <$t>::MIN
+                } else if value > <$t>::MAX as i128 {
+                    <$t>::MAX
+                } else {
+                    value as $t
+                }
+            }
         }
     };
 }
@@ -217,18 +270,70 @@ pub trait Endianness: Sized {
         N: Numeric;
 
     /// Reads signed value from reader in this endianness
-    fn read_signed<R, S>(r: &mut BitReader<R, Self>, bits: u32) -> io::Result<S>
+    fn read_signed<R, S>(r: &mut R, bits: u32) -> io::Result<S>
     where
-        R: io::Read,
+        R: BitRead,
         S: SignedNumeric;
 
     /// Writes signed value to writer in this endianness
-    fn write_signed<W, S>(w: &mut BitWriter<W, Self>, bits: u32, value: S) -> io::Result<()>
+    fn write_signed<W, S>(w: &mut W, bits: u32, value: S) -> io::Result<()>
     where
-        W: io::Write,
+        W: BitWrite,
         S: SignedNumeric;
 }
 
+/// Returns true if the value is smaller than `2 ^ bits`,
+/// where `bits` is smaller than the value's type.
+/// For signed types, `2 ^ bits` may wrap to the minimum value,
+/// in which case the value is not checked.
+#[inline]
+fn fits_bits<N: Numeric>(bits: u32, value: N) -> bool {
+    let limit = N::one() << bits;
+    limit <= N::default() || value < limit
+}
+
+/// Returns true if the value is representable
+/// as a twos-complement number of the given size
+/// no larger than its own type.
+/// Only zero fits in 0 bits.
+#[inline]
+fn signed_fits<S: SignedNumeric>(bits: u32, value: S) -> bool {
+    if bits == 0 {
+        value == S::default()
+    } else if bits == S::bits_size() {
+        true
+    } else if value.is_negative() {
+        !value.as_unsigned(bits).is_negative()
+    } else {
+        value < (S::one() << (bits - 1))
+    }
+}
+
+/// Returns the total size of a fixed-point number
+/// with the given integer and fractional bits.
+fn fixed_bits(int_bits: u32, frac_bits: u32) -> io::Result<u32> {
+    match int_bits.checked_add(frac_bits) {
+        Some(bits) if bits <= 64 => Ok(bits),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "excessive bits for fixed-point value",
+        )),
+    }
+}
+
+/// Reverses the order of a value's low `bits` bits,
+/// where `bits` is at most 32.
+#[inline]
+fn reverse_within(bits: u32, value: u32) -> u32 {
+    value.reverse_bits().checked_shr(32 - bits).unwrap_or(0)
+}
+
+/// Returns `2 ^ -frac_bits`, where `frac_bits` is at most 64.
+#[inline]
+fn fixed_scale(frac_bits: u32) -> f64 {
+    f64::from_bits(u64::from(1023 - frac_bits) << 52)
+}
+
 /// Big-endian, or most significant bits first
 #[derive(Copy, Clone)]
 pub struct BigEndian;
@@ -303,12 +408,14 @@ impl Endianness for BigEndian {
         }
     }
 
-    fn read_signed<R, S>(r: &mut BitReader<R, Self>, bits: u32) -> io::Result<S>
+    fn read_signed<R, S>(r: &mut R, bits: u32) -> io::Result<S>
     where
-        R: io::Read,
+        R: BitRead,
         S: SignedNumeric,
     {
-        if bits <= S::bits_size() {
+        if bits == 0 {
+            Ok(S::default())
+        } else if bits <= S::bits_size() {
             let is_negative = r.read_bit()?;
             let unsigned = r.read::<S>(bits - 1)?;
             Ok(if is_negative {
@@ -324,9 +431,9 @@ impl Endianness for BigEndian {
         }
     }
 
-    fn write_signed<W, S>(w: &mut BitWriter<W, Self>, bits: u32, value: S) -> io::Result<()>
+    fn write_signed<W, S>(w: &mut W, bits: u32, value: S) -> io::Result<()>
     where
-        W: io::Write,
+        W: BitWrite,
         S: SignedNumeric,
     {
         if bits > S::bits_size() {
@@ -334,6 +441,13 @@ impl Endianness for BigEndian {
                 io::ErrorKind::InvalidInput,
                 "excessive bits for type written",
             ))
+        } else if !signed_fits(bits, value) {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive value for bits written",
+            ))
+        } else if bits == 0 {
+            Ok(())
         } else if value.is_negative() {
             w.write_bit(true)
                 .and_then(|()| w.write(bits - 1, value.as_unsigned(bits)))
@@ -411,12 +525,14 @@ impl Endianness for LittleEndian {
         (queue.value ^ !N::default()).trailing_zeros()
     }
 
-    fn read_signed<R, S>(r: &mut BitReader<R, Self>, bits: u32) -> io::Result<S>
+    fn read_signed<R, S>(r: &mut R, bits: u32) -> io::Result<S>
     where
-        R: io::Read,
+        R: BitRead,
         S: SignedNumeric,
     {
-        if bits <= S::bits_size() {
+        if bits == 0 {
+            Ok(S::default())
+        } else if bits <= S::bits_size() {
             let unsigned = r.read::<S>(bits - 1)?;
             let is_negative = r.read_bit()?;
             Ok(if is_negative {
@@ -432,9 +548,9 @@ impl Endianness for LittleEndian {
         }
     }
 
-    fn write_signed<W, S>(w: &mut BitWriter<W, Self>, bits: u32, value: S) -> io::Result<()>
+    fn write_signed<W, S>(w: &mut W, bits: u32, value: S) -> io::Result<()>
     where
-        W: io::Write,
+        W: BitWrite,
         S: SignedNumeric,
     {
         if bits > S::bits_size() {
@@ -442,6 +558,13 @@ impl Endianness for LittleEndian {
                 io::ErrorKind::InvalidInput,
                 "excessive bits for type written",
             ))
+        } else if !signed_fits(bits, value) {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive value for bits written",
+            ))
+        } else if bits == 0 {
+            Ok(())
         } else if value.is_negative() {
             w.write(bits - 1, value.as_unsigned(bits))
                 .and_then(|()| w.write_bit(true))
@@ -451,6 +574,21 @@ impl Endianness for LittleEndian {
     }
 }
 
+/// The order of a multi-byte value's bytes,
+/// independent of the order of bits within each byte.
+///
+/// Used by `OrderedBitReader` and `OrderedBitWriter`,
+/// whose endianness gives the order of bits within each byte.
+/// A value is split into 8-bit groups in stream order,
+/// where any partial group comes last.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// Most significant byte first
+    Big,
+    /// Least significant byte first
+    Little,
+}
+
 /// A queue for efficiently pushing bits onto a value
 /// and popping them off a value.
 pub struct BitQueue<E: Endianness, N: Numeric> {
@@ -459,6 +597,22 @@ pub struct BitQueue<E: Endianness, N: Numeric> {
     bits: u32,
 }
 
+impl<E: Endianness, N: Numeric> Clone for BitQueue<E, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E: Endianness, N: Numeric> Copy for BitQueue<E, N> {}
+
+impl<E: Endianness, N: Numeric> Default for BitQueue<E, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<E: Endianness, N: Numeric> BitQueue<E, N> {
     /// Returns a new empty queue
     #[inline]
@@ -475,7 +629,7 @@ impl<E: Endianness, N: Numeric> BitQueue<E, N> {
     #[inline]
     pub fn from_value(value: N, bits: u32) -> BitQueue<E, N> {
         assert!(if bits < N::bits_size() {
-            value < (N::one() << bits)
+            fits_bits(bits, value)
         } else {
             bits <= N::bits_size()
         });
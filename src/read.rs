@@ -16,7 +16,7 @@
 //!
 //! ```
 //! use std::io::{Cursor, Read};
-//! use bitstream_io::{BigEndian, BitReader};
+//! use bitstream_io::{BigEndian, BitReader, BitRead};
 //!
 //! let flac: Vec<u8> = vec![0x66,0x4C,0x61,0x43,0x00,0x00,0x00,0x22,
 //!                          0x10,0x00,0x10,0x00,0x00,0x06,0x06,0x00,
@@ -74,45 +74,538 @@
 
 #![warn(missing_docs)]
 
-use std::io;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
 
-use super::{huffman::ReadHuffmanTree, BitQueue, Endianness, Numeric, SignedNumeric};
+#[cfg(feature = "std")]
+use super::fits_bits;
+#[cfg(feature = "alloc")]
+use super::huffman::ReadHuffmanTree;
+use super::{
+    fixed_bits, fixed_scale, io, reverse_within, BigEndian, BitQueue, ByteOrder, Endianness,
+    LittleEndian, Numeric, SignedNumeric,
+};
 
-/// For reading non-aligned bits from a stream of bytes in a given endianness.
+/// How a signed value's bits are to be interpreted
+/// by `read_signed_with`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SignedFormat {
+    /// The usual twos-complement representation, as used by `read_signed`
+    TwosComplement,
+    /// A sign bit, set for negative values, followed by the magnitude
+    SignMagnitude,
+    /// Like `SignMagnitude`, but a negative zero is an error
+    /// rather than 0, for formats which forbid it
+    /// or reserve it as a sentinel
+    SignMagnitudeStrict,
+}
+
+/// How `read_signed_as` converts values too large for its output type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// Keeps only the value's low bits, like an `as` cast
+    Wrapping,
+    /// Clamps the value to the output type's minimum or maximum
+    Saturating,
+}
+
+/// Where `read_bits_to_bytes` places a field which is not
+/// a whole number of bytes long within its output bytes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Justify {
+    /// Field starts at the first byte's high bit, padded with 0s at the end
+    Left,
+    /// Field ends at the last byte's low bit, padded with 0s at the start
+    Right,
+}
+
+/// A named field of a given width, for reading with `read_schema`.
 ///
-/// This will read exactly as many whole bytes needed to return
-/// the requested number of bits.  It may cache up to a single partial byte
-/// but no more.
-pub struct BitReader<R: io::Read, E: Endianness> {
-    reader: R,
-    bitqueue: BitQueue<E, u8>,
+/// # Example
+/// ```
+/// use bitstream_io::FieldSpec;
+/// let field = FieldSpec::signed("offset", 12);
+/// assert_eq!(field.name(), "offset");
+/// assert_eq!(field.bits(), 12);
+/// assert!(field.is_signed());
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldSpec {
+    name: String,
+    bits: u32,
+    signed: bool,
 }
 
-impl<R: io::Read, E: Endianness> BitReader<R, E> {
-    /// Wraps a BitReader around something that implements `Read`
-    pub fn new(reader: R) -> BitReader<R, E> {
-        BitReader {
-            reader,
-            bitqueue: BitQueue::new(),
+#[cfg(feature = "alloc")]
+impl FieldSpec {
+    /// An unsigned field with the given number of bits
+    #[inline]
+    pub fn unsigned(name: impl Into<String>, bits: u32) -> FieldSpec {
+        FieldSpec {
+            name: name.into(),
+            bits,
+            signed: false,
         }
     }
 
-    /// Wraps a BitReader around something that implements `Read`
-    /// with the given endianness.
-    pub fn endian(reader: R, _endian: E) -> BitReader<R, E> {
-        BitReader {
-            reader,
-            bitqueue: BitQueue::new(),
+    /// A twos-complement signed field with the given number of bits
+    #[inline]
+    pub fn signed(name: impl Into<String>, bits: u32) -> FieldSpec {
+        FieldSpec {
+            name: name.into(),
+            bits,
+            signed: true,
         }
     }
 
-    /// Unwraps internal reader and disposes of BitReader.
-    /// Any unread partial bits are discarded.
+    /// The field's name
     #[inline]
-    pub fn into_reader(self) -> R {
-        self.reader
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The field's width in bits
+    #[inline]
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// Whether the field is signed
+    #[inline]
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+}
+
+/// Details of a read which ran out of input partway through.
+///
+/// When the `std` feature is enabled, `BitReader` attaches this to
+/// the `UnexpectedEof` errors returned by its fixed-size reads,
+/// where it may be retrieved with `io::Error::get_ref`.
+///
+/// # Example
+/// ```
+/// use std::io::{Cursor, ErrorKind};
+/// use bitstream_io::{BigEndian, BitReader, BitRead, TruncatedRead};
+/// let data = [0b10110111, 0b01100000];
+/// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+/// reader.skip(3).unwrap();
+/// let err = reader.read::<u32>(17).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+/// let truncated = err.get_ref().unwrap().downcast_ref::<TruncatedRead>().unwrap();
+/// assert_eq!(truncated.requested(), 17);
+/// assert_eq!(truncated.received(), 13);
+/// assert_eq!(err.to_string(), "truncated 17-bit read, got 13 bits");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TruncatedRead {
+    requested: u64,
+    received: u64,
+}
+
+impl TruncatedRead {
+    /// The number of bits the read asked for
+    #[inline]
+    pub fn requested(&self) -> u64 {
+        self.requested
+    }
+
+    /// The number of those bits available before the stream ended
+    #[inline]
+    pub fn received(&self) -> u64 {
+        self.received
+    }
+}
+
+impl fmt::Display for TruncatedRead {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "truncated {}-bit read, got {} bits",
+            self.requested, self.received
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TruncatedRead {}
+
+/// Details of magic bytes which did not match
+/// those required by `align_and_expect`.
+///
+/// When the `std` feature is enabled, this is attached to
+/// the `InvalidData` error returned by `align_and_expect`,
+/// where it may be retrieved with `io::Error::get_ref`.
+///
+/// # Example
+/// ```
+/// use std::io::{Cursor, ErrorKind};
+/// use bitstream_io::{BigEndian, BitReader, BitRead, MagicMismatch};
+/// let data = b"RIFF";
+/// let mut reader = BitReader::endian(Cursor::new(data), BigEndian);
+/// let err = reader.align_and_expect(b"fLaC").unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::InvalidData);
+/// let mismatch = err.get_ref().unwrap().downcast_ref::<MagicMismatch>().unwrap();
+/// assert_eq!(mismatch.found(), b"RIFF");
+/// assert_eq!(err.to_string(), "expected magic 664C6143, found 52494646");
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MagicMismatch {
+    expected: Vec<u8>,
+    found: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl MagicMismatch {
+    /// The magic bytes required
+    #[inline]
+    pub fn expected(&self) -> &[u8] {
+        &self.expected
+    }
+
+    /// The bytes actually read from the stream
+    #[inline]
+    pub fn found(&self) -> &[u8] {
+        &self.found
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for MagicMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("expected magic ")?;
+        self.expected
+            .iter()
+            .try_for_each(|b| write!(f, "{:02X}", b))?;
+        f.write_str(", found ")?;
+        self.found.iter().try_for_each(|b| write!(f, "{:02X}", b))
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MagicMismatch {}
+
+/// Details of a fixed field, such as a magic number,
+/// which did not hold the value `expect` required.
+///
+/// When the `std` feature is enabled, this is attached to
+/// the `InvalidData` error returned by `expect`,
+/// where it may be retrieved with `io::Error::get_ref`.
+///
+/// # Example
+/// ```
+/// use std::io::{Cursor, ErrorKind};
+/// use bitstream_io::{BigEndian, BitReader, BitRead, UnexpectedValue};
+/// let data = b"RIFX";
+/// let mut reader = BitReader::endian(Cursor::new(data), BigEndian);
+/// let err = reader.expect(32, 0x52494646).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::InvalidData);
+/// let unexpected = err.get_ref().unwrap().downcast_ref::<UnexpectedValue>().unwrap();
+/// assert_eq!(unexpected.expected(), 0x52494646);
+/// assert_eq!(unexpected.actual(), 0x52494658);
+/// assert_eq!(err.to_string(), "expected 0x52494646, got 0x52494658");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct UnexpectedValue {
+    expected: u32,
+    actual: u32,
+}
+
+impl UnexpectedValue {
+    /// The value the field was required to hold
+    #[inline]
+    pub fn expected(&self) -> u32 {
+        self.expected
+    }
+
+    /// The value actually read from the stream
+    #[inline]
+    pub fn actual(&self) -> u32 {
+        self.actual
+    }
+}
+
+impl fmt::Display for UnexpectedValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected {:#x}, got {:#x}", self.expected, self.actual)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnexpectedValue {}
+
+/// Details of a stream which `assert_position` found
+/// at some position other than the one expected.
+///
+/// When the `std` feature is enabled, this is attached to
+/// the `InvalidData` error returned by `assert_position`,
+/// where it may be retrieved with `io::Error::get_ref`.
+///
+/// # Example
+/// ```
+/// use std::io::{Cursor, ErrorKind};
+/// use bitstream_io::{BigEndian, BitReader, BitRead, PositionMismatch};
+/// let data = [0, 0];
+/// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+/// reader.skip(9).unwrap();
+/// let err = reader.assert_position(8).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::InvalidData);
+/// let mismatch = err.get_ref().unwrap().downcast_ref::<PositionMismatch>().unwrap();
+/// assert_eq!(mismatch.expected(), 8);
+/// assert_eq!(mismatch.actual(), 9);
+/// assert_eq!(err.to_string(), "expected position 8, at position 9");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PositionMismatch {
+    expected: u64,
+    actual: u64,
+}
+
+impl PositionMismatch {
+    /// The bit position the stream was required to be at
+    #[inline]
+    pub fn expected(&self) -> u64 {
+        self.expected
+    }
+
+    /// The stream's actual bit position
+    #[inline]
+    pub fn actual(&self) -> u64 {
+        self.actual
+    }
+}
+
+impl fmt::Display for PositionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "expected position {}, at position {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PositionMismatch {}
+
+/// Details of a signed field which `read_signed_checked`
+/// found outside the range the caller allowed.
+///
+/// When the `std` feature is enabled, this is attached to
+/// the `InvalidData` error returned by `read_signed_checked`,
+/// where it may be retrieved with `io::Error::get_ref`.
+///
+/// # Example
+/// ```
+/// use std::io::{Cursor, ErrorKind};
+/// use bitstream_io::{BigEndian, BitReader, BitRead, ValueOutOfRange};
+/// let data = [0b1000_0000];
+/// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+/// let err = reader.read_signed_checked(7, -32, 31).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::InvalidData);
+/// let range = err.get_ref().unwrap().downcast_ref::<ValueOutOfRange>().unwrap();
+/// assert_eq!(range.value(), -64);
+/// assert_eq!(err.to_string(), "value -64 outside range -32..=31");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ValueOutOfRange {
+    value: i32,
+    min: i32,
+    max: i32,
+}
+
+impl ValueOutOfRange {
+    /// The value read from the stream
+    #[inline]
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    /// The smallest value allowed
+    #[inline]
+    pub fn min(&self) -> i32 {
+        self.min
+    }
+
+    /// The largest value allowed
+    #[inline]
+    pub fn max(&self) -> i32 {
+        self.max
+    }
+}
+
+impl fmt::Display for ValueOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "value {} outside range {}..={}",
+            self.value, self.min, self.max
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValueOutOfRange {}
+
+/// The exact bits consumed by `BitReader::read_logged`,
+/// for displaying in a bit dump.
+///
+/// Displays as its bits in stream order, grouped by fours,
+/// such as `1011_01`.
+///
+/// # Example
+/// ```
+/// use std::io::Cursor;
+/// use bitstream_io::{LittleEndian, BitReader};
+/// let data = [0b1101_0110];
+/// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+/// let (value, pattern) = reader.read_logged(5).unwrap();
+/// assert_eq!(value, 0b10110);
+/// assert_eq!(pattern.to_string(), "0110_1");
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BitPattern {
+    bits: u32,
+    len: u32,
+}
+
+impl BitPattern {
+    /// The bits consumed, with the first in the most significant
+    /// of the low `len` bits
+    #[inline]
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// The number of bits consumed
+    #[inline]
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Whether no bits were consumed
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl fmt::Display for BitPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for i in 0..self.len {
+            if i > 0 && i % 4 == 0 {
+                f.write_str("_")?;
+            }
+            let bit = (self.bits >> (self.len - i - 1)) & 1;
+            f.write_str(if bit == 0 { "0" } else { "1" })?;
+        }
+        Ok(())
+    }
+}
+
+/// A snapshot of where a `BitReader` left off,
+/// returned by `BitReader::save_state`
+/// and restored by `BitReader::from_saved_state`.
+///
+/// It holds no part of the underlying stream,
+/// so it may be persisted by its parts and rebuilt
+/// by `from_parts` to resume parsing after a restart.
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Cursor, Seek, SeekFrom};
+/// use bitstream_io::{BigEndian, BitReader, BitRead, ReaderState};
+/// let data = [0b1011_0110, 0b1110_0001];
+/// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+/// assert_eq!(reader.read::<u8>(3).unwrap(), 0b101);
+/// let state = reader.save_state();
+/// let (position, bytes, bits, value) =
+///     (state.position(), state.bytes_consumed(), state.bits(), state.value());
+///
+/// let state = ReaderState::from_parts(position, bytes, bits, value).unwrap();
+/// let mut cursor = Cursor::new(&data);
+/// cursor.seek(SeekFrom::Start(state.bytes_consumed())).unwrap();
+/// let mut reader = BitReader::<_, BigEndian>::from_saved_state(cursor, state);
+/// assert_eq!(reader.position(), 3);
+/// assert_eq!(reader.read::<u16>(13).unwrap(), 0b1_0110_1110_0001);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ReaderState {
+    position: u64,
+    bytes_consumed: u64,
+    bits: u32,
+    value: u8,
+}
+
+impl ReaderState {
+    /// Rebuilds a state from the parts returned by its accessors
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidData` if the parts
+    /// are inconsistent, such as a partial byte of 8 or more bits
+    /// or a value too large for its bits.
+    pub fn from_parts(
+        position: u64,
+        bytes_consumed: u64,
+        bits: u32,
+        value: u8,
+    ) -> io::Result<ReaderState> {
+        let unread_bits = bytes_consumed.checked_mul(8).and_then(|total| {
+            total
+                .checked_sub(position)
+                .and_then(|unread| unread.checked_sub(u64::from(bits)))
+        });
+        if bits < 8 && u32::from(value) >> bits == 0 && unread_bits.is_some() {
+            Ok(ReaderState {
+                position,
+                bytes_consumed,
+                bits,
+                value,
+            })
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "inconsistent reader state",
+            ))
+        }
+    }
+
+    /// The reader's `position` when saved
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// The reader's `total_bytes_consumed` when saved,
+    /// which is how far to seek a fresh underlying stream
+    /// if reading started at its beginning
+    #[inline]
+    pub fn bytes_consumed(&self) -> u64 {
+        self.bytes_consumed
+    }
+
+    /// The number of unread bits in the partial byte
+    #[inline]
+    pub fn bits(&self) -> u32 {
+        self.bits
     }
 
+    /// The unread bits in the partial byte, as from `into_unread`
+    #[inline]
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+}
+
+/// A trait for anything that can read a variable number of
+/// potentially un-aligned values from an input stream
+pub trait BitRead {
     /// Reads a single bit from the stream.
     /// `true` indicates 1, `false` indicates 0
     ///
@@ -124,7 +617,7 @@ impl<R: io::Read, E: Endianness> BitReader<R, E> {
     ///
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{BigEndian, BitReader};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
     /// let data = [0b10110111];
     /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
     /// assert_eq!(reader.read_bit().unwrap(), true);
@@ -139,7 +632,7 @@ impl<R: io::Read, E: Endianness> BitReader<R, E> {
     ///
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{LittleEndian, BitReader};
+    /// use bitstream_io::{LittleEndian, BitReader, BitRead};
     /// let data = [0b10110111];
     /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
     /// assert_eq!(reader.read_bit().unwrap(), true);
@@ -151,16 +644,36 @@ impl<R: io::Read, E: Endianness> BitReader<R, E> {
     /// assert_eq!(reader.read_bit().unwrap(), false);
     /// assert_eq!(reader.read_bit().unwrap(), true);
     /// ```
-    #[inline(always)]
-    pub fn read_bit(&mut self) -> io::Result<bool> {
-        if self.bitqueue.is_empty() {
-            self.bitqueue.set(read_byte(&mut self.reader)?, 8);
+    fn read_bit(&mut self) -> io::Result<bool>;
+
+    /// Fills the given buffer with single bits from the stream,
+    /// in the order they are read.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b10110111];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// let mut flags = [false; 4];
+    /// reader.read_bits(&mut flags).unwrap();
+    /// assert_eq!(flags, [true, false, true, true]);
+    /// ```
+    fn read_bits(&mut self, buf: &mut [bool]) -> io::Result<()> {
+        for bit in buf.iter_mut() {
+            *bit = self.read_bit()?;
         }
-        Ok(self.bitqueue.pop(1) == 1)
+        Ok(())
     }
 
     /// Reads an unsigned value from the stream with
     /// the given number of bits.
+    /// Reading 0 bits consumes nothing and returns 0
+    /// without touching the underlying stream, even at its end.
     ///
     /// # Errors
     ///
@@ -171,7 +684,7 @@ impl<R: io::Read, E: Endianness> BitReader<R, E> {
     /// # Examples
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{BigEndian, BitReader};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
     /// let data = [0b10110111];
     /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
     /// assert_eq!(reader.read::<u8>(1).unwrap(), 0b1);
@@ -181,7 +694,7 @@ impl<R: io::Read, E: Endianness> BitReader<R, E> {
     ///
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{LittleEndian, BitReader};
+    /// use bitstream_io::{LittleEndian, BitReader, BitRead};
     /// let data = [0b10110111];
     /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
     /// assert_eq!(reader.read::<u8>(1).unwrap(), 0b1);
@@ -191,7 +704,7 @@ impl<R: io::Read, E: Endianness> BitReader<R, E> {
     ///
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{BigEndian, BitReader};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
     /// let data = [0;10];
     /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
     /// assert!(reader.read::<u8>(9).is_err());    // can't read  9 bits to u8
@@ -199,33 +712,83 @@ impl<R: io::Read, E: Endianness> BitReader<R, E> {
     /// assert!(reader.read::<u32>(33).is_err());  // can't read 33 bits to u32
     /// assert!(reader.read::<u64>(65).is_err());  // can't read 65 bits to u64
     /// ```
-    pub fn read<U>(&mut self, mut bits: u32) -> io::Result<U>
+    fn read<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric;
+
+    /// Fills the given buffer with unsigned values from the stream,
+    /// each with the given number of bits.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the output type is too small
+    /// to hold the requested number of bits.
+    /// Values before the failing one are left in the buffer.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b101_110_01, 0b1_000_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// let mut samples = [0u8; 4];
+    /// reader.read_into(3, &mut samples).unwrap();
+    /// assert_eq!(samples, [0b101, 0b110, 0b011, 0b000]);
+    /// ```
+    fn read_into<U>(&mut self, bits: u32, buf: &mut [U]) -> io::Result<()>
     where
         U: Numeric,
     {
-        if bits <= U::bits_size() {
-            let bitqueue_len = self.bitqueue.len();
-            if bits <= bitqueue_len {
-                Ok(U::from_u8(self.bitqueue.pop(bits)))
-            } else {
-                let mut acc =
-                    BitQueue::from_value(U::from_u8(self.bitqueue.pop_all()), bitqueue_len);
-                bits -= bitqueue_len;
+        for value in buf.iter_mut() {
+            *value = self.read(bits)?;
+        }
+        Ok(())
+    }
 
-                read_aligned(&mut self.reader, bits / 8, &mut acc)?;
-                read_unaligned(&mut self.reader, bits % 8, &mut acc, &mut self.bitqueue)?;
-                Ok(acc.value())
+    /// Fills the given buffer with differentially coded samples:
+    /// a twos-complement signed base value of `base_bits`
+    /// followed by a signed delta of `delta_bits` for each later sample,
+    /// which is added to the sample before it.
+    /// Sums wrap around on overflow.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if either width is larger than 32.
+    /// Samples before the failing one are left in the buffer.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b01100_001, 0b111_011_00];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// let mut samples = [0; 4];
+    /// reader.read_delta_samples(5, 3, &mut samples).unwrap();
+    /// assert_eq!(samples, [12, 13, 12, 15]);
+    /// ```
+    fn read_delta_samples(
+        &mut self,
+        base_bits: u32,
+        delta_bits: u32,
+        out: &mut [i32],
+    ) -> io::Result<()> {
+        if let Some((first, rest)) = out.split_first_mut() {
+            let mut sample = self.read_signed::<i32>(base_bits)?;
+            *first = sample;
+            for value in rest.iter_mut() {
+                sample = sample.wrapping_add(self.read_signed(delta_bits)?);
+                *value = sample;
             }
-        } else {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "excessive bits for type read",
-            ))
         }
+        Ok(())
     }
 
     /// Reads a twos-complement signed value from the stream with
     /// the given number of bits.
+    /// Like `read`, reading 0 bits consumes nothing and returns 0
+    /// without touching the underlying stream.
     ///
     /// # Errors
     ///
@@ -236,7 +799,7 @@ impl<R: io::Read, E: Endianness> BitReader<R, E> {
     /// # Examples
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{BigEndian, BitReader};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
     /// let data = [0b10110111];
     /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
     /// assert_eq!(reader.read_signed::<i8>(4).unwrap(), -5);
@@ -245,7 +808,7 @@ impl<R: io::Read, E: Endianness> BitReader<R, E> {
     ///
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{LittleEndian, BitReader};
+    /// use bitstream_io::{LittleEndian, BitReader, BitRead};
     /// let data = [0b10110111];
     /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
     /// assert_eq!(reader.read_signed::<i8>(4).unwrap(), 7);
@@ -254,7 +817,7 @@ impl<R: io::Read, E: Endianness> BitReader<R, E> {
     ///
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{BigEndian, BitReader};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
     /// let data = [0;10];
     /// let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
     /// assert!(r.read_signed::<i8>(9).is_err());   // can't read 9 bits to i8
@@ -262,284 +825,5586 @@ impl<R: io::Read, E: Endianness> BitReader<R, E> {
     /// assert!(r.read_signed::<i32>(33).is_err()); // can't read 33 bits to i32
     /// assert!(r.read_signed::<i64>(65).is_err()); // can't read 65 bits to i64
     /// ```
-    #[inline]
-    pub fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
+    fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
     where
-        S: SignedNumeric,
-    {
-        E::read_signed(self, bits)
-    }
+        S: SignedNumeric;
 
-    /// Skips the given number of bits in the stream.
-    /// Since this method does not need an accumulator,
-    /// it may be slightly faster than reading to an empty variable.
-    /// In addition, since there is no accumulator,
-    /// there is no upper limit on the number of bits
-    /// which may be skipped.
-    /// These bits are still read from the stream, however,
-    /// and are never skipped via a `seek` method.
+    /// Reads a signed value from the stream with
+    /// the given number of bits, using the given sign representation.
+    /// The value's most significant bit is its sign bit.
+    /// A sign-magnitude negative zero is returned as 0,
+    /// unless the format is `SignMagnitudeStrict`.
     ///
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if
+    /// a negative zero is read in the `SignMagnitudeStrict` format,
+    /// in which case its bits are still consumed.
+    /// Also returns an error if more than 32 bits are requested.
     ///
     /// # Examples
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{BigEndian, BitReader};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead, SignedFormat};
     /// let data = [0b10110111];
     /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
-    /// assert!(reader.skip(3).is_ok());
-    /// assert_eq!(reader.read::<u8>(5).unwrap(), 0b10111);
+    /// assert_eq!(reader.read_signed_with(4, SignedFormat::SignMagnitude).unwrap(), -3);
+    /// assert_eq!(reader.read_signed_with(4, SignedFormat::TwosComplement).unwrap(), 7);
     /// ```
     ///
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{LittleEndian, BitReader};
+    /// use bitstream_io::{LittleEndian, BitReader, BitRead, SignedFormat};
     /// let data = [0b10110111];
     /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
-    /// assert!(reader.skip(3).is_ok());
-    /// assert_eq!(reader.read::<u8>(5).unwrap(), 0b10110);
+    /// assert_eq!(reader.read_signed_with(4, SignedFormat::SignMagnitude).unwrap(), 7);
+    /// assert_eq!(reader.read_signed_with(4, SignedFormat::SignMagnitude).unwrap(), -3);
     /// ```
-    pub fn skip(&mut self, mut bits: u32) -> io::Result<()> {
-        use std::cmp::min;
-
-        let to_drop = min(self.bitqueue.len(), bits);
-        if to_drop != 0 {
-            self.bitqueue.drop(to_drop);
-            bits -= to_drop;
+    ///
+    /// ```
+    /// use std::io::{Read, Cursor, ErrorKind};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead, SignedFormat};
+    /// let data = [0b1000_1000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_signed_with(4, SignedFormat::SignMagnitude).unwrap(), 0);
+    /// let err = reader.read_signed_with(4, SignedFormat::SignMagnitudeStrict).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::InvalidData);
+    /// ```
+    fn read_signed_with(&mut self, bits: u32, format: SignedFormat) -> io::Result<i32> {
+        match format {
+            SignedFormat::TwosComplement => self.read_signed(bits),
+            SignedFormat::SignMagnitude | SignedFormat::SignMagnitudeStrict if bits == 0 => Ok(0),
+            SignedFormat::SignMagnitude | SignedFormat::SignMagnitudeStrict if bits <= 32 => {
+                let raw = u64::from(self.read::<u32>(bits)?);
+                let sign = raw >> (bits - 1);
+                let magnitude = (raw & ((1 << (bits - 1)) - 1)) as i32;
+                match (sign, magnitude) {
+                    (0, magnitude) => Ok(magnitude),
+                    (_, 0) if format == SignedFormat::SignMagnitudeStrict => Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "negative zero in sign-magnitude value",
+                    )),
+                    (_, magnitude) => Ok(-magnitude),
+                }
+            }
+            SignedFormat::SignMagnitude | SignedFormat::SignMagnitudeStrict => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits for type read",
+            )),
         }
-
-        skip_aligned(&mut self.reader, bits / 8)?;
-        skip_unaligned(&mut self.reader, bits % 8, &mut self.bitqueue)
     }
 
-    /// Completely fills the given buffer with whole bytes.
-    /// If the stream is already byte-aligned, it will map
-    /// to a faster `read_exact` call.  Otherwise it will read
-    /// bytes individually in 8-bit increments.
+    /// Reads a twos-complement signed value from the stream with
+    /// the given number of bits and checks that it falls
+    /// within `min` to `max`, inclusive.
     ///
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if the value
+    /// is out of range, which carries a `ValueOutOfRange`
+    /// when the `std` feature is enabled.
+    /// Also returns an error if more than 32 bits are requested.
     ///
     /// # Example
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{BigEndian, BitReader};
-    /// let data = b"foobar";
-    /// let mut reader = BitReader::endian(Cursor::new(data), BigEndian);
-    /// assert!(reader.skip(24).is_ok());
-    /// let mut buf = [0;3];
-    /// assert!(reader.read_bytes(&mut buf).is_ok());
-    /// assert_eq!(&buf, b"bar");
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1111_0110, 0b0000_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_signed_checked(7, -32, 31).unwrap(), -5);
+    /// assert!(reader.read_signed_checked(7, -32, 31).is_ok());
     /// ```
-    pub fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
-        if self.byte_aligned() {
-            self.reader.read_exact(buf)
+    fn read_signed_checked(&mut self, bits: u32, min: i32, max: i32) -> io::Result<i32> {
+        let value = self.read_signed(bits)?;
+        if min <= value && value <= max {
+            Ok(value)
         } else {
-            for b in buf.iter_mut() {
-                *b = self.read(8)?;
-            }
-            Ok(())
+            Err(out_of_range(value, min, max))
         }
     }
 
-    /// Counts the number of 1 bits in the stream until the next
-    /// 0 bit and returns the amount read.
-    /// Because this field is variably-sized and may be large,
-    /// its output is always a `u32` type.
+    /// Reads a twos-complement signed value from the stream with
+    /// the given number of bits, which may be wider than the output type,
+    /// converting out-of-range values as given.
     ///
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if more than 128 bits are requested.
     ///
-    /// # Examples
+    /// # Example
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{BigEndian, BitReader};
-    /// let data = [0b01110111, 0b11111110];
+    /// use bitstream_io::{BigEndian, BitReader, BitRead, Overflow};
+    /// let data = [0b1000_0000, 0b0000_0111, 0b1111_1111];
     /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
-    /// assert_eq!(reader.read_unary0().unwrap(), 0);
-    /// assert_eq!(reader.read_unary0().unwrap(), 3);
-    /// assert_eq!(reader.read_unary0().unwrap(), 10);
+    /// assert_eq!(reader.read_signed_as::<i8>(12, Overflow::Saturating).unwrap(), -128);
+    /// assert_eq!(reader.read_signed_as::<i8>(12, Overflow::Wrapping).unwrap(), -1);
     /// ```
+    fn read_signed_as<S>(&mut self, bits: u32, overflow: Overflow) -> io::Result<S>
+    where
+        S: SignedNumeric,
+    {
+        let value = self.read_signed::<i128>(bits)?;
+        Ok(match overflow {
+            Overflow::Wrapping => S::wrapping_from_i128(value),
+            Overflow::Saturating => S::saturating_from_i128(value),
+        })
+    }
+
+    /// Reads a twos-complement signed value from the stream with
+    /// the given number of bits, for fields in which all 0 bits
+    /// mean the value is not present.
+    /// Returns `None` for all 0 bits, including a field of 0 bits,
+    /// and the value otherwise.
     ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if `bits` is larger than 32.
+    ///
+    /// # Example
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{LittleEndian, BitReader};
-    /// let data = [0b11101110, 0b01111111];
-    /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
-    /// assert_eq!(reader.read_unary0().unwrap(), 0);
-    /// assert_eq!(reader.read_unary0().unwrap(), 3);
-    /// assert_eq!(reader.read_unary0().unwrap(), 10);
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b0000_1111];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_signed_opt(4).unwrap(), None);
+    /// assert_eq!(reader.read_signed_opt(4).unwrap(), Some(-1));
     /// ```
-    pub fn read_unary0(&mut self) -> io::Result<u32> {
-        if self.bitqueue.is_empty() {
-            read_aligned_unary(&mut self.reader, 0b1111_1111, &mut self.bitqueue)
-                .map(|u| u + self.bitqueue.pop_1())
-        } else if self.bitqueue.all_1() {
-            let base = self.bitqueue.len();
-            self.bitqueue.clear();
-            read_aligned_unary(&mut self.reader, 0b1111_1111, &mut self.bitqueue)
-                .map(|u| base + u + self.bitqueue.pop_1())
-        } else {
-            Ok(self.bitqueue.pop_1())
-        }
+    #[inline]
+    fn read_signed_opt(&mut self, bits: u32) -> io::Result<Option<i32>> {
+        self.read_signed(bits)
+            .map(|value| if value == 0 { None } else { Some(value) })
     }
 
-    /// Counts the number of 0 bits in the stream until the next
-    /// 1 bit and returns the amount read.
-    /// Because this field is variably-sized and may be large,
-    /// its output is always a `u32` type.
+    /// Reads an unsigned length with the given number of bits,
+    /// followed by an unsigned value of that many bits,
+    /// and returns both as `(length, value)`.
+    /// A length of 0 yields a value of 0.
     ///
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if
+    /// the length read is larger than 32.
+    /// Also returns an error if `len_bits` is larger than 32.
     ///
-    /// # Examples
+    /// # Example
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{BigEndian, BitReader};
-    /// let data = [0b10001000, 0b00000001];
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b0101_1011, 0b0000_0000];
     /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
-    /// assert_eq!(reader.read_unary1().unwrap(), 0);
-    /// assert_eq!(reader.read_unary1().unwrap(), 3);
-    /// assert_eq!(reader.read_unary1().unwrap(), 10);
+    /// assert_eq!(reader.read_length_prefixed(4).unwrap(), (5, 0b10110));
+    /// assert_eq!(reader.read_length_prefixed(4).unwrap(), (0, 0));
     /// ```
+    fn read_length_prefixed(&mut self, len_bits: u32) -> io::Result<(u32, u32)> {
+        match self.read::<u32>(len_bits)? {
+            0 => Ok((0, 0)),
+            length if length <= 32 => self.read(length).map(|value| (length, value)),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "excessive bits for prefixed value",
+            )),
+        }
+    }
+
+    /// Reads an unsigned tag with the given number of bits,
+    /// looks up the width it selects in `widths`,
+    /// and then reads an unsigned value of that many bits,
+    /// returning both as `(tag, value)`.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if
+    /// the tag read has no entry in `widths`.
+    /// Also returns an error if `tag_bits`
+    /// or the width selected is larger than 32.
     ///
+    /// # Example
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{LittleEndian, BitReader};
-    /// let data = [0b00010001, 0b10000000];
-    /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
-    /// assert_eq!(reader.read_unary1().unwrap(), 0);
-    /// assert_eq!(reader.read_unary1().unwrap(), 3);
-    /// assert_eq!(reader.read_unary1().unwrap(), 10);
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b01_101001, 0b00_00_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_tagged(2, &[4, 8, 12, 16]).unwrap(), (1, 0b1010_0100));
+    /// assert_eq!(reader.read_tagged(2, &[4, 8, 12, 16]).unwrap(), (0, 0));
     /// ```
-    pub fn read_unary1(&mut self) -> io::Result<u32> {
-        if self.bitqueue.is_empty() {
-            read_aligned_unary(&mut self.reader, 0b0000_0000, &mut self.bitqueue)
-                .map(|u| u + self.bitqueue.pop_0())
-        } else if self.bitqueue.all_0() {
-            let base = self.bitqueue.len();
-            self.bitqueue.clear();
-            read_aligned_unary(&mut self.reader, 0b0000_0000, &mut self.bitqueue)
-                .map(|u| base + u + self.bitqueue.pop_0())
-        } else {
-            Ok(self.bitqueue.pop_0())
+    fn read_tagged(&mut self, tag_bits: u32, widths: &[u32]) -> io::Result<(u32, u32)> {
+        let tag = self.read::<u32>(tag_bits)?;
+        match widths.get(tag as usize) {
+            Some(&width) => self.read(width).map(|value| (tag, value)),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unmapped tag for width",
+            )),
         }
     }
 
-    /// Returns true if the stream is aligned at a whole byte.
+    /// Reads three consecutive unsigned fields with the given widths,
+    /// such as the components of a packed 5-6-5 RGB pixel,
+    /// returning them in the order read.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream,
+    /// in which case any fields before the failing one are consumed.
+    /// Also returns an error if any width is larger than 32.
     ///
     /// # Example
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{BigEndian, BitReader};
-    /// let data = [0];
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b11111_000, 0b001_00001];
     /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
-    /// assert_eq!(reader.byte_aligned(), true);
-    /// assert!(reader.skip(1).is_ok());
-    /// assert_eq!(reader.byte_aligned(), false);
-    /// assert!(reader.skip(7).is_ok());
-    /// assert_eq!(reader.byte_aligned(), true);
+    /// assert_eq!(reader.read_tuple3(5, 6, 5).unwrap(), (31, 1, 1));
     /// ```
     #[inline]
-    pub fn byte_aligned(&self) -> bool {
-        self.bitqueue.is_empty()
+    fn read_tuple3(&mut self, b0: u32, b1: u32, b2: u32) -> io::Result<(u32, u32, u32)> {
+        Ok((self.read(b0)?, self.read(b1)?, self.read(b2)?))
     }
 
-    /// Throws away all unread bit values until the next whole byte.
-    /// Does nothing if the stream is already aligned.
+    /// Reads four consecutive unsigned fields with the given widths,
+    /// such as the components of a packed RGBA pixel,
+    /// returning them in the order read.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream,
+    /// in which case any fields before the failing one are consumed.
+    /// Also returns an error if any width is larger than 32.
     ///
     /// # Example
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{BigEndian, BitReader};
-    /// let data = [0x00, 0xFF];
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1010_1100, 0b0000_0000, 0b1111_1111, 0b00_00_0011];
     /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
-    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0);
-    /// reader.byte_align();
-    /// assert_eq!(reader.read::<u8>(8).unwrap(), 0xFF);
+    /// assert_eq!(
+    ///     reader.read_tuple4(10, 10, 10, 2).unwrap(),
+    ///     (0b1010_1100_00, 0b00_0000_1111, 0b1111_0000_00, 0b11)
+    /// );
     /// ```
     #[inline]
-    pub fn byte_align(&mut self) {
-        self.bitqueue.clear()
+    fn read_tuple4(
+        &mut self,
+        b0: u32,
+        b1: u32,
+        b2: u32,
+        b3: u32,
+    ) -> io::Result<(u32, u32, u32, u32)> {
+        Ok((
+            self.read(b0)?,
+            self.read(b1)?,
+            self.read(b2)?,
+            self.read(b3)?,
+        ))
     }
 
-    /// Given a compiled Huffman tree, reads bits from the stream
-    /// until the next symbol is encountered.
+    /// Reads each of the fields in the schema in turn,
+    /// returning their names and values in the same order.
     ///
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if an unsigned field's value
+    /// is too large for an `i64`.
+    /// Also returns an error if any field is larger than 64 bits.
     ///
     /// # Example
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{BigEndian, BitReader};
-    /// use bitstream_io::huffman::compile_read_tree;
-    /// let tree = compile_read_tree(
-    ///     vec![('a', vec![0]),
-    ///          ('b', vec![1, 0]),
-    ///          ('c', vec![1, 1, 0]),
-    ///          ('d', vec![1, 1, 1])]).unwrap();
-    /// let data = [0b10110111];
+    /// use bitstream_io::{BigEndian, BitReader, BitRead, FieldSpec};
+    /// let data = [0b0100_1111, 0b1000_0000];
     /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
-    /// assert_eq!(reader.read_huffman(&tree).unwrap(), 'b');
-    /// assert_eq!(reader.read_huffman(&tree).unwrap(), 'c');
-    /// assert_eq!(reader.read_huffman(&tree).unwrap(), 'd');
+    /// let schema = [FieldSpec::unsigned("version", 4), FieldSpec::signed("delta", 5)];
+    /// assert_eq!(
+    ///     reader.read_schema(&schema).unwrap(),
+    ///     vec![("version".to_string(), 4), ("delta".to_string(), -1)],
+    /// );
     /// ```
-    pub fn read_huffman<T>(&mut self, tree: &[ReadHuffmanTree<E, T>]) -> io::Result<T>
-    where
-        T: Clone,
-    {
-        let mut result: &ReadHuffmanTree<E, T> = &tree[self.bitqueue.to_state()];
-        loop {
-            match result {
-                ReadHuffmanTree::Done(ref value, ref queue_val, ref queue_bits, _) => {
-                    self.bitqueue.set(*queue_val, *queue_bits);
-                    return Ok(value.clone());
-                }
-                ReadHuffmanTree::Continue(ref tree) => {
-                    result = &tree[read_byte(&mut self.reader)? as usize];
-                }
-                ReadHuffmanTree::InvalidState => {
-                    panic!("invalid state");
-                }
-            }
+    #[cfg(feature = "alloc")]
+    fn read_schema(&mut self, schema: &[FieldSpec]) -> io::Result<Vec<(String, i64)>> {
+        use core::convert::TryFrom;
+
+        schema
+            .iter()
+            .map(|field| {
+                let value = if field.signed {
+                    self.read_signed::<i64>(field.bits)?
+                } else {
+                    i64::try_from(self.read::<u64>(field.bits)?).map_err(|_| {
+                        io::Error::new(io::ErrorKind::InvalidData, "excessive value for i64")
+                    })?
+                };
+                Ok((field.name.clone(), value))
+            })
+            .collect()
+    }
+
+    /// Reads an unsigned width with the given number of bits,
+    /// followed by a twos-complement signed value of that width.
+    /// A width of 0 yields a value of 0.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if
+    /// the width read is larger than 64.
+    /// Also returns an error if `width_bits` is larger than 32.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b00011_101, 0b00000_000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_signed_prefixed(5).unwrap(), -3);
+    /// assert_eq!(reader.read_signed_prefixed(5).unwrap(), 0);
+    /// ```
+    fn read_signed_prefixed(&mut self, width_bits: u32) -> io::Result<i64> {
+        match self.read::<u32>(width_bits)? {
+            0 => Ok(0),
+            width if width <= 64 => self.read_signed(width),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "excessive bits for prefixed value",
+            )),
         }
     }
 
-    /// Consumes reader and returns any un-read partial byte
-    /// as a `(bits, value)` tuple.
+    /// Reads a 32-bit IEEE 754 floating point value from the stream,
+    /// with its bits ordered by the stream's endianness.
     ///
-    /// # Examples
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{BigEndian, BitReader};
-    /// let data = [0b1010_0101, 0b0101_1010];
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0x3F, 0xC0, 0x00, 0x00];
     /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
-    /// assert_eq!(reader.read::<u16>(9).unwrap(), 0b1010_0101_0);
-    /// let (bits, value) = reader.into_unread();
-    /// assert_eq!(bits, 7);
-    /// assert_eq!(value, 0b101_1010);
+    /// assert_eq!(reader.read_f32().unwrap(), 1.5);
     /// ```
+    #[inline]
+    fn read_f32(&mut self) -> io::Result<f32> {
+        self.read(32).map(f32::from_bits)
+    }
+
+    /// Reads a 64-bit IEEE 754 floating point value from the stream,
+    /// with its bits ordered by the stream's endianness.
+    ///
+    /// # Errors
     ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
     /// ```
     /// use std::io::{Read, Cursor};
-    /// use bitstream_io::{BigEndian, BitReader};
-    /// let data = [0b1010_0101, 0b0101_1010];
+    /// use bitstream_io::{LittleEndian, BitReader, BitRead};
+    /// let data = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF8, 0xBF];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    /// assert_eq!(reader.read_f64().unwrap(), -1.5);
+    /// ```
+    #[inline]
+    fn read_f64(&mut self) -> io::Result<f64> {
+        self.read(64).map(f64::from_bits)
+    }
+
+    /// Reads an 80-bit IEEE 754 extended precision floating point value
+    /// from the stream, such as the sample rate in an AIFF header,
+    /// with its bits ordered by the stream's endianness.
+    /// The value is rounded to the nearest `f64`.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0x40, 0x0E, 0xAC, 0x44, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
     /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
-    /// assert_eq!(reader.read::<u16>(8).unwrap(), 0b1010_0101);
-    /// let (bits, value) = reader.into_unread();
-    /// assert_eq!(bits, 0);
-    /// assert_eq!(value, 0);
+    /// assert_eq!(reader.read_f80().unwrap(), 44100.0);
     /// ```
     #[inline]
-    pub fn into_unread(self) -> (u32, u8) {
-        (self.bitqueue.len(), self.bitqueue.value())
+    fn read_f80(&mut self) -> io::Result<f64> {
+        self.read(80).map(f80_to_f64)
+    }
+
+    /// Skips the given number of bits in the stream.
+    /// Since this method does not need an accumulator,
+    /// it may be slightly faster than reading to an empty variable.
+    /// In addition, since there is no accumulator,
+    /// there is no upper limit on the number of bits
+    /// which may be skipped.
+    /// These bits are still read from the stream, however,
+    /// and are never skipped via a `seek` method.
+    /// Like `read`, skipping 0 bits does nothing
+    /// and never touches the underlying stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b10110111];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert!(reader.skip(3).is_ok());
+    /// assert_eq!(reader.read::<u8>(5).unwrap(), 0b10111);
+    /// ```
+    ///
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{LittleEndian, BitReader, BitRead};
+    /// let data = [0b10110111];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    /// assert!(reader.skip(3).is_ok());
+    /// assert_eq!(reader.read::<u8>(5).unwrap(), 0b10110);
+    /// ```
+    fn skip(&mut self, bits: u32) -> io::Result<()>;
+
+    /// Completely fills the given buffer with whole bytes.
+    /// This maps to a single `read_exact` call on the underlying stream,
+    /// whose bytes are shifted into place if the stream
+    /// is not already byte-aligned.
+    /// An empty buffer never touches the stream,
+    /// whether aligned or not, and succeeds even at its end.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = b"foobar";
+    /// let mut reader = BitReader::endian(Cursor::new(data), BigEndian);
+    /// assert!(reader.skip(24).is_ok());
+    /// let mut buf = [0;3];
+    /// assert!(reader.read_bytes(&mut buf).is_ok());
+    /// assert_eq!(&buf, b"bar");
+    /// ```
+    fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()>;
+
+    /// Reads the given number of whole bytes into a newly
+    /// allocated buffer, using `read_bytes` to fill it.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// `BitReader` returns an error of kind `InvalidData`,
+    /// before allocating, if the count exceeds
+    /// the limit set by `with_max_alloc`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = b"foobar";
+    /// let mut reader = BitReader::endian(Cursor::new(data), BigEndian);
+    /// assert!(reader.skip(24).is_ok());
+    /// assert_eq!(reader.read_to_vec(3).unwrap(), b"bar");
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn read_to_vec(&mut self, count: usize) -> io::Result<Vec<u8>> {
+        let mut buf = alloc::vec![0; count];
+        self.read_bytes(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Completely fills the given buffer with whole bytes,
+    /// like `read_bytes`, but only if the stream is byte-aligned.
+    /// Strict parsers may use this to catch a misaligned field
+    /// which `read_bytes` would otherwise quietly accept.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidInput` if the stream
+    /// is not byte-aligned, in which case nothing is read.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor, ErrorKind};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = b"foobar";
+    /// let mut reader = BitReader::endian(Cursor::new(data), BigEndian);
+    /// let mut buf = [0; 3];
+    /// assert!(reader.read_bytes_aligned(&mut buf).is_ok());
+    /// assert_eq!(&buf, b"foo");
+    /// assert!(reader.skip(1).is_ok());
+    /// assert_eq!(reader.read_bytes_aligned(&mut buf).unwrap_err().kind(), ErrorKind::InvalidInput);
+    /// ```
+    fn read_bytes_aligned(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        if self.byte_aligned() {
+            self.read_bytes(buf)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "stream not byte-aligned",
+            ))
+        }
+    }
+
+    /// Reads the given number of whole bytes, like `read_bytes`,
+    /// and returns them as a single big-endian unsigned value,
+    /// for fields whose width is given in bytes.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if `num_bytes` is larger than 8.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{LittleEndian, BitReader, BitRead};
+    /// let data = [0x12, 0x34, 0x56, 0x78];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    /// assert_eq!(reader.read_bytes_as_u64(3).unwrap(), 0x12_3456);
+    /// assert!(reader.read_bytes_as_u64(9).is_err());
+    /// ```
+    fn read_bytes_as_u64(&mut self, num_bytes: u32) -> io::Result<u64> {
+        if num_bytes <= 8 {
+            let mut buf = [0; 8];
+            self.read_bytes(&mut buf[(8 - num_bytes as usize)..])?;
+            Ok(u64::from_be_bytes(buf))
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bytes for type read",
+            ))
+        }
+    }
+
+    /// Reads the given number of bits, which may be more
+    /// than fit in any primitive type, and appends them to `out`
+    /// packed high bit first into as few bytes as will hold them.
+    /// Bits are packed in the order they are read,
+    /// so on a big-endian stream the bytes appended
+    /// are the field's big-endian representation.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream,
+    /// in which case nothing is appended to `out`.
+    /// `BitReader` returns an error of kind `InvalidData`,
+    /// before allocating, if the bits' bytes exceed
+    /// the limit set by `with_max_alloc`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead, Justify};
+    /// let data = [0b1011_0001, 0b1110_1101];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// let mut left = Vec::new();
+    /// reader.read_bits_to_bytes(12, Justify::Left, &mut left).unwrap();
+    /// assert_eq!(left, [0b1011_0001, 0b1110_0000]);
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// let mut right = Vec::new();
+    /// reader.read_bits_to_bytes(12, Justify::Right, &mut right).unwrap();
+    /// assert_eq!(right, [0b0000_1011, 0b0001_1110]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn read_bits_to_bytes(
+        &mut self,
+        bits: u32,
+        justify: Justify,
+        out: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        // bytes are added as bits arrive,
+        // so a huge count can't allocate more than the stream holds
+        let len = out.len();
+        let start = match justify {
+            Justify::Left => 0,
+            Justify::Right => (8 - bits % 8) % 8,
+        };
+        for index in start..start + bits {
+            if index == start || index % 8 == 0 {
+                out.push(0);
+            }
+            match self.read_bit() {
+                Ok(true) => *out.last_mut().unwrap() |= 0x80 >> (index % 8),
+                Ok(false) => {}
+                Err(err) => {
+                    out.truncate(len);
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts the number of 1 bits in the stream until the next
+    /// 0 bit and returns the amount read.
+    /// Because this field is variably-sized and may be large,
+    /// its output is always a `u32` type.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b01110111, 0b11111110];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_unary0().unwrap(), 0);
+    /// assert_eq!(reader.read_unary0().unwrap(), 3);
+    /// assert_eq!(reader.read_unary0().unwrap(), 10);
+    /// ```
+    ///
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{LittleEndian, BitReader, BitRead};
+    /// let data = [0b11101110, 0b01111111];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    /// assert_eq!(reader.read_unary0().unwrap(), 0);
+    /// assert_eq!(reader.read_unary0().unwrap(), 3);
+    /// assert_eq!(reader.read_unary0().unwrap(), 10);
+    /// ```
+    fn read_unary0(&mut self) -> io::Result<u32>;
+
+    /// Counts the number of 0 bits in the stream until the next
+    /// 1 bit and returns the amount read.
+    /// Because this field is variably-sized and may be large,
+    /// its output is always a `u32` type.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b10001000, 0b00000001];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_unary1().unwrap(), 0);
+    /// assert_eq!(reader.read_unary1().unwrap(), 3);
+    /// assert_eq!(reader.read_unary1().unwrap(), 10);
+    /// ```
+    ///
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{LittleEndian, BitReader, BitRead};
+    /// let data = [0b00010001, 0b10000000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    /// assert_eq!(reader.read_unary1().unwrap(), 0);
+    /// assert_eq!(reader.read_unary1().unwrap(), 3);
+    /// assert_eq!(reader.read_unary1().unwrap(), 10);
+    /// ```
+    fn read_unary1(&mut self) -> io::Result<u32>;
+
+    /// Counts the number of bits in the stream until the next
+    /// `stop_bit`, which must be 0 or 1, and returns the amount read.
+    /// This is `read_unary0` for a stop bit of 0
+    /// and `read_unary1` for a stop bit of 1.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b01110001];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_unary(1).unwrap(), 1);
+    /// assert_eq!(reader.read_unary(0).unwrap(), 2);
+    /// assert_eq!(reader.read_unary(1).unwrap(), 2);
+    /// ```
+    #[inline]
+    fn read_unary(&mut self, stop_bit: u32) -> io::Result<u32> {
+        debug_assert!(stop_bit <= 1);
+        if stop_bit == 0 {
+            self.read_unary0()
+        } else {
+            self.read_unary1()
+        }
+    }
+
+    /// Reads an unsigned Exp-Golomb code from the stream,
+    /// such as H.264's `ue(v)`,
+    /// consisting of a count of leading 0 bits, a 1 bit,
+    /// and then that many bits of value.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if
+    /// the encoded value is too large for a `u32`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1_010_011_0, 0b0100_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_exp_golomb().unwrap(), 0);
+    /// assert_eq!(reader.read_exp_golomb().unwrap(), 1);
+    /// assert_eq!(reader.read_exp_golomb().unwrap(), 2);
+    /// assert_eq!(reader.read_exp_golomb().unwrap(), 3);
+    /// ```
+    fn read_exp_golomb(&mut self) -> io::Result<u32> {
+        let zeros = self.read_unary1()?;
+        if zeros <= 32 {
+            let value = ((1u64 << zeros) - 1) + self.read::<u64>(zeros)?;
+            if value <= u64::from(u32::MAX) {
+                return Ok(value as u32);
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "excessive bits for Exp-Golomb code",
+        ))
+    }
+
+    /// Reads a signed Exp-Golomb code from the stream,
+    /// such as H.264's `se(v)`,
+    /// in which the unsigned codes 0, 1, 2, 3, 4, ...
+    /// map to the values 0, 1, -1, 2, -2, ...
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if
+    /// the encoded value is too large for an `i32`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1_010_011_0, 0b0100_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_exp_golomb_signed().unwrap(), 0);
+    /// assert_eq!(reader.read_exp_golomb_signed().unwrap(), 1);
+    /// assert_eq!(reader.read_exp_golomb_signed().unwrap(), -1);
+    /// assert_eq!(reader.read_exp_golomb_signed().unwrap(), 2);
+    /// ```
+    fn read_exp_golomb_signed(&mut self) -> io::Result<i32> {
+        let k = i64::from(self.read_exp_golomb()?);
+        let value = if k % 2 == 1 { (k + 1) / 2 } else { -(k / 2) };
+        if value <= i64::from(i32::MAX) {
+            Ok(value as i32)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "excessive bits for Exp-Golomb code",
+            ))
+        }
+    }
+
+    /// Reads an Elias delta code from the stream,
+    /// consisting of the value's length in bits as an Elias gamma code
+    /// followed by the value's bits after its leading 1.
+    /// Values start at 1.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if
+    /// the encoded value is too large for a `u32`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1_0100_010, 0b1_01100_00, 0b100000_00];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_elias_delta().unwrap(), 1);
+    /// assert_eq!(reader.read_elias_delta().unwrap(), 2);
+    /// assert_eq!(reader.read_elias_delta().unwrap(), 3);
+    /// assert_eq!(reader.read_elias_delta().unwrap(), 4);
+    /// assert_eq!(reader.read_elias_delta().unwrap(), 8);
+    /// ```
+    fn read_elias_delta(&mut self) -> io::Result<u32> {
+        let zeros = self.read_unary1()?;
+        if zeros <= 5 {
+            let len = (1 << zeros) + self.read::<u32>(zeros)?;
+            if len <= 32 {
+                return Ok((1 << (len - 1)) + self.read::<u32>(len - 1)?);
+            }
+        }
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "excessive bits for Elias delta code",
+        ))
+    }
+
+    /// Reads a Rice code from the stream with the given parameter,
+    /// such as those in FLAC residuals,
+    /// consisting of a quotient encoded as by `write_unary1`
+    /// followed by a `k`-bit remainder.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if
+    /// the encoded value is too large for a `u32`.
+    /// Also returns an error if `k` is larger than 32.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1_11_001_00, 0b1_00_00000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_rice(2).unwrap(), 3);
+    /// assert_eq!(reader.read_rice(2).unwrap(), 8);
+    /// assert_eq!(reader.read_rice(2).unwrap(), 0);
+    /// ```
+    fn read_rice(&mut self, k: u32) -> io::Result<u32> {
+        let quotient = self.read_unary1()?;
+        let remainder = self.read::<u32>(k)?;
+        let value = (u64::from(quotient) << k) | u64::from(remainder);
+        if value <= u64::from(u32::MAX) {
+            Ok(value as u32)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "excessive bits for Rice code",
+            ))
+        }
+    }
+
+    /// Reads a signed Rice code from the stream with the given parameter,
+    /// in which the unsigned values 0, 1, 2, 3, 4, ...
+    /// map to the values 0, -1, 1, -2, 2, ... as in FLAC.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if
+    /// the encoded value is too large for an `i32`.
+    /// Also returns an error if `k` is larger than 32.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1_11_001_00, 0b1_00_00000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_rice_signed(2).unwrap(), -2);
+    /// assert_eq!(reader.read_rice_signed(2).unwrap(), 4);
+    /// assert_eq!(reader.read_rice_signed(2).unwrap(), 0);
+    /// ```
+    #[inline]
+    fn read_rice_signed(&mut self, k: u32) -> io::Result<i32> {
+        let value = self.read_rice(k)?;
+        Ok((value >> 1) as i32 ^ -((value & 1) as i32))
+    }
+
+    /// Reads an unsigned value with the given number of bits
+    /// and zig-zag decodes it, as in protobuf,
+    /// so that the values 0, 1, 2, 3, 4, ...
+    /// map to the values 0, -1, 1, -2, 2, ...
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if `bits` is larger than 32.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b000_001_01, 0b0_011_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_zigzag(3).unwrap(), 0);
+    /// assert_eq!(reader.read_zigzag(3).unwrap(), -1);
+    /// assert_eq!(reader.read_zigzag(3).unwrap(), 1);
+    /// assert_eq!(reader.read_zigzag(3).unwrap(), -2);
+    /// ```
+    #[inline]
+    fn read_zigzag(&mut self, bits: u32) -> io::Result<i32> {
+        let value = self.read::<u32>(bits)?;
+        Ok((value >> 1) as i32 ^ -((value & 1) as i32))
+    }
+
+    /// Reads an unsigned Gray-coded value with the given number of bits,
+    /// as used by some ADCs and rotary encoders,
+    /// and returns it converted to ordinary binary.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if `bits` is larger than 32.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b000_001_01, 0b1_010_110_0];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_gray(3).unwrap(), 0);
+    /// assert_eq!(reader.read_gray(3).unwrap(), 1);
+    /// assert_eq!(reader.read_gray(3).unwrap(), 2);
+    /// assert_eq!(reader.read_gray(3).unwrap(), 3);
+    /// assert_eq!(reader.read_gray(3).unwrap(), 4);
+    /// ```
+    fn read_gray(&mut self, bits: u32) -> io::Result<u32> {
+        let mut value: u32 = self.read(bits)?;
+        value ^= value >> 1;
+        value ^= value >> 2;
+        value ^= value >> 4;
+        value ^= value >> 8;
+        value ^= value >> 16;
+        Ok(value)
+    }
+
+    /// Reads an unsigned value with the given number of bits
+    /// which is stored with those bits in reverse order,
+    /// as in some RFID and barcode encodings,
+    /// and returns it with its bits restored.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if `bits` is larger than 32.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1000_0110];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_reversed(4).unwrap(), 0b0001);
+    /// assert_eq!(reader.read_reversed(4).unwrap(), 0b0110);
+    /// ```
+    #[inline]
+    fn read_reversed(&mut self, bits: u32) -> io::Result<u32> {
+        self.read(bits).map(|value| reverse_within(bits, value))
+    }
+
+    /// Reads a signed fixed-point number in Q format,
+    /// such as Q1.15, with the given number of integer bits
+    /// (including the sign bit) and fractional bits,
+    /// and returns it scaled to an `f64`.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the total number of bits is larger than 64.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0x40, 0x00, 0x80, 0x00, 0xE0];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_fixed(1, 15).unwrap(), 0.5);
+    /// assert_eq!(reader.read_fixed(1, 15).unwrap(), -1.0);
+    /// assert_eq!(reader.read_fixed(2, 2).unwrap(), -0.5);
+    /// ```
+    fn read_fixed(&mut self, int_bits: u32, frac_bits: u32) -> io::Result<f64> {
+        let bits = fixed_bits(int_bits, frac_bits)?;
+        let value: i64 = self.read_signed(bits)?;
+        Ok(value as f64 * fixed_scale(frac_bits))
+    }
+
+    /// Counts the number of bits in the stream until the next
+    /// `stop_bit` (which must be 0 or 1) and returns the amount read,
+    /// like `read_unary0` and `read_unary1`.
+    /// Unlike those, at most `max` bits may precede the stop bit,
+    /// which protects against malformed streams that would
+    /// otherwise be read until their end.
+    ///
+    /// Note that `max` is inclusive, so a count of exactly `max`
+    /// succeeds, whereas `read_unary0_max` fails
+    /// once its count reaches its `max`.
+    /// `read_unary_limited(0, max)` returns the same as
+    /// `read_unary0_max(max + 1)`.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if more than `max`
+    /// bits are read without encountering the stop bit,
+    /// in which case `max + 1` bits have been consumed.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b01110111, 0b11111110];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_unary_limited(0, 3).unwrap(), 0);
+    /// assert_eq!(reader.read_unary_limited(0, 3).unwrap(), 3);
+    /// assert!(reader.read_unary_limited(0, 3).is_err());
+    /// ```
+    fn read_unary_limited(&mut self, stop_bit: u32, max: u32) -> io::Result<u32> {
+        debug_assert!(stop_bit <= 1);
+        let stop_bit = stop_bit == 1;
+        let mut count = 0;
+        while self.read_bit()? != stop_bit {
+            if count == max {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "excessive bits for unary value",
+                ));
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Counts the number of 1 bits in the stream until the next 0
+    /// and returns the amount read, like `read_unary0`,
+    /// but fails once the count reaches `max`.
+    /// On success, exactly the run of 1 bits and its stop bit
+    /// are consumed.
+    ///
+    /// Note that `max` is exclusive, so at most `max - 1` 1 bits
+    /// may precede the stop bit, whereas `read_unary_limited`
+    /// allows a count of exactly its `max`.
+    /// `read_unary0_max(max)` returns the same as
+    /// `read_unary_limited(0, max - 1)` for a nonzero `max`.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if `max`
+    /// 1 bits are read without encountering the stop bit,
+    /// in which case `max` bits have been consumed.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b01101111];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_unary0_max(3).unwrap(), 0);
+    /// assert_eq!(reader.read_unary0_max(3).unwrap(), 2);
+    /// assert!(reader.read_unary0_max(3).is_err());
+    /// ```
+    fn read_unary0_max(&mut self, max: u32) -> io::Result<u32> {
+        let mut count = 0;
+        loop {
+            if count == max {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "excessive bits for unary value",
+                ));
+            } else if !self.read_bit()? {
+                return Ok(count);
+            }
+            count += 1;
+        }
+    }
+
+    /// Reads an unsigned value from the stream with
+    /// the given number of bits and returns the item
+    /// paired with that value in the given table.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if
+    /// the value read is not in the table.
+    /// Also returns an error if `bits` is larger than 32.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let table = [(0b00, 'a'), (0b01, 'b'), (0b11, 'd')];
+    /// let data = [0b01_11_00_10];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_enum(2, &table).unwrap(), 'b');
+    /// assert_eq!(reader.read_enum(2, &table).unwrap(), 'd');
+    /// assert_eq!(reader.read_enum(2, &table).unwrap(), 'a');
+    /// assert!(reader.read_enum(2, &table).is_err());
+    /// ```
+    fn read_enum<T>(&mut self, bits: u32, table: &[(u32, T)]) -> io::Result<T>
+    where
+        T: Clone,
+    {
+        let code = self.read::<u32>(bits)?;
+        table
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(_, item)| item.clone())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "unmapped code for enum"))
+    }
+
+    /// Reads an unsigned value from the stream with
+    /// the given number of bits and splits it into mixed-radix digits,
+    /// most significant first, such as `a * 6 + b`
+    /// for the radices `[5, 6]`.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if the value
+    /// is not less than the product of the radices.
+    /// Also returns an error if `bits` is larger than 64
+    /// or if any radix is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b10111_111];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_mixed_radix(5, &[5, 6]).unwrap(), [3, 5]);
+    /// assert!(reader.read_mixed_radix(3, &[2, 3]).is_err());
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn read_mixed_radix(&mut self, bits: u32, radices: &[u32]) -> io::Result<Vec<u32>> {
+        if radices.contains(&0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "radix must be nonzero",
+            ));
+        }
+        let mut value = self.read::<u64>(bits)?;
+        let limit = radices.iter().try_fold(1u64, |product, &radix| {
+            product.checked_mul(u64::from(radix))
+        });
+        if limit.map_or(false, |limit| value >= limit) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "excessive value for radices",
+            ));
+        }
+        let mut digits = alloc::vec![0; radices.len()];
+        for (digit, &radix) in digits.iter_mut().zip(radices).rev() {
+            *digit = (value % u64::from(radix)) as u32;
+            value /= u64::from(radix);
+        }
+        Ok(digits)
+    }
+
+    /// Reads an unsigned value from the stream with
+    /// the given number of bits and checks that it matches
+    /// the expected value, such as a magic number or sync code.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if
+    /// the value read does not match,
+    /// which carries an `UnexpectedValue` when the `std` feature is enabled.
+    /// Also returns an error if `bits` is larger than 32.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0xFF, 0xF8, 0x00];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert!(reader.expect(14, 0b11111111111110).is_ok());
+    /// assert!(reader.expect(2, 0b11).is_err());
+    /// ```
+    fn expect(&mut self, bits: u32, value: u32) -> io::Result<()> {
+        let actual = self.read::<u32>(bits)?;
+        if actual == value {
+            Ok(())
+        } else {
+            Err(unexpected(value, actual))
+        }
+    }
+
+    /// Discards any partial byte, as by `byte_align`,
+    /// then reads as many bytes as `magic` holds
+    /// and checks that they match it,
+    /// such as the signature starting a RIFF, FLAC or PNG file.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if
+    /// the bytes read do not match,
+    /// which carries a `MagicMismatch` when the `std` feature is enabled.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = b"\x80fLaC";
+    /// let mut reader = BitReader::endian(Cursor::new(data), BigEndian);
+    /// assert!(reader.read_bit().unwrap());
+    /// assert!(reader.align_and_expect(b"fLaC").is_ok());
+    /// ```
+    fn align_and_expect(&mut self, magic: &[u8]) -> io::Result<()> {
+        self.byte_align();
+        expect_magic(self, magic)
+    }
+
+    /// Returns true if the stream is aligned at a whole byte.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.byte_aligned(), true);
+    /// assert!(reader.skip(1).is_ok());
+    /// assert_eq!(reader.byte_aligned(), false);
+    /// assert!(reader.skip(7).is_ok());
+    /// assert_eq!(reader.byte_aligned(), true);
+    /// ```
+    fn byte_aligned(&self) -> bool;
+
+    /// Throws away all unread bit values until the next whole byte.
+    /// Does nothing if the stream is already aligned.
+    ///
+    /// Only bits already buffered from a partially-read byte
+    /// are discarded, so the underlying stream is never read
+    /// and aligning is always safe, even at the end of the stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0x00, 0xFF];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0);
+    /// reader.byte_align();
+    /// assert_eq!(reader.read::<u8>(8).unwrap(), 0xFF);
+    /// ```
+    fn byte_align(&mut self);
+
+    /// Wraps this reader so that no more than the given number of bits
+    /// may be read through it, analogous to `std::io::Read::take`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b10110111, 0b01100000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian).take(10);
+    /// assert_eq!(reader.read::<u8>(8).unwrap(), 0b10110111);
+    /// assert!(reader.read::<u8>(3).is_err());
+    /// assert_eq!(reader.read::<u8>(2).unwrap(), 0b01);
+    /// assert_eq!(reader.bits_remaining(), 0);
+    /// ```
+    #[inline]
+    fn take(self, bits: u64) -> BitReaderTake<Self>
+    where
+        Self: Sized,
+    {
+        BitReaderTake {
+            inner: self,
+            remaining: bits,
+        }
+    }
+
+    /// Returns an iterator over the remaining bits of the stream,
+    /// which calls `read_bit` until the stream ends.
+    /// This is convenient for debugging but slower than
+    /// reading values of several bits at once.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1011_0010];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// reader.skip(2).unwrap();
+    /// let bits = reader.bits().collect::<Result<Vec<bool>, _>>().unwrap();
+    /// assert_eq!(bits, [true, true, false, false, true, false]);
+    /// ```
+    #[inline]
+    fn bits(&mut self) -> Bits<'_, Self>
+    where
+        Self: Sized,
+    {
+        Bits { reader: self }
+    }
+
+    /// Reads the length of a single run in a bilevel run-length code,
+    /// stored as an unsigned value of the given number of bits.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if `length_bits` is larger than 32.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b0011_1010];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_run_length(4).unwrap(), 3);
+    /// assert_eq!(reader.read_run_length(4).unwrap(), 10);
+    /// ```
+    #[inline]
+    fn read_run_length(&mut self, length_bits: u32) -> io::Result<u32> {
+        self.read(length_bits)
+    }
+}
+
+/// An iterator over the bits of a stream.
+///
+/// Returned by `BitRead::bits`.
+/// Yields each bit until the stream ends,
+/// or any other I/O error from the underlying stream.
+pub struct Bits<'a, B> {
+    reader: &'a mut B,
+}
+
+impl<'a, B: BitRead> Iterator for Bits<'a, B> {
+    type Item = io::Result<bool>;
+
+    fn next(&mut self) -> Option<io::Result<bool>> {
+        match self.reader.read_bit() {
+            Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => None,
+            result => Some(result),
+        }
+    }
+}
+
+/// An iterator over the alternating runs of a bilevel run-length code.
+///
+/// Returned by `BitReader::run_lengths`.
+/// Yields each run's color and length until the stream ends
+/// before a run's length, taking any bits left over in its final byte
+/// as padding so long as there are fewer than `length_bits` of them.
+/// A stream which ends partway through a run's length
+/// yields an error of kind `UnexpectedEof` instead,
+/// as does any other I/O error from the underlying stream.
+pub struct RunLengthDecoder<'a, R: io::Read, E: Endianness> {
+    reader: &'a mut BitReader<R, E>,
+    length_bits: u32,
+    color: bool,
+}
+
+impl<'a, R: io::Read, E: Endianness> RunLengthDecoder<'a, R, E> {
+    /// The color of the next run to be read
+    #[inline]
+    pub fn color(&self) -> bool {
+        self.color
+    }
+}
+
+impl<'a, R: io::Read, E: Endianness> Iterator for RunLengthDecoder<'a, R, E> {
+    type Item = io::Result<(bool, u32)>;
+
+    fn next(&mut self) -> Option<io::Result<(bool, u32)>> {
+        match self.reader.read_unless_end(self.length_bits) {
+            Ok(Some(length)) => {
+                let color = self.color;
+                self.color = !color;
+                Some(Ok((color, length)))
+            }
+            Ok(None) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// Forwards to the borrowed reader, so that helpers
+/// taking `impl BitRead` by value may be passed `&mut reader`.
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Cursor};
+/// use bitstream_io::{BigEndian, BitReader, BitRead};
+/// fn read_header(mut r: impl BitRead) -> u8 {
+///     r.read(4).unwrap()
+/// }
+/// let data = [0b1011_0110];
+/// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+/// assert_eq!(read_header(&mut reader), 0b1011);
+/// assert_eq!(reader.read::<u8>(4).unwrap(), 0b0110);
+/// ```
+impl<R: BitRead + ?Sized> BitRead for &mut R {
+    #[inline]
+    fn read_bit(&mut self) -> io::Result<bool> {
+        (**self).read_bit()
+    }
+
+    #[inline]
+    fn read<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        (**self).read(bits)
+    }
+
+    #[inline]
+    fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
+    where
+        S: SignedNumeric,
+    {
+        (**self).read_signed(bits)
+    }
+
+    #[inline]
+    fn skip(&mut self, bits: u32) -> io::Result<()> {
+        (**self).skip(bits)
+    }
+
+    #[inline]
+    fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        (**self).read_bytes(buf)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn read_to_vec(&mut self, count: usize) -> io::Result<Vec<u8>> {
+        (**self).read_to_vec(count)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn read_bits_to_bytes(
+        &mut self,
+        bits: u32,
+        justify: Justify,
+        out: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        (**self).read_bits_to_bytes(bits, justify, out)
+    }
+
+    #[inline]
+    fn read_unary0(&mut self) -> io::Result<u32> {
+        (**self).read_unary0()
+    }
+
+    #[inline]
+    fn read_unary1(&mut self) -> io::Result<u32> {
+        (**self).read_unary1()
+    }
+
+    #[inline]
+    fn byte_aligned(&self) -> bool {
+        (**self).byte_aligned()
+    }
+
+    #[inline]
+    fn byte_align(&mut self) {
+        (**self).byte_align()
+    }
+}
+
+/// A trait for reading whole big-endian values
+/// from a bitstream which has been byte-aligned.
+///
+/// The byte order of these values is independent
+/// of the stream's bit order, and the `_le` methods
+/// read little-endian values from either kind of stream.
+///
+/// Each method fails rather than implicitly aligning the stream,
+/// so that misplaced byte reads are caught instead of silently
+/// discarding bits.
+pub trait ByteRead: BitRead {
+    /// Reads an unsigned 8-bit big-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_u8(&mut self) -> io::Result<u8> {
+        read_aligned_bytes::<Self, 1>(self).map(u8::from_be_bytes)
+    }
+
+    /// Reads an unsigned 16-bit big-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{LittleEndian, BitReader, BitRead, ByteRead};
+    /// let data = [0x12, 0x34, 0x56];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    /// assert_eq!(reader.read_u16().unwrap(), 0x1234);
+    /// assert!(reader.skip(1).is_ok());
+    /// assert!(reader.read_u8().is_err());
+    /// ```
+    fn read_u16(&mut self) -> io::Result<u16> {
+        read_aligned_bytes::<Self, 2>(self).map(u16::from_be_bytes)
+    }
+
+    /// Reads an unsigned 32-bit big-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_u32(&mut self) -> io::Result<u32> {
+        read_aligned_bytes::<Self, 4>(self).map(u32::from_be_bytes)
+    }
+
+    /// Reads an unsigned 64-bit big-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_u64(&mut self) -> io::Result<u64> {
+        read_aligned_bytes::<Self, 8>(self).map(u64::from_be_bytes)
+    }
+
+    /// Reads a signed 8-bit big-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_i8(&mut self) -> io::Result<i8> {
+        read_aligned_bytes::<Self, 1>(self).map(i8::from_be_bytes)
+    }
+
+    /// Reads a signed 16-bit big-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_i16(&mut self) -> io::Result<i16> {
+        read_aligned_bytes::<Self, 2>(self).map(i16::from_be_bytes)
+    }
+
+    /// Reads a signed 32-bit big-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_i32(&mut self) -> io::Result<i32> {
+        read_aligned_bytes::<Self, 4>(self).map(i32::from_be_bytes)
+    }
+
+    /// Reads a signed 64-bit big-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_i64(&mut self) -> io::Result<i64> {
+        read_aligned_bytes::<Self, 8>(self).map(i64::from_be_bytes)
+    }
+
+    /// Reads an unsigned 16-bit little-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, ByteRead};
+    /// let data = [0x12, 0x34, 0x12, 0x34];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_u16_le().unwrap(), 0x3412);
+    /// assert_eq!(reader.read_u16_be().unwrap(), 0x1234);
+    /// ```
+    fn read_u16_le(&mut self) -> io::Result<u16> {
+        read_aligned_bytes::<Self, 2>(self).map(u16::from_le_bytes)
+    }
+
+    /// Reads an unsigned 32-bit little-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_u32_le(&mut self) -> io::Result<u32> {
+        read_aligned_bytes::<Self, 4>(self).map(u32::from_le_bytes)
+    }
+
+    /// Reads an unsigned 64-bit little-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_u64_le(&mut self) -> io::Result<u64> {
+        read_aligned_bytes::<Self, 8>(self).map(u64::from_le_bytes)
+    }
+
+    /// Reads a signed 16-bit little-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_i16_le(&mut self) -> io::Result<i16> {
+        read_aligned_bytes::<Self, 2>(self).map(i16::from_le_bytes)
+    }
+
+    /// Reads a signed 32-bit little-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_i32_le(&mut self) -> io::Result<i32> {
+        read_aligned_bytes::<Self, 4>(self).map(i32::from_le_bytes)
+    }
+
+    /// Reads a signed 64-bit little-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_i64_le(&mut self) -> io::Result<i64> {
+        read_aligned_bytes::<Self, 8>(self).map(i64::from_le_bytes)
+    }
+
+    /// Reads an unsigned 16-bit big-endian value from a byte-aligned stream,
+    /// the same as `read_u16`.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, ByteRead};
+    /// let data = [0x12, 0x34, 0x12, 0x34];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_u16_be().unwrap(), 0x1234);
+    /// assert_eq!(reader.read_u16_le().unwrap(), 0x3412);
+    /// ```
+    #[inline]
+    fn read_u16_be(&mut self) -> io::Result<u16> {
+        self.read_u16()
+    }
+
+    /// Reads an unsigned 32-bit big-endian value from a byte-aligned stream,
+    /// the same as `read_u32`.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    #[inline]
+    fn read_u32_be(&mut self) -> io::Result<u32> {
+        self.read_u32()
+    }
+
+    /// Reads an unsigned 64-bit big-endian value from a byte-aligned stream,
+    /// the same as `read_u64`.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    #[inline]
+    fn read_u64_be(&mut self) -> io::Result<u64> {
+        self.read_u64()
+    }
+
+    /// Reads a signed 16-bit big-endian value from a byte-aligned stream,
+    /// the same as `read_i16`.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    #[inline]
+    fn read_i16_be(&mut self) -> io::Result<i16> {
+        self.read_i16()
+    }
+
+    /// Reads a signed 32-bit big-endian value from a byte-aligned stream,
+    /// the same as `read_i32`.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    #[inline]
+    fn read_i32_be(&mut self) -> io::Result<i32> {
+        self.read_i32()
+    }
+
+    /// Reads a signed 64-bit big-endian value from a byte-aligned stream,
+    /// the same as `read_i64`.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    #[inline]
+    fn read_i64_be(&mut self) -> io::Result<i64> {
+        self.read_i64()
+    }
+
+    /// Reads an unsigned 24-bit big-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_u24(&mut self) -> io::Result<u32> {
+        read_aligned_bytes::<Self, 3>(self).map(|[b0, b1, b2]| u32::from_be_bytes([0, b0, b1, b2]))
+    }
+
+    /// Reads a signed 24-bit big-endian value from a byte-aligned stream,
+    /// sign-extended to 32 bits.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, ByteRead};
+    /// let data = [0x80, 0x00, 0x00, 0x7F, 0xFF, 0xFF];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_i24().unwrap(), -0x80_0000);
+    /// assert_eq!(reader.read_i24().unwrap(), 0x7F_FFFF);
+    /// ```
+    fn read_i24(&mut self) -> io::Result<i32> {
+        self.read_u24().map(|u| ((u << 8) as i32) >> 8)
+    }
+
+    /// Reads an unsigned 48-bit big-endian value from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_u48(&mut self) -> io::Result<u64> {
+        read_aligned_bytes::<Self, 6>(self)
+            .map(|[b0, b1, b2, b3, b4, b5]| u64::from_be_bytes([0, 0, b0, b1, b2, b3, b4, b5]))
+    }
+
+    /// Reads a signed 48-bit big-endian value from a byte-aligned stream,
+    /// sign-extended to 64 bits.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    fn read_i48(&mut self) -> io::Result<i64> {
+        self.read_u48().map(|u| ((u << 16) as i64) >> 16)
+    }
+
+    /// Reads an unsigned LEB128 variable-length value
+    /// from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    /// Returns an error of kind `InvalidData` if the encoded
+    /// value does not fit in a `u64`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, ByteRead};
+    /// let data = [0xE5, 0x8E, 0x26, 0x7F];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_uleb128().unwrap(), 624485);
+    /// assert_eq!(reader.read_uleb128().unwrap(), 127);
+    /// ```
+    fn read_uleb128(&mut self) -> io::Result<u64> {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_u8()?;
+            let payload = u64::from(byte & 0x7F);
+            if shift == 63 && payload > 1 {
+                return Err(excessive_leb128());
+            }
+            value |= payload << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            } else if shift == 63 {
+                return Err(excessive_leb128());
+            }
+            shift += 7;
+        }
+    }
+
+    /// Reads a signed LEB128 variable-length value
+    /// from a byte-aligned stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the stream is not byte-aligned.
+    /// Returns an error of kind `InvalidData` if the encoded
+    /// value does not fit in an `i64`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, ByteRead};
+    /// let data = [0xC0, 0xBB, 0x78, 0x3F];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_sleb128().unwrap(), -123456);
+    /// assert_eq!(reader.read_sleb128().unwrap(), 63);
+    /// ```
+    fn read_sleb128(&mut self) -> io::Result<i64> {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_u8()?;
+            let payload = i64::from(byte & 0x7F);
+            if shift == 63 && payload != 0 && payload != 0x7F {
+                return Err(excessive_leb128());
+            }
+            value |= payload << shift;
+            if byte & 0x80 == 0 {
+                if shift < 57 && byte & 0x40 != 0 {
+                    value |= -1 << (shift + 7);
+                }
+                return Ok(value);
+            } else if shift == 63 {
+                return Err(excessive_leb128());
+            }
+            shift += 7;
+        }
+    }
+
+    /// Reads the given number of bytes from a byte-aligned stream
+    /// as a UTF-8 string, such as a fixed-length text field.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if the bytes
+    /// are not valid UTF-8, in which case they are still consumed.
+    /// Also returns an error if the stream is not byte-aligned.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, ByteRead};
+    /// let data = "fLaCé".as_bytes();
+    /// let mut reader = BitReader::endian(Cursor::new(data), BigEndian);
+    /// assert_eq!(reader.read_utf8(6).unwrap(), "fLaCé");
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn read_utf8(&mut self, bytes: usize) -> io::Result<String> {
+        String::from_utf8(read_aligned_vec(self, bytes)?)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid UTF-8 string"))
+    }
+
+    /// Reads the given number of bytes from a byte-aligned stream
+    /// as an ASCII string, such as a RIFF chunk's four-character code.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidData` if any byte
+    /// is larger than 0x7F, in which case they are still consumed.
+    /// Also returns an error if the stream is not byte-aligned.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, ByteRead};
+    /// let data = b"RIFF\xFFAVE";
+    /// let mut reader = BitReader::endian(Cursor::new(data), BigEndian);
+    /// assert_eq!(reader.read_ascii(4).unwrap(), "RIFF");
+    /// assert!(reader.read_ascii(4).is_err());
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn read_ascii(&mut self, bytes: usize) -> io::Result<String> {
+        let buf = read_aligned_vec(self, bytes)?;
+        if buf.is_ascii() {
+            Ok(buf.into_iter().map(char::from).collect())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid ASCII string",
+            ))
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn read_aligned_vec<B>(reader: &mut B, bytes: usize) -> io::Result<Vec<u8>>
+where
+    B: BitRead + ?Sized,
+{
+    if reader.byte_aligned() {
+        reader.read_to_vec(bytes)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "stream not byte-aligned",
+        ))
+    }
+}
+
+#[inline]
+fn excessive_leb128() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "excessive bytes for LEB128 value",
+    )
+}
+
+fn read_aligned_bytes<B, const N: usize>(reader: &mut B) -> io::Result<[u8; N]>
+where
+    B: BitRead + ?Sized,
+{
+    if reader.byte_aligned() {
+        let mut buf = [0; N];
+        reader.read_bytes(&mut buf)?;
+        Ok(buf)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "stream not byte-aligned",
+        ))
+    }
+}
+
+/// For reading non-aligned bits from a stream of bytes in a given endianness.
+///
+/// This will read exactly as many whole bytes needed to return
+/// the requested number of bits.  It may cache up to a single partial byte
+/// but no more.
+///
+/// Since byte slices implement `Read` themselves,
+/// in-memory data can be wrapped directly without a `Cursor`.
+/// Streams where each read is costly, such as files,
+/// are best wrapped in a `std::io::BufReader` first
+/// so that bytes are pulled from memory rather than
+/// by one system call apiece.
+///
+/// # Example
+/// ```
+/// use bitstream_io::{BigEndian, BitReader, BitRead};
+/// let data: &[u8] = &[0b10110111, 0x01, 0x02];
+/// let mut reader = BitReader::endian(data, BigEndian);
+/// assert_eq!(reader.read::<u8>(3).unwrap(), 0b101);
+/// assert_eq!(reader.read::<u8>(5).unwrap(), 0b10111);
+/// assert_eq!(reader.into_reader(), &[0x01, 0x02]);
+/// ```
+pub struct BitReader<R: io::Read, E: Endianness> {
+    reader: R,
+    bitqueue: BitQueue<E, u8>,
+    bytes_read: u64,
+    origin: u64,
+    max_alloc: usize,
+    last_span: (u64, u64),
+}
+
+impl<R: io::Read, E: Endianness> BitReader<R, E> {
+    /// Wraps a BitReader around something that implements `Read`
+    pub fn new(reader: R) -> BitReader<R, E> {
+        BitReader {
+            reader,
+            bitqueue: BitQueue::new(),
+            bytes_read: 0,
+            origin: 0,
+            max_alloc: usize::MAX,
+            last_span: (0, 0),
+        }
+    }
+
+    /// Wraps a BitReader around something that implements `Read`
+    /// with the given endianness.
+    pub fn endian(reader: R, _endian: E) -> BitReader<R, E> {
+        BitReader {
+            reader,
+            bitqueue: BitQueue::new(),
+            bytes_read: 0,
+            origin: 0,
+            max_alloc: usize::MAX,
+            last_span: (0, 0),
+        }
+    }
+
+    /// Wraps a BitReader around something that implements `Read`
+    /// whose container declares its total length in bits up front,
+    /// so that no read may go past that length.
+    ///
+    /// This is the same as `take` on a new reader,
+    /// and the returned reader's `bits_remaining`
+    /// gives the bits left of the declared length.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor, ErrorKind};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1011_0001, 0b1110_1101];
+    /// let mut reader = BitReader::<_, BigEndian>::with_length(Cursor::new(&data), 12);
+    /// assert_eq!(reader.read::<u8>(8).unwrap(), 0b1011_0001);
+    /// assert_eq!(reader.bits_remaining(), 4);
+    /// assert_eq!(reader.read::<u8>(5).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b1110);
+    /// ```
+    #[inline]
+    pub fn with_length(reader: R, total_bits: u64) -> BitReaderTake<BitReader<R, E>> {
+        BitReader::new(reader).take(total_bits)
+    }
+
+    /// Wraps a BitReader around something that implements `Read`,
+    /// resuming from a state saved by `save_state`
+    /// so that `position`, `total_bytes_consumed` and
+    /// subsequent reads continue exactly as before.
+    ///
+    /// The underlying stream should be placed just past the
+    /// saved partial byte, which is `bytes_consumed` bytes past
+    /// wherever the original reader started.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1010_0101, 0b0101_1010];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u16>(9).unwrap(), 0b1010_0101_0);
+    /// let state = reader.save_state();
+    /// let mut reader = BitReader::<_, BigEndian>::from_saved_state(&data[2..], state);
+    /// assert_eq!(reader.position(), 9);
+    /// assert_eq!(reader.read::<u8>(7).unwrap(), 0b101_1010);
+    /// ```
+    pub fn from_saved_state(reader: R, state: ReaderState) -> BitReader<R, E> {
+        BitReader {
+            reader,
+            bitqueue: BitQueue::from_value(state.value, state.bits),
+            bytes_read: state.bytes_consumed,
+            origin: state.bytes_consumed * 8 - u64::from(state.bits) - state.position,
+            max_alloc: usize::MAX,
+            last_span: (0, 0),
+        }
+    }
+
+    /// Wraps a BitReader around something that implements `Read`,
+    /// restoring a partial byte previously returned by `into_state`
+    /// or `into_unread` so that reading resumes mid-byte.
+    /// The restored partial byte counts as one byte read
+    /// for the purposes of `position`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is 8 or more,
+    /// or if `value` does not fit in the given number of bits.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1010_0101, 0b0101_1010];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u8>(3).unwrap(), 0b101);
+    /// let (cursor, bits, value) = reader.into_state();
+    /// let mut reader = BitReader::<_, BigEndian>::from_state(cursor, bits, value);
+    /// assert_eq!(reader.read::<u16>(13).unwrap(), 0b0_0101_0101_1010);
+    /// ```
+    pub fn from_state(reader: R, bits: u32, value: u8) -> BitReader<R, E> {
+        assert!(bits < 8);
+        BitReader {
+            reader,
+            bitqueue: BitQueue::from_value(value, bits),
+            bytes_read: if bits > 0 { 1 } else { 0 },
+            origin: 0,
+            max_alloc: usize::MAX,
+            last_span: (0, 0),
+        }
+    }
+
+    /// Limits the number of bytes `read_to_vec`, `read_bits_to_bytes`
+    /// and `sub_reader` may allocate,
+    /// which guards against length fields in untrusted input
+    /// demanding huge buffers from a small stream.
+    /// By default, allocations are unlimited.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor, ErrorKind};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = b"foobar";
+    /// let mut reader = BitReader::endian(Cursor::new(data), BigEndian).with_max_alloc(4);
+    /// assert_eq!(reader.read_to_vec(3).unwrap(), b"foo");
+    /// assert_eq!(reader.read_to_vec(1 << 40).unwrap_err().kind(), ErrorKind::InvalidData);
+    /// assert_eq!(reader.position(), 24);
+    /// ```
+    #[inline]
+    pub fn with_max_alloc(mut self, bytes: usize) -> BitReader<R, E> {
+        self.max_alloc = bytes;
+        self
+    }
+
+    /// Checks that a buffer of the given number of bytes
+    /// is within the limit set by `with_max_alloc`
+    #[cfg(feature = "alloc")]
+    fn alloc_limit(&self, bytes: u64) -> io::Result<usize> {
+        use core::convert::TryFrom;
+
+        match usize::try_from(bytes) {
+            Ok(bytes) if bytes <= self.max_alloc => Ok(bytes),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "excessive bytes for allocation",
+            )),
+        }
+    }
+
+    /// Returns the total number of bits consumed from the stream
+    /// since the reader was constructed or `reset_position` was called.
+    /// Bits discarded by `byte_align` count as consumed,
+    /// as do any bits a failed `read`, `skip` or `read_bytes`
+    /// drained from the stream before the error.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0; 4];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.position(), 0);
+    /// reader.read::<u8>(3).unwrap();
+    /// assert_eq!(reader.position(), 3);
+    /// reader.skip(6).unwrap();
+    /// assert_eq!(reader.position(), 9);
+    /// reader.byte_align();
+    /// assert_eq!(reader.position(), 16);
+    /// ```
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.bytes_read * 8 - u64::from(self.bitqueue.len()) - self.origin
+    }
+
+    /// Restarts `position` from 0 at the current bit,
+    /// such as at the start of each frame of a stream,
+    /// without disturbing any buffered partial byte.
+    /// `total_bytes_consumed` is unaffected.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1011_0111, 0b0110_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// reader.read::<u8>(3).unwrap();
+    /// reader.reset_position();
+    /// assert_eq!(reader.position(), 0);
+    /// assert_eq!(reader.read::<u8>(5).unwrap(), 0b10111);
+    /// assert_eq!(reader.position(), 5);
+    /// ```
+    #[inline]
+    pub fn reset_position(&mut self) {
+        self.origin = self.bytes_read * 8 - u64::from(self.bitqueue.len());
+    }
+
+    /// Returns the range of bit positions, from start to end,
+    /// covered by the most recent successful `read`,
+    /// `read_signed` or `read_bytes` call.
+    /// Other reading and skipping methods leave it unchanged,
+    /// and it is `(0, 0)` before any such read.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0, 0, 0];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert!(reader.skip(6).is_ok());
+    /// assert_eq!(reader.read::<u16>(10).unwrap(), 0);
+    /// assert_eq!(reader.last_read_span(), (6, 16));
+    /// ```
+    #[inline]
+    pub fn last_read_span(&self) -> (u64, u64) {
+        self.last_span
+    }
+
+    /// Reads an unsigned value of up to 32 bits, as by `read`,
+    /// and also returns the exact bits consumed in stream order.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if `bits` is larger than 32.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader};
+    /// let data = [0b1011_0111];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// let (value, pattern) = reader.read_logged(6).unwrap();
+    /// assert_eq!(value, 0b101101);
+    /// assert_eq!(pattern.to_string(), "1011_01");
+    /// ```
+    pub fn read_logged(&mut self, bits: u32) -> io::Result<(u32, BitPattern)> {
+        let value = self.read(bits)?;
+        let mut queue = BitQueue::<E, u32>::from_value(value, bits);
+        let mut pattern = BitPattern { bits: 0, len: bits };
+        while !queue.is_empty() {
+            pattern.bits = (pattern.bits << 1) | queue.pop(1);
+        }
+        Ok((value, pattern))
+    }
+
+    /// Checks that exactly the expected number of bits
+    /// have been consumed, as by `position`,
+    /// to catch mistakes in field widths after parsing
+    /// a structure of known size.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidData` if the position differs,
+    /// which carries a `PositionMismatch` when the `std` feature is enabled.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0; 4];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// reader.read::<u8>(3).unwrap();
+    /// reader.read::<u16>(13).unwrap();
+    /// assert!(reader.assert_position(16).is_ok());
+    /// assert!(reader.assert_position(15).is_err());
+    /// ```
+    pub fn assert_position(&self, expected_bits: u64) -> io::Result<()> {
+        let actual = self.position();
+        if actual == expected_bits {
+            Ok(())
+        } else {
+            Err(position_mismatch(expected_bits, actual))
+        }
+    }
+
+    /// Returns the total number of whole bytes pulled
+    /// from the underlying stream since the reader was constructed,
+    /// including any partial byte still being read.
+    /// Therefore `total_bytes_consumed() * 8 >= position()`,
+    /// with the difference being `bits_to_byte_align()`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0; 4];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.total_bytes_consumed(), 0);
+    /// reader.read::<u8>(3).unwrap();
+    /// assert_eq!(reader.total_bytes_consumed(), 1);
+    /// reader.skip(14).unwrap();
+    /// assert_eq!(reader.total_bytes_consumed(), 3);
+    /// assert_eq!(reader.position(), 17);
+    /// ```
+    #[inline]
+    pub fn total_bytes_consumed(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// Returns the number of bits remaining until
+    /// the next whole byte, or 0 if the stream is byte-aligned.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0; 2];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.bits_to_byte_align(), 0);
+    /// reader.read::<u8>(3).unwrap();
+    /// assert_eq!(reader.bits_to_byte_align(), 5);
+    /// reader.read::<u8>(7).unwrap();
+    /// assert_eq!(reader.bits_to_byte_align(), 6);
+    /// ```
+    #[inline]
+    pub fn bits_to_byte_align(&self) -> u32 {
+        self.bitqueue.len()
+    }
+
+    /// Reads an unsigned value with the given number of bits
+    /// and returns it along with the number of bits left unread
+    /// in the last byte the value touched,
+    /// which is 0 if the read ended on a whole byte.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if `bits` is larger than 32.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1011_0001, 0b1100_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_padded(10).unwrap(), (0b1011_0001_11, 6));
+    /// assert_eq!(reader.read_padded(6).unwrap(), (0, 0));
+    /// ```
+    #[inline]
+    pub fn read_padded(&mut self, bits: u32) -> io::Result<(u32, u32)> {
+        let value = self.read(bits)?;
+        Ok((value, self.bits_to_byte_align()))
+    }
+
+    /// Reads an unsigned value from the stream with
+    /// the given number of bits, like `read`,
+    /// but returns `None` if the stream ends before
+    /// any of the value's bits, to tell the absence of
+    /// optional trailing fields apart from a truncated one.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream,
+    /// including an error of kind `UnexpectedEof`
+    /// if the stream ends partway through the value.
+    /// Also returns an error if the output type is too small
+    /// to hold the requested number of bits.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor, ErrorKind};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1011_0111];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.try_read::<u8>(4).unwrap(), Some(0b1011));
+    /// assert_eq!(reader.try_read::<u8>(8).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.try_read::<u8>(8).unwrap(), Some(0b1011_0111));
+    /// assert_eq!(reader.try_read::<u8>(8).unwrap(), None);
+    /// ```
+    pub fn try_read<U>(&mut self, bits: u32) -> io::Result<Option<U>>
+    where
+        U: Numeric,
+    {
+        if bits > U::bits_size() {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits for type read",
+            ))
+        } else if bits == 0 || !self.bitqueue.is_empty() {
+            self.read(bits).map(Some)
+        } else {
+            match read_byte(&mut self.reader) {
+                Ok(byte) => {
+                    self.bitqueue.set(byte, 8);
+                    self.bytes_read += 1;
+                    self.read(bits).map(Some)
+                }
+                Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+                Err(err) => Err(err),
+            }
+        }
+    }
+
+    /// Reads an unsigned value of up to 32 bits, as by `read`,
+    /// or returns `None` if the stream ends first,
+    /// taking any fewer bits left in a partial byte as padding.
+    fn read_unless_end(&mut self, bits: u32) -> io::Result<Option<u32>> {
+        let buffered = self.bitqueue.len();
+        if bits <= buffered || bits > 32 {
+            return self.read(bits).map(Some);
+        }
+
+        // fetch the next byte by hand to tell a clean end of stream
+        // from a truncated value
+        let mut byte = [0];
+        match io::Read::read_exact(&mut self.reader, &mut byte) {
+            Ok(()) => {}
+            Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+        let mut value =
+            BitQueue::<E, u32>::from_value(u32::from(self.bitqueue.pop_all()), buffered);
+        self.bitqueue.push(8, byte[0]);
+        self.bytes_read += 1;
+        let rest = bits - buffered;
+        value.push(rest, self.read(rest)?);
+        Ok(Some(value.value()))
+    }
+
+    /// Returns an iterator over the runs of a bilevel run-length code,
+    /// where runs of 0s and 1s alternate starting with `first_color`
+    /// and each is stored as a `length_bits`-bit length,
+    /// as by `read_run_length`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b0011_1010, 0b0000_0001];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// let runs = reader
+    ///     .run_lengths(4, false)
+    ///     .collect::<Result<Vec<(bool, u32)>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(runs, [(false, 3), (true, 10), (false, 0), (true, 1)]);
+    /// ```
+    #[inline]
+    pub fn run_lengths(
+        &mut self,
+        length_bits: u32,
+        first_color: bool,
+    ) -> RunLengthDecoder<'_, R, E> {
+        RunLengthDecoder {
+            reader: self,
+            length_bits,
+            color: first_color,
+        }
+    }
+
+    /// Reads a single whole byte directly from the underlying stream,
+    /// which must be byte-aligned.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidInput` if the stream
+    /// is not byte-aligned, in which case nothing is read.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0x12, 0x34];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_aligned_byte().unwrap(), 0x12);
+    /// assert_eq!(reader.read::<u8>(1).unwrap(), 0);
+    /// assert!(reader.read_aligned_byte().is_err());
+    /// ```
+    #[inline]
+    pub fn read_aligned_byte(&mut self) -> io::Result<u8> {
+        if self.bitqueue.is_empty() {
+            let byte = read_byte(&mut self.reader).map_err(|err| truncated(err, 8, 0))?;
+            self.bytes_read += 1;
+            Ok(byte)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "stream not byte-aligned",
+            ))
+        }
+    }
+
+    /// Borrows the rest of the stream as an ordinary byte reader,
+    /// for handing off to code which knows only `Read`,
+    /// while keeping the bitstream reader for later use.
+    /// Since at most a partial byte is ever buffered,
+    /// an aligned reader has nothing buffered and the bytes
+    /// come straight from the underlying stream.
+    /// Bytes read through it advance `position` as usual.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidInput` if the stream
+    /// is not byte-aligned, since the remaining bits of a partial byte
+    /// can't be handed off as bytes.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = b"\x03foobar";
+    /// let mut reader = BitReader::endian(Cursor::new(data), BigEndian);
+    /// let len = reader.read::<u8>(8).unwrap();
+    /// let mut text = String::new();
+    /// reader.as_byte_reader().unwrap().take(len.into()).read_to_string(&mut text).unwrap();
+    /// assert_eq!(text, "foo");
+    /// assert_eq!(reader.position(), 32);
+    /// assert_eq!(reader.read::<u8>(8).unwrap(), b'b');
+    /// ```
+    pub fn as_byte_reader(&mut self) -> io::Result<impl io::Read + '_> {
+        if self.bitqueue.is_empty() {
+            Ok(AlignedBytes {
+                reader: &mut self.reader,
+                bytes_read: &mut self.bytes_read,
+            })
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "stream not byte-aligned",
+            ))
+        }
+    }
+
+    /// Reads the given number of bits into an owned buffer
+    /// and returns a new reader limited to just those bits,
+    /// advancing this reader past them.
+    /// Since the sub-reader shares nothing with this one,
+    /// reading past its end errors without affecting this reader.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream,
+    /// in which case some of the bits may have been consumed.
+    /// Returns an error of kind `InvalidData`, before allocating
+    /// or consuming anything, if the bits' bytes exceed
+    /// the limit set by `with_max_alloc`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1011_0111, 0b0100_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// let mut block = reader.sub_reader(6).unwrap();
+    /// assert_eq!(block.read::<u8>(4).unwrap(), 0b1011);
+    /// assert!(block.read::<u8>(4).is_err());
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b1101);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn sub_reader(&mut self, bits: u64) -> io::Result<BitReaderTake<BitReader<OwnedBytes, E>>> {
+        self.alloc_limit(bits / 8 + u64::from(bits % 8 != 0))?;
+        let whole = (bits / 8) as usize;
+        let partial = (bits % 8) as u32;
+        let mut buf = alloc::vec![0; whole];
+        self.read_bytes(&mut buf)?;
+        if partial > 0 {
+            let mut last = BitQueue::<E, u8>::from_value(self.read(partial)?, partial);
+            last.push(8 - partial, 0);
+            buf.push(last.value());
+        }
+        Ok(BitReader::new(OwnedBytes { buf, pos: 0 }).take(bits))
+    }
+
+    /// Skips bits until the stream's `position` is a multiple
+    /// of the given number of bits, which needn't be a multiple of 8.
+    /// Does nothing if the stream is already aligned.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the boundary is 0 bits.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0x00, 0x00, 0x00, 0x00, 0xFF];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// reader.read::<u16>(10).unwrap();
+    /// reader.align(32).unwrap();
+    /// assert_eq!(reader.position(), 32);
+    /// assert_eq!(reader.read::<u8>(8).unwrap(), 0xFF);
+    /// ```
+    pub fn align(&mut self, boundary_bits: u32) -> io::Result<()> {
+        if boundary_bits == 0 {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "alignment boundary must be non-zero",
+            ))
+        } else {
+            let boundary = u64::from(boundary_bits);
+            let remainder = self.position() % boundary;
+            if remainder == 0 {
+                Ok(())
+            } else {
+                self.skip((boundary - remainder) as u32)
+            }
+        }
+    }
+
+    /// Throws away all unread bit values until the next whole byte,
+    /// like `byte_align`, but only if they are all 0.
+    /// Does nothing if the stream is already aligned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidData` if any of
+    /// the bits to be discarded are 1,
+    /// in which case the stream is left unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1010_0000, 0b1010_0001];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b1010);
+    /// assert!(reader.byte_align_checked().is_ok());
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b1010);
+    /// assert!(reader.byte_align_checked().is_err());
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b0001);
+    /// ```
+    pub fn byte_align_checked(&mut self) -> io::Result<()> {
+        if self.bitqueue.all_0() {
+            self.bitqueue.clear();
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "non-zero padding bits",
+            ))
+        }
+    }
+
+    /// Unwraps internal reader and disposes of BitReader.
+    /// Any unread partial bits are discarded,
+    /// so the stream should be byte-aligned beforehand
+    /// if the reader will be used further.
+    #[inline]
+    pub fn into_reader(self) -> R {
+        self.reader
+    }
+
+    /// Unwraps internal reader and disposes of BitReader
+    /// if the stream is aligned at a whole byte.
+    /// Otherwise, returns the BitReader unchanged
+    /// so that no unread partial bits are lost.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1010_0101, 0b0101_1010];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b1010);
+    /// let mut reader = reader.try_into_reader().unwrap_err();
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b0101);
+    /// let mut cursor = reader.try_into_reader().ok().unwrap();
+    /// let mut rest = Vec::new();
+    /// cursor.read_to_end(&mut rest).unwrap();
+    /// assert_eq!(rest, [0b0101_1010]);
+    /// ```
+    #[inline]
+    pub fn try_into_reader(self) -> Result<R, Self> {
+        if self.byte_aligned() {
+            Ok(self.reader)
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Switches to reading from a new underlying stream,
+    /// returning the old one.
+    /// Any unread bits of the current partial byte are kept
+    /// and read before any bits of the new stream,
+    /// so a value may straddle the two.
+    /// The reader's position carries over.
+    ///
+    /// For sources that are all available up front,
+    /// `std::io::Read::chain` serves the same purpose.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let first = [0b1011_0110];
+    /// let second = [0b0101_1010];
+    /// let mut reader = BitReader::endian(&first[..], BigEndian);
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b1011);
+    /// let (rest, mut reader) = reader.continue_with(&second[..]);
+    /// assert!(rest.is_empty());
+    /// assert_eq!(reader.read::<u8>(8).unwrap(), 0b0110_0101);
+    /// assert_eq!(reader.position(), 12);
+    /// ```
+    pub fn continue_with<S: io::Read>(self, reader: S) -> (R, BitReader<S, E>) {
+        (
+            self.reader,
+            BitReader {
+                reader,
+                bitqueue: self.bitqueue,
+                bytes_read: self.bytes_read,
+                origin: self.origin,
+                max_alloc: self.max_alloc,
+                last_span: self.last_span,
+            },
+        )
+    }
+
+    /// Converts to a reader of the given endianness
+    /// if the stream is aligned at a whole byte,
+    /// carrying over the underlying stream and position.
+    /// Otherwise, returns the BitReader unchanged,
+    /// since the order of any unread partial bits can't be
+    /// meaningfully reinterpreted.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, LittleEndian, BitReader, BitRead};
+    /// let data = [0b1011_0001, 0b0000_0001, 0b1000_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b1011);
+    /// let mut reader = reader.try_into_endian(LittleEndian).unwrap_err();
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b0001);
+    /// let mut reader = reader.try_into_endian(LittleEndian).ok().unwrap();
+    /// assert_eq!(reader.read::<u16>(16).unwrap(), 0x8001);
+    /// assert_eq!(reader.position(), 24);
+    /// ```
+    #[inline]
+    pub fn try_into_endian<F: Endianness>(self, _endian: F) -> Result<BitReader<R, F>, Self> {
+        if self.byte_aligned() {
+            Ok(BitReader {
+                reader: self.reader,
+                bitqueue: BitQueue::new(),
+                bytes_read: self.bytes_read,
+                origin: self.origin,
+                max_alloc: self.max_alloc,
+                last_span: self.last_span,
+            })
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Reads whole bytes until the underlying stream is exhausted,
+    /// appending them to the given buffer and returning
+    /// the number of bytes appended.
+    ///
+    /// If the stream is not byte-aligned, each appended byte
+    /// straddles two bytes of the underlying stream.
+    /// Any trailing partial byte at the end of the stream is
+    /// not appended and remains available to subsequent reads.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1011_0111, 0b0110_0001, 0b1010_0101];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b1011);
+    /// let mut rest = Vec::new();
+    /// assert_eq!(reader.read_to_end(&mut rest).unwrap(), 2);
+    /// assert_eq!(rest, [0b0111_0110, 0b0001_1010]);
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b0101);
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        let start = buf.len();
+        let mut chunk = [0; 64];
+        loop {
+            let read = match self.reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            self.bytes_read += read as u64;
+            realign_bytes(&mut chunk[0..read], &mut self.bitqueue);
+            buf.extend_from_slice(&chunk[0..read]);
+        }
+        Ok(buf.len() - start)
+    }
+
+    /// Reads as many whole bytes as the underlying stream
+    /// delivers in a single `read` call, up to the length of `buf`,
+    /// and returns how many were read, like `io::Read::read`.
+    /// The stream needn't be byte-aligned;
+    /// any partial byte stays buffered for later reads.
+    /// Returning 0 indicates the end of the stream
+    /// (unless `buf` is empty).
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream,
+    /// in which case nothing is consumed.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0x12, 0x34, 0x56];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0x1);
+    /// let mut buf = [0; 4];
+    /// assert_eq!(reader.read_bytes_partial(&mut buf).unwrap(), 2);
+    /// assert_eq!(&buf[0..2], [0x23, 0x45]);
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0x6);
+    /// ```
+    pub fn read_bytes_partial(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let start = self.position();
+        let read = self.reader.read(buf)?;
+        self.bytes_read += read as u64;
+        if read > 0 {
+            self.last_span = (start, start + read as u64 * 8);
+        }
+        realign_bytes(&mut buf[0..read], &mut self.bitqueue);
+        Ok(read)
+    }
+
+    /// Skips up to the given number of bits,
+    /// stopping early without error if the stream ends,
+    /// and returns the number of bits actually skipped.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream
+    /// other than reaching its end.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use bitstream_io::{BigEndian, BitReader};
+    /// let data = [0; 3];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.skip_at_most(5).unwrap(), 5);
+    /// assert_eq!(reader.skip_at_most(100).unwrap(), 19);
+    /// assert_eq!(reader.skip_at_most(100).unwrap(), 0);
+    /// ```
+    pub fn skip_at_most(&mut self, bits: u32) -> io::Result<u32> {
+        use core::cmp::min;
+
+        let mut skipped = min(self.bitqueue.len(), bits);
+        self.bitqueue.drop(skipped);
+        let mut remaining = bits - skipped;
+
+        let mut buf = [0; 64];
+        while remaining >= 8 {
+            let to_read = min(buf.len(), (remaining / 8) as usize);
+            match self.reader.read(&mut buf[0..to_read]) {
+                Ok(0) => return Ok(skipped),
+                Ok(read) => {
+                    self.bytes_read += read as u64;
+                    skipped += read as u32 * 8;
+                    remaining -= read as u32 * 8;
+                }
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        if remaining > 0 {
+            match read_byte(&mut self.reader) {
+                Ok(byte) => {
+                    self.bitqueue.set(byte, 8);
+                    self.bitqueue.drop(remaining);
+                    self.bytes_read += 1;
+                    skipped += remaining;
+                }
+                Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(skipped)
+    }
+
+    /// Discards any partial byte and consumes the rest
+    /// of the underlying stream, returning the number of bits skipped.
+    /// This is much faster than calling `skip` in a loop.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0u8; 100];
+    /// let mut reader = BitReader::endian(Cursor::new(&data[..]), BigEndian);
+    /// assert!(reader.skip(3).is_ok());
+    /// assert_eq!(reader.skip_to_end().unwrap(), 797);
+    /// assert_eq!(reader.position(), 800);
+    /// assert!(reader.read_bit().is_err());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn skip_to_end(&mut self) -> io::Result<u64> {
+        let queued = u64::from(self.bitqueue.len());
+        self.bitqueue.clear();
+        let skipped = io::copy(&mut self.reader, &mut io::sink())?;
+        self.bytes_read += skipped;
+        Ok(queued + skipped * 8)
+    }
+
+    /// Given a compiled Huffman tree, reads bits from the stream
+    /// until the next symbol is encountered.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader};
+    /// use bitstream_io::huffman::compile_read_tree;
+    /// let tree = compile_read_tree(
+    ///     vec![('a', vec![0]),
+    ///          ('b', vec![1, 0]),
+    ///          ('c', vec![1, 1, 0]),
+    ///          ('d', vec![1, 1, 1])]).unwrap();
+    /// let data = [0b10110111];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_huffman(&tree).unwrap(), 'b');
+    /// assert_eq!(reader.read_huffman(&tree).unwrap(), 'c');
+    /// assert_eq!(reader.read_huffman(&tree).unwrap(), 'd');
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn read_huffman<T>(&mut self, tree: &[ReadHuffmanTree<E, T>]) -> io::Result<T>
+    where
+        T: Clone,
+    {
+        let mut result: &ReadHuffmanTree<E, T> = &tree[self.bitqueue.to_state()];
+        loop {
+            match result {
+                ReadHuffmanTree::Done(ref value, ref queue_val, ref queue_bits, _) => {
+                    self.bitqueue.set(*queue_val, *queue_bits);
+                    return Ok(value.clone());
+                }
+                ReadHuffmanTree::Continue(ref tree) => {
+                    result = &tree[read_byte(&mut self.reader)? as usize];
+                    self.bytes_read += 1;
+                }
+                ReadHuffmanTree::InvalidState => {
+                    panic!("invalid state");
+                }
+            }
+        }
+    }
+
+    /// Consumes reader and returns any un-read partial byte
+    /// as a `(bits, value)` tuple.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1010_0101, 0b0101_1010];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u16>(9).unwrap(), 0b1010_0101_0);
+    /// let (bits, value) = reader.into_unread();
+    /// assert_eq!(bits, 7);
+    /// assert_eq!(value, 0b101_1010);
+    /// ```
+    ///
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1010_0101, 0b0101_1010];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u16>(8).unwrap(), 0b1010_0101);
+    /// let (bits, value) = reader.into_unread();
+    /// assert_eq!(bits, 0);
+    /// assert_eq!(value, 0);
+    /// ```
+    #[inline]
+    pub fn into_unread(self) -> (u32, u8) {
+        (self.bitqueue.len(), self.bitqueue.value())
+    }
+
+    /// Consumes reader and returns the underlying stream
+    /// along with any un-read partial byte
+    /// as a `(reader, bits, value)` tuple,
+    /// which `from_state` accepts to resume reading later.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{LittleEndian, BitReader, BitRead};
+    /// let data = [0b1010_0101, 0b0101_1010];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    /// assert_eq!(reader.read::<u8>(3).unwrap(), 0b101);
+    /// let (cursor, bits, value) = reader.into_state();
+    /// assert_eq!(cursor.position(), 1);
+    /// assert_eq!(bits, 5);
+    /// assert_eq!(value, 0b1_0100);
+    /// ```
+    #[inline]
+    pub fn into_state(self) -> (R, u32, u8) {
+        (self.reader, self.bitqueue.len(), self.bitqueue.value())
+    }
+
+    /// Returns a snapshot of the reader's position and partial byte,
+    /// which `from_saved_state` restores over an underlying stream
+    /// placed just past that partial byte.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{LittleEndian, BitReader, BitRead};
+    /// let data = [0b1010_0101, 0b0101_1010];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    /// assert_eq!(reader.read::<u8>(3).unwrap(), 0b101);
+    /// let state = reader.save_state();
+    /// assert_eq!(state.position(), 3);
+    /// assert_eq!(state.bytes_consumed(), 1);
+    /// assert_eq!((state.bits(), state.value()), (5, 0b1_0100));
+    /// ```
+    #[inline]
+    pub fn save_state(&self) -> ReaderState {
+        ReaderState {
+            position: self.position(),
+            bytes_consumed: self.bytes_read,
+            bits: self.bitqueue.len(),
+            value: self.bitqueue.value(),
+        }
+    }
+
+    /// Removes any partial byte from the reader,
+    /// returning its unread bits as a `(value, bits)` tuple
+    /// and leaving the reader byte-aligned over the underlying stream.
+    ///
+    /// The value is the same as the next read of that many bits
+    /// would have returned, and the bits count as consumed
+    /// for the purposes of `position`.
+    /// They may be restored later with `from_state`,
+    /// which takes its bits first and its value as a `u8`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1010_0101, 0b0101_1010];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u8>(3).unwrap(), 0b101);
+    /// assert_eq!(reader.take_buffered(), (0b0_0101, 5));
+    /// assert!(reader.byte_aligned());
+    /// assert_eq!(reader.take_buffered(), (0, 0));
+    /// assert_eq!(reader.read::<u8>(8).unwrap(), 0b0101_1010);
+    /// ```
+    ///
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{LittleEndian, BitReader, BitRead};
+    /// let data = [0b1010_0101, 0b0101_1010];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    /// assert_eq!(reader.read::<u8>(3).unwrap(), 0b101);
+    /// let (value, bits) = reader.take_buffered();
+    /// let mut reader: BitReader<_, LittleEndian> =
+    ///     BitReader::from_state(reader.into_reader(), bits, value as u8);
+    /// assert_eq!(reader.read::<u16>(13).unwrap(), 0b0101_1010_1010_0);
+    /// ```
+    #[inline]
+    pub fn take_buffered(&mut self) -> (u32, u32) {
+        let bits = self.bitqueue.len();
+        (u32::from(self.bitqueue.pop_all()), bits)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: io::BufRead, E: Endianness> BitReader<R, E> {
+    /// Returns true if at least one more bit may be read,
+    /// either from the partial byte or the underlying stream.
+    ///
+    /// This may perform one underlying read to fill
+    /// the stream's buffer, but consumes nothing from it,
+    /// so the reader's position is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1010_0101];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// let mut nibbles = Vec::new();
+    /// while reader.has_bits_remaining().unwrap() {
+    ///     nibbles.push(reader.read::<u8>(4).unwrap());
+    /// }
+    /// assert_eq!(nibbles, [0b1010, 0b0101]);
+    /// ```
+    pub fn has_bits_remaining(&mut self) -> io::Result<bool> {
+        if self.bitqueue.is_empty() {
+            self.reader.fill_buf().map(|buf| !buf.is_empty())
+        } else {
+            Ok(true)
+        }
+    }
+
+    /// Returns the next whole byte in the stream without consuming it,
+    /// so that the next read of 8 bits returns the same value.
+    ///
+    /// This may perform one underlying read to fill
+    /// the stream's buffer, but consumes nothing from it,
+    /// so the reader's position is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `UnexpectedEof` if the stream has ended,
+    /// or of kind `InvalidInput` if the stream is not byte-aligned,
+    /// since the next 8 bits would then span two bytes.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0x47, 0x11];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.peek_byte().unwrap(), 0x47);
+    /// assert_eq!(reader.read::<u8>(8).unwrap(), 0x47);
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0x1);
+    /// assert!(reader.peek_byte().is_err());
+    /// ```
+    pub fn peek_byte(&mut self) -> io::Result<u8> {
+        if !self.bitqueue.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "peeked byte is not aligned",
+            ));
+        }
+        match self.reader.fill_buf()?.first() {
+            Some(byte) => Ok(*byte),
+            None => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "no byte to peek",
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: io::Read + io::Seek, E: Endianness> BitReader<R, E> {
+    /// Reads an unsigned value from the stream with
+    /// the given number of bits without consuming it,
+    /// so that a subsequent read sees the same bits.
+    ///
+    /// Because bits beyond the current partial byte
+    /// must be read from the underlying stream,
+    /// the stream is seeked back to its original position afterward.
+    /// The number of bits is limited to the size of the output type.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the output type is too small
+    /// to hold the requested number of bits.
+    /// The reader's position is unchanged in either case.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b10110111, 0b01100000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read::<u8>(3).unwrap(), 0b101);
+    /// assert_eq!(reader.peek::<u8>(7).unwrap(), 0b1011101);
+    /// assert_eq!(reader.read::<u8>(7).unwrap(), 0b1011101);
+    /// ```
+    pub fn peek<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        let (bitqueue, bytes_read, last_span) = (self.bitqueue, self.bytes_read, self.last_span);
+        let position = self.reader.stream_position()?;
+        let result = self.read(bits);
+        self.reader.seek(io::SeekFrom::Start(position))?;
+        self.bitqueue = bitqueue;
+        self.bytes_read = bytes_read;
+        self.last_span = last_span;
+        result
+    }
+
+    /// Counts the number of 1 bits in the stream until the next 0
+    /// and returns the amount read, like `read_unary0`,
+    /// but leaves the 0 stop bit unconsumed
+    /// so that it is the next bit read.
+    ///
+    /// If the stop bit begins a byte, the underlying stream
+    /// is seeked back one byte so it may be read again.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b1110_1111, 0b0000_0000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// assert_eq!(reader.read_unary0_peek_stop().unwrap(), 3);
+    /// assert_eq!(reader.read::<u8>(1).unwrap(), 0);
+    /// assert_eq!(reader.read_unary0_peek_stop().unwrap(), 4);
+    /// assert_eq!(reader.position(), 8);
+    /// assert_eq!(reader.read::<u8>(8).unwrap(), 0);
+    /// ```
+    pub fn read_unary0_peek_stop(&mut self) -> io::Result<u32> {
+        let unary = self.read_unary0()?;
+        self.unread_stop_bit(0)?;
+        Ok(unary)
+    }
+
+    /// Counts the number of 0 bits in the stream until the next 1
+    /// and returns the amount read, like `read_unary1`,
+    /// but leaves the 1 stop bit unconsumed
+    /// so that it is the next bit read.
+    ///
+    /// If the stop bit begins a byte, the underlying stream
+    /// is seeked back one byte so it may be read again.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{LittleEndian, BitReader, BitRead};
+    /// let data = [0b1111_1000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    /// assert_eq!(reader.read_unary1_peek_stop().unwrap(), 3);
+    /// assert_eq!(reader.read::<u8>(5).unwrap(), 0b11111);
+    /// ```
+    pub fn read_unary1_peek_stop(&mut self) -> io::Result<u32> {
+        let unary = self.read_unary1()?;
+        self.unread_stop_bit(1)?;
+        Ok(unary)
+    }
+
+    /// Puts the just-read stop bit back at the head of the stream.
+    fn unread_stop_bit(&mut self, stop_bit: u8) -> io::Result<()> {
+        if self.bitqueue.len() < 7 {
+            let mut bitqueue = BitQueue::from_value(stop_bit, 1);
+            bitqueue.push(self.bitqueue.len(), self.bitqueue.value());
+            self.bitqueue = bitqueue;
+        } else {
+            // the stop bit began its byte, so read that byte again
+            self.reader.seek(io::SeekFrom::Current(-1))?;
+            self.bitqueue.clear();
+            self.bytes_read -= 1;
+        }
+        Ok(())
+    }
+
+    /// Seeks to the given bit offset from the start of
+    /// the underlying stream, discarding any partial byte.
+    ///
+    /// The stream is seeked to the byte containing that bit
+    /// and any remaining bits of that byte are skipped.
+    /// Afterward, `position` reports the given offset.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0b10110111, 0b01100000];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// reader.seek_bits(6).unwrap();
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b1101);
+    /// reader.seek_bits(1).unwrap();
+    /// assert_eq!(reader.read::<u8>(3).unwrap(), 0b011);
+    /// assert_eq!(reader.position(), 4);
+    /// ```
+    pub fn seek_bits(&mut self, pos: u64) -> io::Result<()> {
+        self.reader.seek(io::SeekFrom::Start(pos / 8))?;
+        self.bitqueue.clear();
+        self.bytes_read = pos / 8;
+        self.origin = 0;
+        self.skip((pos % 8) as u32)
+    }
+
+    /// Advances the stream until the given sync pattern
+    /// is at the read position, such as the 12 bits of all 1s
+    /// beginning an MPEG audio or ADTS frame,
+    /// so that the next read of `pattern_bits` bits returns `pattern`.
+    ///
+    /// If `byte_aligned` is true, the stream is first byte-aligned
+    /// and only patterns starting on a whole byte are found.
+    /// Otherwise, the pattern may start on any bit.
+    /// Everything before the pattern is consumed,
+    /// and the stream is seeked back so that the pattern itself is not.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream,
+    /// including an error of kind `UnexpectedEof` if the stream
+    /// ends before the pattern is found.
+    /// Returns an error of kind `InvalidInput` if `pattern_bits`
+    /// is larger than 32 or if `pattern` does not fit in that many bits.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0x00, 0x3F, 0xFC, 0x12];
+    /// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    /// reader.find_sync(0xFFF, 12, false).unwrap();
+    /// assert_eq!(reader.position(), 10);
+    /// assert_eq!(reader.read::<u16>(12).unwrap(), 0xFFF);
+    /// ```
+    pub fn find_sync(
+        &mut self,
+        pattern: u32,
+        pattern_bits: u32,
+        byte_aligned: bool,
+    ) -> io::Result<()> {
+        if pattern_bits > 32 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits for sync pattern",
+            ));
+        } else if pattern_bits < 32 && !fits_bits(pattern_bits, pattern) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive value for sync pattern",
+            ));
+        }
+
+        let step = if byte_aligned {
+            self.byte_align();
+            8
+        } else {
+            1
+        };
+
+        // the bits from the candidate position onward,
+        // which are scanned rather than read as fields
+        let last_span = self.last_span;
+        let mut window = BitQueue::<E, u64>::new();
+        let found = self
+            .read::<u32>(pattern_bits)
+            .and_then(|bits| {
+                window.push(pattern_bits, u64::from(bits));
+                while window.value() != u64::from(pattern) {
+                    window.push(step, u64::from(self.read::<u8>(step)?));
+                    window.drop(step);
+                }
+                Ok(())
+            })
+            .and_then(|()| self.rewind_bits(u64::from(pattern_bits)));
+        self.last_span = last_span;
+        found
+    }
+
+    /// Moves the read position back by the given number of bits,
+    /// which must have already been read.
+    fn rewind_bits(&mut self, bits: u64) -> io::Result<()> {
+        let target = self.bytes_read * 8 - u64::from(self.bitqueue.len()) - bits;
+        let bytes_back = self.bytes_read - target / 8;
+        self.reader
+            .seek(io::SeekFrom::Current(-(bytes_back as i64)))?;
+        self.bitqueue.clear();
+        self.bytes_read = target / 8;
+        self.skip((target % 8) as u32)
+    }
+}
+
+impl<'a, E: Endianness> BitReader<&'a [u8], E> {
+    /// Borrows the given number of bytes directly
+    /// from the underlying slice without copying them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if the stream is not byte-aligned
+    /// and `UnexpectedEof` if fewer than `count` bytes remain.
+    /// The stream is left unchanged in either case.
+    ///
+    /// # Example
+    /// ```
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0x01, 0x02, 0x03, 0x04];
+    /// let mut reader = BitReader::endian(&data[..], BigEndian);
+    /// assert_eq!(reader.read::<u8>(8).unwrap(), 0x01);
+    /// assert_eq!(reader.read_bytes_ref(2).unwrap(), &[0x02, 0x03]);
+    /// assert_eq!(reader.position(), 24);
+    /// assert!(reader.read_bytes_ref(2).is_err());
+    /// ```
+    pub fn read_bytes_ref(&mut self, count: usize) -> io::Result<&'a [u8]> {
+        if !self.byte_aligned() {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "stream not byte-aligned",
+            ))
+        } else if count > self.reader.len() {
+            Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "insufficient bytes remaining",
+            ))
+        } else {
+            let (head, tail) = self.reader.split_at(count);
+            self.reader = tail;
+            self.bytes_read += count as u64;
+            Ok(head)
+        }
+    }
+
+    /// Skips the given number of bits in constant time
+    /// by advancing through the underlying slice,
+    /// for large skips over in-memory or memory-mapped data
+    /// where `skip` would read every byte skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedEof` if fewer than `bits` bits remain,
+    /// in which case the stream is left unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use bitstream_io::{BigEndian, BitReader, BitRead};
+    /// let data = [0x01, 0x02, 0x03, 0x04];
+    /// let mut reader = BitReader::endian(&data[..], BigEndian);
+    /// reader.advance(20).unwrap();
+    /// assert_eq!(reader.read::<u16>(12).unwrap(), 0x304);
+    /// assert!(reader.advance(1).is_err());
+    /// ```
+    pub fn advance(&mut self, bits: u64) -> io::Result<()> {
+        let queued = u64::from(self.bitqueue.len());
+        if bits <= queued {
+            self.bitqueue.drop(bits as u32);
+            return Ok(());
+        }
+        let bits = bits - queued;
+        let whole = bits / 8;
+        let partial = (bits % 8) as u32;
+        if whole + u64::from(partial > 0) > self.reader.len() as u64 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "insufficient bytes remaining",
+            ));
+        }
+        self.bitqueue.clear();
+        self.reader = &self.reader[whole as usize..];
+        self.bytes_read += whole;
+        if partial > 0 {
+            self.bitqueue.set(self.reader[0], 8);
+            self.bitqueue.drop(partial);
+            self.reader = &self.reader[1..];
+            self.bytes_read += 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<R: io::Read, E: Endianness> BitReader<RewindReader<R>, E> {
+    /// Remembers the current position so that a later `rewind`
+    /// can return to it, replacing any earlier mark.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead, RewindReader};
+    /// let data = [0b1011_0001, 0b1110_0101, 0b0011_1100];
+    /// let mut reader = BitReader::endian(RewindReader::new(Cursor::new(&data), 16), BigEndian);
+    /// assert_eq!(reader.read::<u8>(3).unwrap(), 0b101);
+    /// reader.mark();
+    /// assert_eq!(reader.read::<u16>(9).unwrap(), 0b1_0001_1110);
+    /// reader.rewind().unwrap();
+    /// assert_eq!(reader.read::<u8>(5).unwrap(), 0b1_0001);
+    /// assert_eq!(reader.position(), 8);
+    /// ```
+    pub fn mark(&mut self) {
+        self.reader
+            .mark(self.bitqueue.len(), self.bitqueue.value(), self.bytes_read);
+    }
+
+    /// Returns to the position saved by the most recent `mark`.
+    /// The mark remains set, so one may rewind to it repeatedly.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidInput` if no mark has been set
+    /// or if more bytes have been consumed since the mark
+    /// than the `RewindReader`'s window allows.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor, ErrorKind};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead, RewindReader};
+    /// let data = [0x01, 0x02, 0x03, 0x04];
+    /// let mut reader = BitReader::endian(RewindReader::new(Cursor::new(&data), 2), BigEndian);
+    /// reader.mark();
+    /// assert_eq!(reader.read::<u32>(24).unwrap(), 0x01_02_03);
+    /// assert_eq!(reader.rewind().unwrap_err().kind(), ErrorKind::InvalidInput);
+    /// assert_eq!(reader.read::<u8>(8).unwrap(), 0x04);
+    /// ```
+    pub fn rewind(&mut self) -> io::Result<()> {
+        let (bits, value, bytes_read) = self.reader.rewind()?;
+        self.bitqueue = BitQueue::from_value(value, bits);
+        self.bytes_read = bytes_read;
+        // a position reset since the mark restarts at the mark instead
+        self.origin = self.origin.min(bytes_read * 8 - u64::from(bits));
+        Ok(())
+    }
+
+    /// Calls the closure with this reader and, should it fail,
+    /// rewinds to where the reader was beforehand,
+    /// so that a composite read either succeeds as a whole
+    /// or consumes nothing.
+    /// This sets a mark, replacing any earlier one.
+    ///
+    /// # Errors
+    ///
+    /// Passes along the closure's error once the reader is restored.
+    /// If the closure consumed more bytes than the `RewindReader`'s
+    /// window allows, the reader cannot be restored and
+    /// an error of kind `InvalidInput` is returned instead.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor, ErrorKind};
+    /// use bitstream_io::{BigEndian, BitReader, BitRead, RewindReader};
+    /// let data = [0b1011_0001, 0b1110_0101];
+    /// let mut reader = BitReader::endian(RewindReader::new(Cursor::new(&data), 4), BigEndian);
+    /// assert_eq!(reader.read::<u8>(4).unwrap(), 0b1011);
+    /// let err = reader
+    ///     .read_atomic(|r| Ok((r.read::<u8>(8)?, r.read::<u8>(8)?)))
+    ///     .unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    /// assert_eq!(reader.position(), 4);
+    /// assert_eq!(reader.read::<u16>(12).unwrap(), 0b0001_1110_0101);
+    /// ```
+    pub fn read_atomic<T, F>(&mut self, f: F) -> io::Result<T>
+    where
+        F: FnOnce(&mut Self) -> io::Result<T>,
+    {
+        self.mark();
+        match f(self) {
+            Ok(value) => Ok(value),
+            Err(err) => {
+                self.rewind()?;
+                Err(err)
+            }
+        }
+    }
+}
+
+impl<R: io::Read, E: Endianness> BitRead for BitReader<R, E> {
+    #[inline(always)]
+    fn read_bit(&mut self) -> io::Result<bool> {
+        if self.bitqueue.is_empty() {
+            let byte = read_byte(&mut self.reader).map_err(|err| truncated(err, 1, 0))?;
+            self.bitqueue.set(byte, 8);
+            self.bytes_read += 1;
+        }
+        Ok(self.bitqueue.pop(1) == 1)
+    }
+
+    fn read<U>(&mut self, mut bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        if bits <= U::bits_size() {
+            let start = self.position();
+            let bitqueue_len = self.bitqueue.len();
+            if bits <= bitqueue_len {
+                self.last_span = (start, start + u64::from(bits));
+                Ok(U::from_u8(self.bitqueue.pop(bits)))
+            } else {
+                let requested = bits;
+                let mut acc =
+                    BitQueue::from_value(U::from_u8(self.bitqueue.pop_all()), bitqueue_len);
+                bits -= bitqueue_len;
+
+                let bitqueue = &mut self.bitqueue;
+                let mut reader = CountingReader::new(&mut self.reader);
+                let result = read_aligned(&mut reader, bits / 8, &mut acc)
+                    .and_then(|()| read_unaligned(&mut reader, bits % 8, &mut acc, bitqueue));
+                // bytes drained before an error stay consumed
+                self.bytes_read += reader.bytes;
+                result.map_err(|err| {
+                    truncated(
+                        err,
+                        u64::from(requested),
+                        u64::from(bitqueue_len) + reader.bytes * 8,
+                    )
+                })?;
+                self.last_span = (start, start + u64::from(requested));
+                Ok(acc.value())
+            }
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits for type read",
+            ))
+        }
+    }
+
+    #[inline]
+    fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
+    where
+        S: SignedNumeric,
+    {
+        let (start, last_span) = (self.position(), self.last_span);
+        match E::read_signed(self, bits) {
+            Ok(value) => {
+                self.last_span = (start, self.position());
+                Ok(value)
+            }
+            Err(err) => {
+                self.last_span = last_span;
+                Err(err)
+            }
+        }
+    }
+
+    fn skip(&mut self, mut bits: u32) -> io::Result<()> {
+        use core::cmp::min;
+
+        let requested = bits;
+        let to_drop = min(self.bitqueue.len(), bits);
+        if to_drop != 0 {
+            self.bitqueue.drop(to_drop);
+            bits -= to_drop;
+        }
+
+        let bitqueue = &mut self.bitqueue;
+        let mut reader = CountingReader::new(&mut self.reader);
+        let result = skip_aligned(&mut reader, bits / 8)
+            .and_then(|()| skip_unaligned(&mut reader, bits % 8, bitqueue));
+        self.bytes_read += reader.bytes;
+        result.map_err(|err| {
+            truncated(
+                err,
+                u64::from(requested),
+                u64::from(to_drop) + reader.bytes * 8,
+            )
+        })
+    }
+
+    fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        let start = self.position();
+        let mut reader = CountingReader::new(&mut self.reader);
+        let result = io::Read::read_exact(&mut reader, buf);
+        self.bytes_read += reader.bytes;
+        result.map_err(|err| truncated(err, buf.len() as u64 * 8, reader.bytes * 8))?;
+        self.last_span = (start, start + buf.len() as u64 * 8);
+        realign_bytes(buf, &mut self.bitqueue);
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn read_bits_to_bytes(
+        &mut self,
+        bits: u32,
+        justify: Justify,
+        out: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        let size = self.alloc_limit((u64::from(bits) + 7) / 8)?;
+        let (len, whole, partial) = (out.len(), (bits / 8) as usize, bits % 8);
+        // bytes come back in the endianness's order,
+        // but are packed in the order their bits were read
+        let high_first = BitQueue::<E, u8>::from_value(0x80, 8).pop(1) == 1;
+        // whole bytes are read in chunks, so a huge count
+        // can't allocate much more than the stream holds
+        let mut read = Ok(());
+        while read.is_ok() && out.len() < len + whole {
+            let start = out.len();
+            out.resize(start + core::cmp::min(len + whole - start, 4096), 0);
+            read = self.read_bytes(&mut out[start..]);
+        }
+        let read = read.and_then(|()| {
+            if partial > 0 {
+                self.read::<u8>(partial)
+            } else {
+                Ok(0)
+            }
+        });
+        let last = match read {
+            Ok(last) => last,
+            Err(err) => {
+                out.truncate(len);
+                return Err(err);
+            }
+        };
+        if !high_first {
+            for byte in out[len..].iter_mut() {
+                *byte = byte.reverse_bits();
+            }
+        }
+        if partial > 0 {
+            out.push(if high_first {
+                last << (8 - partial)
+            } else {
+                last.reverse_bits()
+            });
+            if let Justify::Right = justify {
+                let shift = 8 - partial;
+                for index in (len..len + size).rev() {
+                    let carry = if index > len {
+                        out[index - 1] << partial
+                    } else {
+                        0
+                    };
+                    out[index] = (out[index] >> shift) | carry;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn read_to_vec(&mut self, count: usize) -> io::Result<Vec<u8>> {
+        let mut buf = alloc::vec![0; self.alloc_limit(count as u64)?];
+        self.read_bytes(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn read_unary0(&mut self) -> io::Result<u32> {
+        if self.bitqueue.is_empty() {
+            let u = read_aligned_unary(&mut self.reader, 0b1111_1111, &mut self.bitqueue)?;
+            self.bytes_read += u64::from(u / 8 + 1);
+            Ok(u + self.bitqueue.pop_1())
+        } else if self.bitqueue.all_1() {
+            let base = self.bitqueue.len();
+            self.bitqueue.clear();
+            let u = read_aligned_unary(&mut self.reader, 0b1111_1111, &mut self.bitqueue)?;
+            self.bytes_read += u64::from(u / 8 + 1);
+            Ok(base + u + self.bitqueue.pop_1())
+        } else {
+            Ok(self.bitqueue.pop_1())
+        }
+    }
+
+    fn read_unary1(&mut self) -> io::Result<u32> {
+        if self.bitqueue.is_empty() {
+            let u = read_aligned_unary(&mut self.reader, 0b0000_0000, &mut self.bitqueue)?;
+            self.bytes_read += u64::from(u / 8 + 1);
+            Ok(u + self.bitqueue.pop_0())
+        } else if self.bitqueue.all_0() {
+            let base = self.bitqueue.len();
+            self.bitqueue.clear();
+            let u = read_aligned_unary(&mut self.reader, 0b0000_0000, &mut self.bitqueue)?;
+            self.bytes_read += u64::from(u / 8 + 1);
+            Ok(base + u + self.bitqueue.pop_0())
+        } else {
+            Ok(self.bitqueue.pop_0())
+        }
+    }
+
+    #[inline]
+    fn byte_aligned(&self) -> bool {
+        self.bitqueue.is_empty()
+    }
+
+    #[inline]
+    fn byte_align(&mut self) {
+        self.bitqueue.clear()
+    }
+}
+
+impl<R: ByteRead + ?Sized> ByteRead for &mut R {}
+
+impl<R: io::Read, E: Endianness> ByteRead for BitReader<R, E> {}
+
+/// Shows the reader's position and its buffered bits
+/// in the order they will be read.
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Cursor};
+/// use bitstream_io::{BigEndian, BitReader, BitRead};
+/// let data = [0b10110111];
+/// let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+/// reader.read::<u8>(3).unwrap();
+/// assert_eq!(
+///     format!("{:?}", reader),
+///     "BitReader { position: 3, buffered: 10111 }"
+/// );
+/// ```
+impl<R: io::Read, E: Endianness> fmt::Debug for BitReader<R, E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BitReader")
+            .field("position", &self.position())
+            .field("buffered", &QueuedBits(self.bitqueue))
+            .finish()
+    }
+}
+
+struct QueuedBits<E: Endianness>(BitQueue<E, u8>);
+
+impl<E: Endianness> fmt::Debug for QueuedBits<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut queue = self.0;
+        while !queue.is_empty() {
+            f.write_str(if queue.pop(1) == 0 { "0" } else { "1" })?;
+        }
+        Ok(())
+    }
+}
+
+/// A bitstream reader which limits the number of bits
+/// that may be read from an inner reader.
+///
+/// Returned by `BitRead::take`.
+/// Any fixed-size read which would exceed the limit fails with
+/// an error of kind `UnexpectedEof` and consumes nothing.
+/// Unary reads consume bits up to the limit before failing.
+pub struct BitReaderTake<B> {
+    inner: B,
+    remaining: u64,
+}
+
+impl<B: BitRead> BitReaderTake<B> {
+    /// Returns the number of bits which may still be read
+    #[inline]
+    pub fn bits_remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    /// Unwraps the inner reader, discarding any remaining limit
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn consume(&mut self, bits: u64) -> io::Result<()> {
+        if bits <= self.remaining {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "read past end of limited stream",
+            ))
+        }
+    }
+}
+
+impl<B: BitRead> BitRead for BitReaderTake<B> {
+    fn read_bit(&mut self) -> io::Result<bool> {
+        self.consume(1)?;
+        let bit = self.inner.read_bit()?;
+        self.remaining -= 1;
+        Ok(bit)
+    }
+
+    fn read<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        self.consume(u64::from(bits))?;
+        let value = self.inner.read(bits)?;
+        self.remaining -= u64::from(bits);
+        Ok(value)
+    }
+
+    fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
+    where
+        S: SignedNumeric,
+    {
+        self.consume(u64::from(bits))?;
+        let value = self.inner.read_signed(bits)?;
+        self.remaining -= u64::from(bits);
+        Ok(value)
+    }
+
+    fn skip(&mut self, bits: u32) -> io::Result<()> {
+        self.consume(u64::from(bits))?;
+        self.inner.skip(bits)?;
+        self.remaining -= u64::from(bits);
+        Ok(())
+    }
+
+    fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        let bits = buf.len() as u64 * 8;
+        self.consume(bits)?;
+        self.inner.read_bytes(buf)?;
+        self.remaining -= bits;
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn read_to_vec(&mut self, count: usize) -> io::Result<Vec<u8>> {
+        let bits = (count as u64).saturating_mul(8);
+        self.consume(bits)?;
+        let buf = self.inner.read_to_vec(count)?;
+        self.remaining -= bits;
+        Ok(buf)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn read_bits_to_bytes(
+        &mut self,
+        bits: u32,
+        justify: Justify,
+        out: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        self.consume(u64::from(bits))?;
+        self.inner.read_bits_to_bytes(bits, justify, out)?;
+        self.remaining -= u64::from(bits);
+        Ok(())
+    }
+
+    fn read_unary0(&mut self) -> io::Result<u32> {
+        let mut unary = 0;
+        while self.read_bit()? {
+            unary += 1;
+        }
+        Ok(unary)
+    }
+
+    fn read_unary1(&mut self) -> io::Result<u32> {
+        let mut unary = 0;
+        while !self.read_bit()? {
+            unary += 1;
+        }
+        Ok(unary)
+    }
+
+    #[inline]
+    fn byte_aligned(&self) -> bool {
+        self.inner.byte_aligned()
+    }
+
+    fn byte_align(&mut self) {
+        while !self.inner.byte_aligned() && self.read_bit().is_ok() {}
+        self.inner.byte_align()
+    }
+}
+
+impl<B: BitRead> ByteRead for BitReaderTake<B> {}
+
+/// For reading non-aligned bits from a stream of bytes
+/// in an endianness chosen at runtime.
+///
+/// Each read branches on the endianness before dispatching
+/// to the corresponding `BitReader`,
+/// so prefer `BitReader` itself when the endianness is known
+/// at compile time.
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Cursor};
+/// use bitstream_io::{BitRead, DynamicEndianBitReader};
+/// let data = [0b10110111];
+/// let big_endian: bool = true;
+/// let mut reader = if big_endian {
+///     DynamicEndianBitReader::big_endian(Cursor::new(&data))
+/// } else {
+///     DynamicEndianBitReader::little_endian(Cursor::new(&data))
+/// };
+/// assert_eq!(reader.read::<u8>(3).unwrap(), 0b101);
+/// ```
+pub enum DynamicEndianBitReader<R: io::Read> {
+    /// Reads most significant bits first
+    Big(BitReader<R, BigEndian>),
+    /// Reads least significant bits first
+    Little(BitReader<R, LittleEndian>),
+}
+
+macro_rules! dispatch_reader {
+    ($reader:expr, $r:ident => $body:expr) => {
+        match $reader {
+            DynamicEndianBitReader::Big($r) => $body,
+            DynamicEndianBitReader::Little($r) => $body,
+        }
+    };
+}
+
+impl<R: io::Read> DynamicEndianBitReader<R> {
+    /// Wraps a big-endian reader around something that implements `Read`
+    #[inline]
+    pub fn big_endian(reader: R) -> DynamicEndianBitReader<R> {
+        DynamicEndianBitReader::Big(BitReader::new(reader))
+    }
+
+    /// Wraps a little-endian reader around something that implements `Read`
+    #[inline]
+    pub fn little_endian(reader: R) -> DynamicEndianBitReader<R> {
+        DynamicEndianBitReader::Little(BitReader::new(reader))
+    }
+
+    /// Returns true if the stream is being read big-endian
+    #[inline]
+    pub fn is_big_endian(&self) -> bool {
+        match self {
+            DynamicEndianBitReader::Big(_) => true,
+            DynamicEndianBitReader::Little(_) => false,
+        }
+    }
+
+    /// Returns the total number of bits consumed from the stream
+    /// since the reader was constructed.
+    #[inline]
+    pub fn position(&self) -> u64 {
+        dispatch_reader!(self, r => r.position())
+    }
+
+    /// Returns the total number of whole bytes pulled
+    /// from the underlying stream since the reader was constructed.
+    #[inline]
+    pub fn total_bytes_consumed(&self) -> u64 {
+        dispatch_reader!(self, r => r.total_bytes_consumed())
+    }
+
+    /// Unwraps internal reader and disposes of the reader.
+    /// Any partial bits are discarded.
+    #[inline]
+    pub fn into_reader(self) -> R {
+        dispatch_reader!(self, r => r.into_reader())
+    }
+}
+
+impl<R: io::Read> BitRead for DynamicEndianBitReader<R> {
+    #[inline]
+    fn read_bit(&mut self) -> io::Result<bool> {
+        dispatch_reader!(self, r => r.read_bit())
+    }
+
+    #[inline]
+    fn read<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        dispatch_reader!(self, r => r.read(bits))
+    }
+
+    #[inline]
+    fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
+    where
+        S: SignedNumeric,
+    {
+        dispatch_reader!(self, r => r.read_signed(bits))
+    }
+
+    #[inline]
+    fn skip(&mut self, bits: u32) -> io::Result<()> {
+        dispatch_reader!(self, r => r.skip(bits))
+    }
+
+    #[inline]
+    fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        dispatch_reader!(self, r => r.read_bytes(buf))
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn read_to_vec(&mut self, count: usize) -> io::Result<Vec<u8>> {
+        dispatch_reader!(self, r => r.read_to_vec(count))
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn read_bits_to_bytes(
+        &mut self,
+        bits: u32,
+        justify: Justify,
+        out: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        dispatch_reader!(self, r => r.read_bits_to_bytes(bits, justify, out))
+    }
+
+    #[inline]
+    fn read_unary0(&mut self) -> io::Result<u32> {
+        dispatch_reader!(self, r => r.read_unary0())
+    }
+
+    #[inline]
+    fn read_unary1(&mut self) -> io::Result<u32> {
+        dispatch_reader!(self, r => r.read_unary1())
+    }
+
+    #[inline]
+    fn byte_aligned(&self) -> bool {
+        dispatch_reader!(self, r => r.byte_aligned())
+    }
+
+    #[inline]
+    fn byte_align(&mut self) {
+        dispatch_reader!(self, r => r.byte_align())
+    }
+}
+
+impl<R: io::Read> ByteRead for DynamicEndianBitReader<R> {}
+
+/// For reading from two logical bitstreams which a format interleaves,
+/// each with its own underlying stream and partial byte.
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Cursor};
+/// use bitstream_io::{BigEndian, BitReader, BitRead, InterleavedBitReader};
+/// let first = [0b1010_0000];
+/// let second = [0b0111_0000];
+/// let mut reader = InterleavedBitReader::new(
+///     BitReader::endian(Cursor::new(&first), BigEndian),
+///     BitReader::endian(Cursor::new(&second), BigEndian),
+/// );
+/// assert_eq!(reader.read_from::<u8>(0, 3).unwrap(), 0b101);
+/// assert_eq!(reader.read_from::<u8>(1, 4).unwrap(), 0b0111);
+/// assert_eq!(reader.read_from::<u8>(0, 1).unwrap(), 0b0);
+/// ```
+pub struct InterleavedBitReader<R: io::Read, E: Endianness> {
+    readers: [BitReader<R, E>; 2],
+}
+
+impl<R: io::Read, E: Endianness> InterleavedBitReader<R, E> {
+    /// Combines two readers, which become streams 0 and 1
+    #[inline]
+    pub fn new(first: BitReader<R, E>, second: BitReader<R, E>) -> InterleavedBitReader<R, E> {
+        InterleavedBitReader {
+            readers: [first, second],
+        }
+    }
+
+    /// Reads an unsigned value with the given number of bits
+    /// from the given stream, leaving the other untouched.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Also returns an error if the output type is too small
+    /// to hold the requested number of bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stream` is neither 0 nor 1.
+    #[inline]
+    pub fn read_from<U>(&mut self, stream: usize, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        self.readers[stream].read(bits)
+    }
+
+    /// Borrows the reader for the given stream,
+    /// for reading anything other than unsigned values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stream` is neither 0 nor 1.
+    #[inline]
+    pub fn stream(&mut self, stream: usize) -> &mut BitReader<R, E> {
+        &mut self.readers[stream]
+    }
+
+    /// Splits into the readers for streams 0 and 1
+    #[inline]
+    pub fn into_readers(self) -> (BitReader<R, E>, BitReader<R, E>) {
+        let [first, second] = self.readers;
+        (first, second)
+    }
+}
+
+/// For reading bits in a given order within each byte
+/// while assembling multi-byte values in a separately chosen byte order,
+/// such as little-endian bytes of most significant bits first.
+///
+/// A value of `bits` bits is read as 8-bit groups,
+/// with any partial group last, each read in the endianness's bit order.
+/// The combinations of `BigEndian` with `ByteOrder::Big`
+/// and `LittleEndian` with `ByteOrder::Little`
+/// read the same as an ordinary `BitReader`.
+///
+/// For instance, the four combinations read a 12-bit value
+/// from the bytes `[0x12, 0xB4]` as:
+///
+/// | bit order      | byte order          | value   |
+/// |----------------|---------------------|---------|
+/// | `BigEndian`    | `ByteOrder::Big`    | `0x12B` |
+/// | `BigEndian`    | `ByteOrder::Little` | `0xB12` |
+/// | `LittleEndian` | `ByteOrder::Big`    | `0x124` |
+/// | `LittleEndian` | `ByteOrder::Little` | `0x412` |
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Cursor};
+/// use bitstream_io::{BigEndian, BitRead, ByteOrder, OrderedBitReader};
+/// let data = [0x34, 0x12, 0b1011_0000];
+/// let mut reader = OrderedBitReader::new(Cursor::new(&data), BigEndian, ByteOrder::Little);
+/// assert_eq!(reader.read::<u16>(16).unwrap(), 0x1234);
+/// assert_eq!(reader.read::<u8>(4).unwrap(), 0b1011);
+/// ```
+pub struct OrderedBitReader<R: io::Read, E: Endianness> {
+    reader: BitReader<R, E>,
+    byte_order: ByteOrder,
+}
+
+impl<R: io::Read, E: Endianness> OrderedBitReader<R, E> {
+    /// Wraps a reader with the given bit order within bytes
+    /// and byte order within values
+    #[inline]
+    pub fn new(reader: R, _bit_order: E, byte_order: ByteOrder) -> OrderedBitReader<R, E> {
+        OrderedBitReader {
+            reader: BitReader::new(reader),
+            byte_order,
+        }
+    }
+
+    /// Returns the byte order of multi-byte values
+    #[inline]
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+
+    /// Unwraps the inner bitstream reader
+    #[inline]
+    pub fn into_bitreader(self) -> BitReader<R, E> {
+        self.reader
+    }
+
+    /// Reads a value as groups of up to 8 bits,
+    /// reading its most significant group with the given closure
+    /// and the remaining groups as unsigned values
+    fn read_groups<N, F>(&mut self, bits: u32, most_significant: F) -> io::Result<N>
+    where
+        N: Numeric,
+        F: FnOnce(&mut BitReader<R, E>, u32) -> io::Result<N>,
+    {
+        use core::cmp::min;
+
+        if bits > N::bits_size() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits for type read",
+            ));
+        } else if bits == 0 {
+            return Ok(N::default());
+        }
+
+        let mut acc;
+        match self.byte_order {
+            ByteOrder::Big => {
+                let group = min(8, bits);
+                acc = most_significant(&mut self.reader, group)?;
+                let mut remaining = bits - group;
+                while remaining > 0 {
+                    let group = min(8, remaining);
+                    let part = N::from_u8(self.reader.read(group)?);
+                    acc <<= group;
+                    acc |= part;
+                    remaining -= group;
+                }
+            }
+            ByteOrder::Little => {
+                acc = N::default();
+                let mut shift = 0;
+                while bits - shift > 8 {
+                    acc |= N::from_u8(self.reader.read(8)?) << shift;
+                    shift += 8;
+                }
+                acc |= most_significant(&mut self.reader, bits - shift)? << shift;
+            }
+        }
+        Ok(acc)
+    }
+}
+
+impl<R: io::Read, E: Endianness> BitRead for OrderedBitReader<R, E> {
+    #[inline]
+    fn read_bit(&mut self) -> io::Result<bool> {
+        self.reader.read_bit()
+    }
+
+    fn read<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        self.read_groups(bits, |r, group| r.read::<u8>(group).map(U::from_u8))
+    }
+
+    fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
+    where
+        S: SignedNumeric,
+    {
+        self.read_groups(bits, |r, group| r.read_signed(group))
+    }
+
+    #[inline]
+    fn skip(&mut self, bits: u32) -> io::Result<()> {
+        self.reader.skip(bits)
+    }
+
+    #[inline]
+    fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.reader.read_bytes(buf)
+    }
+
+    #[inline]
+    fn read_unary0(&mut self) -> io::Result<u32> {
+        self.reader.read_unary0()
+    }
+
+    #[inline]
+    fn read_unary1(&mut self) -> io::Result<u32> {
+        self.reader.read_unary1()
+    }
+
+    #[inline]
+    fn byte_aligned(&self) -> bool {
+        self.reader.byte_aligned()
+    }
+
+    #[inline]
+    fn byte_align(&mut self) {
+        self.reader.byte_align()
+    }
+}
+
+impl<R: io::Read, E: Endianness> ByteRead for OrderedBitReader<R, E> {}
+
+/// A bitstream reader which removes stuffing bits from an inner reader,
+/// such as the 0 bit HDLC inserts after every five consecutive 1 bits,
+/// so that reads see only the logical stream.
+///
+/// The stuffing bit following a run is consumed lazily,
+/// just before the next logical bit is read.
+/// If that bit is a 1 rather than the expected 0,
+/// the read fails with an error of kind `InvalidData`.
+/// Multi-bit reads which fail partway through may have
+/// consumed some bits of the inner reader.
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Cursor};
+/// use bitstream_io::{BigEndian, BitReader, BitRead, Unstuffer};
+/// let data = [0b1111_1011, 0b1000_0000];
+/// let mut reader = Unstuffer::new(BitReader::endian(Cursor::new(&data), BigEndian), 5);
+/// assert_eq!(reader.read::<u8>(8).unwrap(), 0b1111_1111);
+/// assert_eq!(reader.read::<u8>(1).unwrap(), 0);
+/// ```
+pub struct Unstuffer<R: io::Read, E: Endianness> {
+    reader: BitReader<R, E>,
+    run_length: u32,
+    ones: u32,
+}
+
+impl<R: io::Read, E: Endianness> Unstuffer<R, E> {
+    /// Wraps a reader, dropping the 0 bit which follows
+    /// every `run_length` consecutive 1 bits
+    ///
+    /// # Panics
+    ///
+    /// Panics if `run_length` is 0.
+    #[inline]
+    pub fn new(reader: BitReader<R, E>, run_length: u32) -> Unstuffer<R, E> {
+        assert!(run_length > 0, "stuffing run length must be nonzero");
+        Unstuffer {
+            reader,
+            run_length,
+            ones: 0,
+        }
+    }
+
+    /// Unwraps the inner reader
+    #[inline]
+    pub fn into_reader(self) -> BitReader<R, E> {
+        self.reader
+    }
+}
+
+impl<R: io::Read, E: Endianness> BitRead for Unstuffer<R, E> {
+    fn read_bit(&mut self) -> io::Result<bool> {
+        if self.ones == self.run_length {
+            if self.reader.read_bit()? {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "missing stuffing bit",
+                ));
+            }
+            self.ones = 0;
+        }
+        let bit = self.reader.read_bit()?;
+        if bit {
+            self.ones += 1;
+        } else {
+            self.ones = 0;
+        }
+        Ok(bit)
+    }
+
+    fn read<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        if bits <= U::bits_size() {
+            let mut acc = BitQueue::<E, U>::new();
+            for _ in 0..bits {
+                acc.push(
+                    1,
+                    if self.read_bit()? {
+                        U::one()
+                    } else {
+                        U::default()
+                    },
+                );
+            }
+            Ok(acc.value())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits for type read",
+            ))
+        }
+    }
+
+    #[inline]
+    fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
+    where
+        S: SignedNumeric,
+    {
+        E::read_signed(self, bits)
+    }
+
+    fn skip(&mut self, bits: u32) -> io::Result<()> {
+        for _ in 0..bits {
+            self.read_bit()?;
+        }
+        Ok(())
+    }
+
+    fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        for byte in buf.iter_mut() {
+            *byte = self.read(8)?;
+        }
+        Ok(())
+    }
+
+    fn read_unary0(&mut self) -> io::Result<u32> {
+        let mut unary = 0;
+        while self.read_bit()? {
+            unary += 1;
+        }
+        Ok(unary)
+    }
+
+    fn read_unary1(&mut self) -> io::Result<u32> {
+        let mut unary = 0;
+        while !self.read_bit()? {
+            unary += 1;
+        }
+        Ok(unary)
+    }
+
+    /// Whether the inner reader is aligned,
+    /// counting stuffing bits
+    #[inline]
+    fn byte_aligned(&self) -> bool {
+        self.reader.byte_aligned()
+    }
+
+    /// Aligns the inner reader, counting stuffing bits,
+    /// and restarts the count of consecutive 1 bits
+    #[inline]
+    fn byte_align(&mut self) {
+        self.reader.byte_align();
+        self.ones = 0;
+    }
+}
+
+impl<R: io::Read, E: Endianness> ByteRead for Unstuffer<R, E> {}
+
+/// A bitstream reader which keeps the running parity
+/// of every bit consumed through it,
+/// for validating the parity bits of error-detecting layers
+/// without reading the data twice.
+///
+/// Bits discarded by `byte_align` are not counted.
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Cursor};
+/// use bitstream_io::{BigEndian, BitReader, BitRead, ParityReader};
+/// let data = [0b1011_0001];
+/// let mut reader = ParityReader::new(BitReader::endian(Cursor::new(&data), BigEndian));
+/// assert_eq!(reader.read::<u8>(7).unwrap(), 0b1011_000);
+/// assert!(reader.parity());
+/// assert!(reader.read_bit().unwrap());
+/// assert!(!reader.parity());
+/// ```
+pub struct ParityReader<R: io::Read, E: Endianness> {
+    reader: BitReader<R, E>,
+    parity: bool,
+}
+
+impl<R: io::Read, E: Endianness> ParityReader<R, E> {
+    /// Wraps a reader, starting with even parity
+    #[inline]
+    pub fn new(reader: BitReader<R, E>) -> ParityReader<R, E> {
+        ParityReader {
+            reader,
+            parity: false,
+        }
+    }
+
+    /// Returns true if an odd number of 1 bits
+    /// have been consumed since the parity was last reset
+    #[inline]
+    pub fn parity(&self) -> bool {
+        self.parity
+    }
+
+    /// Resets the running parity to even
+    #[inline]
+    pub fn reset_parity(&mut self) {
+        self.parity = false;
+    }
+
+    /// Unwraps the inner reader
+    #[inline]
+    pub fn into_reader(self) -> BitReader<R, E> {
+        self.reader
+    }
+}
+
+impl<R: io::Read, E: Endianness> BitRead for ParityReader<R, E> {
+    #[inline]
+    fn read_bit(&mut self) -> io::Result<bool> {
+        let bit = self.reader.read_bit()?;
+        self.parity ^= bit;
+        Ok(bit)
+    }
+
+    #[inline]
+    fn read<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        let value: U = self.reader.read(bits)?;
+        self.parity ^= value.count_ones() % 2 == 1;
+        Ok(value)
+    }
+
+    #[inline]
+    fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
+    where
+        S: SignedNumeric,
+    {
+        E::read_signed(self, bits)
+    }
+
+    fn skip(&mut self, mut bits: u32) -> io::Result<()> {
+        while bits > 0 {
+            let chunk = core::cmp::min(bits, 32);
+            self.read::<u32>(chunk)?;
+            bits -= chunk;
+        }
+        Ok(())
+    }
+
+    fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.reader.read_bytes(buf)?;
+        for byte in buf.iter() {
+            self.parity ^= byte.count_ones() % 2 == 1;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn read_unary0(&mut self) -> io::Result<u32> {
+        let unary = self.reader.read_unary0()?;
+        self.parity ^= unary % 2 == 1;
+        Ok(unary)
+    }
+
+    #[inline]
+    fn read_unary1(&mut self) -> io::Result<u32> {
+        let unary = self.reader.read_unary1()?;
+        self.parity = !self.parity;
+        Ok(unary)
+    }
+
+    #[inline]
+    fn byte_aligned(&self) -> bool {
+        self.reader.byte_aligned()
+    }
+
+    #[inline]
+    fn byte_align(&mut self) {
+        self.reader.byte_align()
+    }
+}
+
+impl<R: io::Read, E: Endianness> ByteRead for ParityReader<R, E> {}
+
+/// A bitstream reader whose buffered bits are exposed
+/// as a single word, for entropy decoders which shift bits
+/// into their own registers without a method call per bit.
+///
+/// Bits are pulled from the stream a byte at a time
+/// into an accumulator of up to 64 bits,
+/// which is also drained by the ordinary `BitRead` methods.
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Cursor};
+/// use bitstream_io::{BigEndian, AccumulatorReader, BitRead};
+/// let data = [0b1011_0001, 0b1110_1101, 0b0011_1011];
+/// let mut reader = AccumulatorReader::endian(Cursor::new(&data), BigEndian);
+/// reader.fill_accumulator(12).unwrap();
+/// let (value, count) = reader.accumulator();
+/// assert_eq!(count, 16);
+/// assert_eq!(value >> (count - 3), 0b101);
+/// reader.consume(3);
+/// assert_eq!(reader.read::<u8>(5).unwrap(), 0b10001);
+/// ```
+pub struct AccumulatorReader<R: io::Read, E: Endianness> {
+    reader: R,
+    queue: BitQueue<E, u64>,
+}
+
+impl<R: io::Read, E: Endianness> AccumulatorReader<R, E> {
+    /// Wraps an accumulating reader around something that implements `Read`
+    #[inline]
+    pub fn new(reader: R) -> AccumulatorReader<R, E> {
+        AccumulatorReader {
+            reader,
+            queue: BitQueue::new(),
+        }
+    }
+
+    /// Wraps an accumulating reader around something that implements `Read`
+    /// with the given endianness.
+    #[inline]
+    pub fn endian(reader: R, _endian: E) -> AccumulatorReader<R, E> {
+        AccumulatorReader::new(reader)
+    }
+
+    /// Reads whole bytes from the stream into the accumulator
+    /// until it holds at least `min_bits` bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `min_bits` is greater than 57,
+    /// since the accumulator may not have room for another byte.
+    /// Passes along any I/O error from the underlying stream,
+    /// in which case any bytes read before the error
+    /// remain in the accumulator.
+    pub fn fill_accumulator(&mut self, min_bits: u32) -> io::Result<()> {
+        if min_bits > 57 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits for type read",
+            ));
+        }
+        while self.queue.len() < min_bits {
+            self.queue.push(8, u64::from(read_byte(&mut self.reader)?));
+        }
+        Ok(())
+    }
+
+    /// Returns the accumulator's value and the number of bits it holds.
+    ///
+    /// The next bit in the stream is the most significant
+    /// of those bits for big-endian streams
+    /// and the least significant for little-endian ones.
+    #[inline]
+    pub fn accumulator(&self) -> (u64, u32) {
+        (self.queue.value(), self.queue.len())
+    }
+
+    /// Discards the next `bits` bits from the accumulator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the accumulator holds fewer than `bits` bits.
+    #[inline]
+    pub fn consume(&mut self, bits: u32) {
+        self.queue.drop(bits)
+    }
+
+    /// Unwraps the inner reader,
+    /// discarding any bits in the accumulator
+    #[inline]
+    pub fn into_reader(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: io::Read, E: Endianness> BitRead for AccumulatorReader<R, E> {
+    #[inline]
+    fn read_bit(&mut self) -> io::Result<bool> {
+        self.fill_accumulator(1)?;
+        Ok(self.queue.pop(1) == 1)
+    }
+
+    fn read<U>(&mut self, bits: u32) -> io::Result<U>
+    where
+        U: Numeric,
+    {
+        if bits <= U::bits_size() {
+            self.fill_accumulator(bits.min(57))?;
+            let mut acc = BitQueue::<E, U>::new();
+            let mut remaining = bits;
+            while remaining > 0 {
+                let chunk = remaining.min(8);
+                self.fill_accumulator(chunk)?;
+                acc.push(chunk, U::from_u8(self.queue.pop(chunk) as u8));
+                remaining -= chunk;
+            }
+            Ok(acc.value())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits for type read",
+            ))
+        }
+    }
+
+    #[inline]
+    fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
+    where
+        S: SignedNumeric,
+    {
+        E::read_signed(self, bits)
+    }
+
+    fn skip(&mut self, mut bits: u32) -> io::Result<()> {
+        while bits > 0 {
+            let chunk = bits.min(57);
+            self.fill_accumulator(chunk)?;
+            self.consume(chunk);
+            bits -= chunk;
+        }
+        Ok(())
+    }
+
+    fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        if self.queue.is_empty() {
+            self.reader.read_exact(buf)
+        } else {
+            for byte in buf.iter_mut() {
+                *byte = self.read(8)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn read_unary0(&mut self) -> io::Result<u32> {
+        let mut unary = 0;
+        loop {
+            self.fill_accumulator(1)?;
+            if self.queue.all_1() {
+                unary += self.queue.len();
+                self.queue.clear();
+            } else {
+                return Ok(unary + self.queue.pop_1());
+            }
+        }
+    }
+
+    fn read_unary1(&mut self) -> io::Result<u32> {
+        let mut unary = 0;
+        loop {
+            self.fill_accumulator(1)?;
+            if self.queue.all_0() {
+                unary += self.queue.len();
+                self.queue.clear();
+            } else {
+                return Ok(unary + self.queue.pop_0());
+            }
+        }
+    }
+
+    #[inline]
+    fn byte_aligned(&self) -> bool {
+        self.queue.len() % 8 == 0
+    }
+
+    #[inline]
+    fn byte_align(&mut self) {
+        self.queue.drop(self.queue.len() % 8)
+    }
+}
+
+impl<R: io::Read, E: Endianness> ByteRead for AccumulatorReader<R, E> {}
+
+/// A wrapper around a stream which passes every byte
+/// read from it to a closure, such as a CRC accumulator.
+///
+/// When wrapped by a `BitReader`, a byte is passed to the closure
+/// as soon as any of its bits are needed,
+/// since the bitstream reader pulls whole bytes at a time.
+/// Bits later discarded by `byte_align` belong to a byte
+/// which has already been passed along.
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Cursor};
+/// use bitstream_io::{BigEndian, BitReader, BitRead, ChecksumReader};
+/// let data = [0x01, 0x02, 0x03, 0x04];
+/// let mut sum = 0u32;
+/// {
+///     let mut reader = BitReader::endian(
+///         ChecksumReader::new(Cursor::new(&data), |b| sum += u32::from(b)),
+///         BigEndian,
+///     );
+///     reader.read::<u16>(12).unwrap();
+/// }
+/// assert_eq!(sum, 0x01 + 0x02);
+/// ```
+pub struct ChecksumReader<R, F> {
+    reader: R,
+    f: F,
+}
+
+impl<R: io::Read, F: FnMut(u8)> ChecksumReader<R, F> {
+    /// Wraps a stream, passing each byte read from it to the closure
+    #[inline]
+    pub fn new(reader: R, f: F) -> ChecksumReader<R, F> {
+        ChecksumReader { reader, f }
+    }
+
+    /// Unwraps the stream and the closure
+    #[inline]
+    pub fn into_inner(self) -> (R, F) {
+        (self.reader, self.f)
+    }
+}
+
+impl<R: io::Read, F: FnMut(u8)> io::Read for ChecksumReader<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        buf[0..read].iter().copied().for_each(&mut self.f);
+        Ok(read)
+    }
+}
+
+/// A wrapper around a stream which passes every byte
+/// read from it through a closure, such as to XOR it
+/// with a keystream, before a `BitReader` extracts its bits.
+///
+/// The closure is called exactly once per byte,
+/// in stream order, as each byte is pulled from the stream.
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Cursor};
+/// use bitstream_io::{BigEndian, BitReader, BitRead, TransformReader};
+/// let data = [0x5A ^ 0xB1, 0x5A ^ 0xED];
+/// let mut reader = BitReader::endian(
+///     TransformReader::new(Cursor::new(&data), |b| b ^ 0x5A),
+///     BigEndian,
+/// );
+/// assert_eq!(reader.read::<u16>(12).unwrap(), 0xB1E);
+/// ```
+pub struct TransformReader<R, F> {
+    reader: R,
+    f: F,
+}
+
+impl<R: io::Read, F: FnMut(u8) -> u8> TransformReader<R, F> {
+    /// Wraps a stream, passing each byte read from it through the closure
+    #[inline]
+    pub fn new(reader: R, f: F) -> TransformReader<R, F> {
+        TransformReader { reader, f }
+    }
+
+    /// Unwraps the stream and the closure
+    #[inline]
+    pub fn into_inner(self) -> (R, F) {
+        (self.reader, self.f)
+    }
+}
+
+impl<R: io::Read, F: FnMut(u8) -> u8> io::Read for TransformReader<R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        for byte in buf[0..read].iter_mut() {
+            *byte = (self.f)(*byte);
+        }
+        Ok(read)
+    }
+}
+
+/// An iterator over the length-prefixed frames of a stream.
+///
+/// Each frame is an unsigned `length_bits`-bit length,
+/// in bits, followed by that many bits of payload,
+/// which is handed back as its own bounded reader
+/// as by `BitReader::sub_reader`.
+///
+/// Iteration ends once the stream ends before a frame's length,
+/// taking any bits left over in its final byte as padding
+/// so long as there are fewer than `length_bits` of them.
+/// Since padding can't be told apart from data, leftover bits
+/// which do fill a length are read as another frame,
+/// typically an empty one if the padding is 0s.
+/// A stream which ends partway through a frame's length or payload
+/// yields an error of kind `UnexpectedEof` instead.
+///
+/// Each frame's payload is read into a buffer of its own,
+/// so the wrapped reader's `with_max_alloc` limit applies to it.
+/// A frame over the limit yields an error of kind `InvalidData`
+/// before anything is allocated.
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Cursor};
+/// use bitstream_io::{BigEndian, BitReader, BitRead, FrameReader};
+/// let data = [0b0100_1011, 0b0011_0100];
+/// let reader = BitReader::endian(Cursor::new(&data), BigEndian);
+/// let mut frames = FrameReader::new(reader, 4);
+/// let mut frame = frames.next().unwrap().unwrap();
+/// assert_eq!(frame.read::<u8>(4).unwrap(), 0b1011);
+/// assert!(frame.read_bit().is_err());
+/// let mut frame = frames.next().unwrap().unwrap();
+/// assert_eq!(frame.read::<u8>(3).unwrap(), 0b010);
+/// assert!(frames.next().is_none());
+/// ```
+#[cfg(feature = "alloc")]
+pub struct FrameReader<R: io::Read, E: Endianness> {
+    reader: BitReader<R, E>,
+    length_bits: u32,
+}
+
+#[cfg(feature = "alloc")]
+impl<R: io::Read, E: Endianness> FrameReader<R, E> {
+    /// Wraps a reader whose frames have lengths of the given number of bits
+    ///
+    /// # Panics
+    ///
+    /// Panics if `length_bits` is 0 or larger than 32.
+    #[inline]
+    pub fn new(reader: BitReader<R, E>, length_bits: u32) -> FrameReader<R, E> {
+        assert!(
+            length_bits > 0 && length_bits <= 32,
+            "frame length bits must be between 1 and 32"
+        );
+        FrameReader {
+            reader,
+            length_bits,
+        }
+    }
+
+    /// Unwraps the reader, positioned after the last frame read
+    #[inline]
+    pub fn into_reader(self) -> BitReader<R, E> {
+        self.reader
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<R: io::Read, E: Endianness> Iterator for FrameReader<R, E> {
+    type Item = io::Result<BitReaderTake<BitReader<OwnedBytes, E>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.reader.read_unless_end(self.length_bits) {
+            Ok(Some(length)) => Some(self.reader.sub_reader(u64::from(length))),
+            Ok(None) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+macro_rules! define_crc_reader {
+    ($(#[$attr:meta])* $reader:ident, $input:ident, $t:ty) => {
+        $(#[$attr])*
+        pub struct $reader<R: io::Read, E: Endianness> {
+            reader: BitReader<$input<R>, E>,
+        }
+
+        impl<R: io::Read, E: Endianness> $reader<R, E> {
+            /// Wraps a stream with the given endianness,
+            /// computing a CRC with the given polynomial,
+            /// most-significant bit first and without its leading term,
+            /// starting from the given initial value
+            #[inline]
+            pub fn new(reader: R, _endian: E, poly: $t, init: $t) -> $reader<R, E> {
+                $reader {
+                    reader: BitReader::new($input {
+                        reader,
+                        poly,
+                        init,
+                        crc: init,
+                    }),
+                }
+            }
+
+            /// The CRC of every byte pulled from the stream
+            /// since it was last restarted,
+            /// including any whose bits are still buffered
+            #[inline]
+            pub fn crc(&self) -> $t {
+                self.reader.reader.crc
+            }
+
+            /// Restarts the CRC from its initial value
+            #[inline]
+            pub fn reset_crc(&mut self) {
+                self.reader.reader.crc = self.reader.reader.init;
+            }
+
+            /// Aligns the stream to a whole byte,
+            /// reads a stored `width`-bit CRC,
+            /// and compares it to the CRC of every byte before it.
+            /// The CRC then restarts for any data which follows.
+            ///
+            /// # Errors
+            ///
+            /// Passes along any I/O error from the underlying stream.
+            /// Returns an error of kind `InvalidData` if the stored CRC
+            /// does not match the computed one.
+            /// Returns an error of kind `InvalidInput`, without reading,
+            /// if `width` is not the CRC's own width.
+            pub fn verify_trailing(&mut self, width: u32) -> io::Result<()> {
+                if width != <$t>::BITS {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "CRC width does not match stored CRC",
+                    ));
+                }
+                self.reader.byte_align();
+                let computed = self.crc();
+                let stored: $t = self.reader.read(width)?;
+                // the stored CRC's own bytes don't count toward the next one
+                self.reset_crc();
+                if stored == computed {
+                    Ok(())
+                } else {
+                    Err(unexpected(u32::from(computed), u32::from(stored)))
+                }
+            }
+
+            /// Unwraps the stream.
+            /// Any partially-read byte is lost.
+            #[inline]
+            pub fn into_inner(self) -> R {
+                self.reader.into_reader().reader
+            }
+        }
+
+        impl<R: io::Read, E: Endianness> BitRead for $reader<R, E> {
+            #[inline]
+            fn read_bit(&mut self) -> io::Result<bool> {
+                self.reader.read_bit()
+            }
+
+            #[inline]
+            fn read<U>(&mut self, bits: u32) -> io::Result<U>
+            where
+                U: Numeric,
+            {
+                self.reader.read(bits)
+            }
+
+            #[inline]
+            fn read_signed<S>(&mut self, bits: u32) -> io::Result<S>
+            where
+                S: SignedNumeric,
+            {
+                self.reader.read_signed(bits)
+            }
+
+            #[inline]
+            fn skip(&mut self, bits: u32) -> io::Result<()> {
+                self.reader.skip(bits)
+            }
+
+            #[inline]
+            fn read_bytes(&mut self, buf: &mut [u8]) -> io::Result<()> {
+                self.reader.read_bytes(buf)
+            }
+
+            #[inline]
+            fn read_unary0(&mut self) -> io::Result<u32> {
+                self.reader.read_unary0()
+            }
+
+            #[inline]
+            fn read_unary1(&mut self) -> io::Result<u32> {
+                self.reader.read_unary1()
+            }
+
+            #[inline]
+            fn byte_aligned(&self) -> bool {
+                self.reader.byte_aligned()
+            }
+
+            #[inline]
+            fn byte_align(&mut self) {
+                self.reader.byte_align()
+            }
+        }
+
+        impl<R: io::Read, E: Endianness> ByteRead for $reader<R, E> {}
+
+        /// Updates a CRC with every byte pulled from a stream.
+        struct $input<R> {
+            reader: R,
+            poly: $t,
+            init: $t,
+            crc: $t,
+        }
+
+        impl<R: io::Read> io::Read for $input<R> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                const TOP: $t = 1 << (<$t>::BITS - 1);
+                let read = self.reader.read(buf)?;
+                for &byte in buf[0..read].iter() {
+                    self.crc ^= <$t>::from(byte) << (<$t>::BITS - 8);
+                    for _ in 0..8 {
+                        self.crc = if self.crc & TOP != 0 {
+                            (self.crc << 1) ^ self.poly
+                        } else {
+                            self.crc << 1
+                        };
+                    }
+                }
+                Ok(read)
+            }
+        }
+    };
+}
+
+define_crc_reader!(
+    /// A bitstream reader which keeps a 16-bit CRC
+    /// of every byte consumed, such as FLAC's frame CRC,
+    /// for validating a CRC stored after the data.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor};
+    /// use bitstream_io::{BigEndian, BitRead, Crc16Reader};
+    /// let data = b"123456789\x29\xB1";
+    /// let mut reader = Crc16Reader::new(Cursor::new(data), BigEndian, 0x1021, 0xFFFF);
+    /// let mut payload = [0; 9];
+    /// reader.read_bytes(&mut payload).unwrap();
+    /// assert!(reader.verify_trailing(16).is_ok());
+    /// ```
+    Crc16Reader,
+    Crc16Input,
+    u16
+);
+
+define_crc_reader!(
+    /// A bitstream reader which keeps a 32-bit CRC
+    /// of every byte consumed, such as MPEG-2's section CRC,
+    /// for validating a CRC stored after the data.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Read, Cursor, ErrorKind};
+    /// use bitstream_io::{BigEndian, BitRead, Crc32Reader};
+    /// let data = b"123456789\x03\x76\xE6\xE8";
+    /// let mut reader = Crc32Reader::new(Cursor::new(data), BigEndian, 0x04C1_1DB7, 0xFFFF_FFFF);
+    /// let mut payload = [0; 9];
+    /// reader.read_bytes(&mut payload).unwrap();
+    /// let err = reader.verify_trailing(32).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::InvalidData);
+    /// ```
+    Crc32Reader,
+    Crc32Input,
+    u32
+);
+
+/// A wrapper around a stream which, once the stream ends,
+/// supplies up to a fixed number of 0 bytes before ending itself,
+/// for decoders specified to read 0 bits past the end of their data.
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Cursor};
+/// use bitstream_io::{BigEndian, BitReader, BitRead, ZeroPadded};
+/// let data = [0xFF];
+/// let mut reader = BitReader::endian(ZeroPadded::new(Cursor::new(&data), 1), BigEndian);
+/// assert_eq!(reader.read::<u16>(12).unwrap(), 0xFF0);
+/// assert_eq!(reader.read::<u8>(4).unwrap(), 0);
+/// assert!(reader.read_bit().is_err());
+/// ```
+pub struct ZeroPadded<R> {
+    reader: R,
+    padding: u64,
+    ended: bool,
+}
+
+impl<R: io::Read> ZeroPadded<R> {
+    /// Wraps a stream, allowing up to `max_padding` 0 bytes
+    /// to be read after it ends
+    #[inline]
+    pub fn new(reader: R, max_padding: u64) -> ZeroPadded<R> {
+        ZeroPadded {
+            reader,
+            padding: max_padding,
+            ended: false,
+        }
+    }
+
+    /// Returns the number of 0 bytes which may still be read
+    /// after the stream ends
+    #[inline]
+    pub fn padding_remaining(&self) -> u64 {
+        self.padding
+    }
+
+    /// Unwraps the stream
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: io::Read> io::Read for ZeroPadded<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.ended {
+            match self.reader.read(buf)? {
+                0 if !buf.is_empty() => self.ended = true,
+                read => return Ok(read),
+            }
+        }
+        let padding = core::cmp::min(buf.len() as u64, self.padding) as usize;
+        buf[0..padding].iter_mut().for_each(|b| *b = 0);
+        self.padding -= padding as u64;
+        Ok(padding)
+    }
+}
+
+/// A stream over a byte slice which yields its bytes
+/// from last to first, for formats such as some range coder trailers
+/// which are stored backwards from the end of their data.
+///
+/// Only the order of bytes is reversed.
+/// When wrapped by a `BitReader`, bits are still taken from each byte
+/// in the reader's endianness, so a `BigEndian` reader
+/// reads the last byte's most significant bit first,
+/// then the rest of the last byte, then the second-to-last byte
+/// starting from its most significant bit, and so on.
+///
+/// # Example
+/// ```
+/// use bitstream_io::{BigEndian, BitReader, BitRead, ReverseByteReader};
+/// let data = [0x12, 0x34, 0b1011_0000];
+/// let mut reader = BitReader::endian(ReverseByteReader::new(&data), BigEndian);
+/// assert_eq!(reader.read::<u8>(4).unwrap(), 0b1011);
+/// assert_eq!(reader.read::<u16>(12).unwrap(), 0x034);
+/// assert_eq!(reader.read::<u8>(8).unwrap(), 0x12);
+/// ```
+pub struct ReverseByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ReverseByteReader<'a> {
+    /// Wraps a byte slice, to be read starting from its end
+    #[inline]
+    pub fn new(bytes: &'a [u8]) -> ReverseByteReader<'a> {
+        ReverseByteReader { bytes }
+    }
+
+    /// Returns the bytes not yet read, in their original order
+    #[inline]
+    pub fn remaining(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+impl<'a> io::Read for ReverseByteReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let amount = core::cmp::min(buf.len(), self.bytes.len());
+        let (rest, tail) = self.bytes.split_at(self.bytes.len() - amount);
+        for (output, input) in buf.iter_mut().zip(tail.iter().rev()) {
+            *output = *input;
+        }
+        self.bytes = rest;
+        Ok(amount)
+    }
+}
+
+/// A wrapper around a stream which keeps the bytes
+/// read since a mark, so that a `BitReader` over it
+/// may `mark` a position and later `rewind` to it.
+///
+/// At most `window` bytes are kept.
+/// Consuming more than that since the mark discards it,
+/// so buffering never grows unbounded.
+///
+/// # Example
+/// ```
+/// use std::io::{Read, Cursor};
+/// use bitstream_io::{BigEndian, BitReader, BitRead, RewindReader};
+/// let data = [0xFF, 0x12, 0x34];
+/// let mut reader = BitReader::endian(RewindReader::new(Cursor::new(&data), 4), BigEndian);
+/// reader.mark();
+/// if reader.read::<u8>(8).unwrap() != 0x00 {
+///     reader.rewind().unwrap();
+/// }
+/// assert_eq!(reader.read::<u32>(24).unwrap(), 0xFF_12_34);
+/// ```
+#[cfg(feature = "alloc")]
+pub struct RewindReader<R> {
+    reader: R,
+    window: usize,
+    buffer: Vec<u8>,
+    pos: usize,
+    mark: Option<(u32, u8, u64)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<R: io::Read> RewindReader<R> {
+    /// Wraps a stream, allowing up to `window` bytes
+    /// to be consumed between a mark and its rewind
+    #[inline]
+    pub fn new(reader: R, window: usize) -> RewindReader<R> {
+        RewindReader {
+            reader,
+            window,
+            buffer: Vec::new(),
+            pos: 0,
+            mark: None,
+        }
+    }
+
+    /// Unwraps the stream.
+    /// Any bytes buffered for replay after a rewind are lost.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn mark(&mut self, bits: u32, value: u8, bytes_read: u64) {
+        self.buffer.drain(0..self.pos);
+        self.pos = 0;
+        self.mark = Some((bits, value, bytes_read));
+    }
+
+    fn rewind(&mut self) -> io::Result<(u32, u8, u64)> {
+        match self.mark {
+            Some(mark) => {
+                self.pos = 0;
+                Ok(mark)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no mark within rewind window",
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<R: io::Read> io::Read for RewindReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos < self.buffer.len() {
+            let amount = core::cmp::min(buf.len(), self.buffer.len() - self.pos);
+            buf[0..amount].copy_from_slice(&self.buffer[self.pos..self.pos + amount]);
+            self.pos += amount;
+            if self.mark.is_none() && self.pos == self.buffer.len() {
+                self.buffer.clear();
+                self.pos = 0;
+            }
+            Ok(amount)
+        } else {
+            let read = self.reader.read(buf)?;
+            if self.mark.is_some() {
+                if self.buffer.len() + read > self.window {
+                    self.mark = None;
+                    self.buffer.clear();
+                    self.pos = 0;
+                } else {
+                    self.buffer.extend_from_slice(&buf[0..read]);
+                    self.pos += read;
+                }
+            }
+            Ok(read)
+        }
+    }
+}
+
+/// Lends out an aligned `BitReader`'s stream,
+/// keeping its position up to date.
+struct AlignedBytes<'a, R> {
+    reader: &'a mut R,
+    bytes_read: &'a mut u64,
+}
+
+impl<'a, R: io::Read> io::Read for AlignedBytes<'a, R> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        *self.bytes_read += read as u64;
+        Ok(read)
+    }
+}
+
+/// The bytes of a buffer read by `BitReader::sub_reader`,
+/// handed out to the returned reader.
+#[cfg(feature = "alloc")]
+pub struct OwnedBytes {
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl io::Read for OwnedBytes {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut rest = &self.buf[self.pos..];
+        let read = io::Read::read(&mut rest, buf)?;
+        self.pos += read;
+        Ok(read)
+    }
+}
+
+/// Tallies the bytes handed back by the stream,
+/// so a failed read can report how far it got.
+struct CountingReader<R> {
+    reader: R,
+    bytes: u64,
+}
+
+impl<R: io::Read> CountingReader<R> {
+    #[inline]
+    fn new(reader: R) -> CountingReader<R> {
+        CountingReader { reader, bytes: 0 }
+    }
+}
+
+impl<R: io::Read> io::Read for CountingReader<R> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        self.bytes += read as u64;
+        Ok(read)
+    }
+}
+
+/// Replaces an end-of-stream error with one
+/// noting how much of the requested read was satisfied.
+#[cfg(feature = "std")]
+fn truncated(err: io::Error, requested: u64, received: u64) -> io::Error {
+    if err.kind() == io::ErrorKind::UnexpectedEof {
+        io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            TruncatedRead {
+                requested,
+                received,
+            },
+        )
+    } else {
+        err
+    }
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+fn truncated(err: io::Error, _requested: u64, _received: u64) -> io::Error {
+    err
+}
+
+/// Builds the error for a field which didn't match its expected value.
+#[cfg(feature = "std")]
+fn unexpected(expected: u32, actual: u32) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        UnexpectedValue { expected, actual },
+    )
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+fn unexpected(_expected: u32, _actual: u32) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "unexpected value read")
+}
+
+/// Reads magic bytes, returning an error which holds them
+/// if they don't match.
+#[cfg(feature = "std")]
+fn expect_magic<B: BitRead + ?Sized>(reader: &mut B, magic: &[u8]) -> io::Result<()> {
+    let mut found = alloc::vec![0; magic.len()];
+    reader.read_bytes(&mut found)?;
+    if found == magic {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            MagicMismatch {
+                expected: magic.to_vec(),
+                found,
+            },
+        ))
+    }
+}
+
+#[cfg(not(feature = "std"))]
+fn expect_magic<B: BitRead + ?Sized>(reader: &mut B, magic: &[u8]) -> io::Result<()> {
+    let mut buf = [0; 16];
+    let mut matched = true;
+    for expected in magic.chunks(buf.len()) {
+        let found = &mut buf[0..expected.len()];
+        reader.read_bytes(found)?;
+        matched &= found == expected;
+    }
+    if matched {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unexpected magic bytes",
+        ))
+    }
+}
+
+/// Builds the error for a stream found at an unexpected position.
+#[cfg(feature = "std")]
+fn position_mismatch(expected: u64, actual: u64) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        PositionMismatch { expected, actual },
+    )
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+fn position_mismatch(_expected: u64, _actual: u64) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "stream at unexpected position")
+}
+
+/// Builds the error for a value outside its allowed range.
+#[cfg(feature = "std")]
+fn out_of_range(value: i32, min: i32, max: i32) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        ValueOutOfRange { value, min, max },
+    )
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+fn out_of_range(_value: i32, _min: i32, _max: i32) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "value out of range")
+}
+
+/// Converts an 80-bit extended precision value,
+/// with its explicit integer bit, to the nearest `f64`.
+fn f80_to_f64(bits: u128) -> f64 {
+    let negative = (bits >> 79) & 1 == 1;
+    let exponent = ((bits >> 64) & 0x7FFF) as i32;
+    let mantissa = bits as u64;
+    let magnitude = if exponent == 0x7FFF {
+        if mantissa << 1 == 0 {
+            f64::INFINITY
+        } else {
+            f64::NAN
+        }
+    } else {
+        // value is mantissa * 2 ^ (exponent - bias - 63)
+        let mut value = mantissa as f64;
+        let mut exponent = exponent.max(1) - 16383 - 63;
+        let pow2 = |e: i32| f64::from_bits(((e + 1023) as u64) << 52);
+        while exponent > 1023 {
+            value *= pow2(1023);
+            exponent -= 1023;
+        }
+        while exponent < -1022 {
+            value *= pow2(-1022);
+            exponent += 1022;
+        }
+        value * pow2(exponent)
+    };
+    if negative {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// Shifts whole bytes freshly read from the stream
+/// by the bits remaining in the partial byte,
+/// leaving the final byte's leftover bits in the partial byte.
+fn realign_bytes<E>(buf: &mut [u8], rem: &mut BitQueue<E, u8>)
+where
+    E: Endianness,
+{
+    if !rem.is_empty() {
+        let bits = 8 - rem.len();
+        for b in buf.iter_mut() {
+            let mut next = BitQueue::from_value(*b, 8);
+            let mut byte = *rem;
+            byte.push(bits, next.pop(bits));
+            *b = byte.value();
+            *rem = next;
+        }
     }
 }
 
@@ -574,7 +6439,7 @@ fn skip_aligned<R>(mut reader: R, mut bytes: u32) -> io::Result<()>
 where
     R: io::Read,
 {
-    use std::cmp::min;
+    use core::cmp::min;
 
     /*skip up to 8 bytes at a time
     (unlike with read_aligned, "bytes" may be larger than any native type)*/
@@ -16,7 +16,7 @@
 //!
 //! ```
 //! use std::io::Write;
-//! use bitstream_io::{BigEndian, BitWriter};
+//! use bitstream_io::{BigEndian, BitWriter, BitWrite};
 //!
 //! let mut flac: Vec<u8> = Vec::new();
 //! {
@@ -71,47 +71,53 @@
 
 #![warn(missing_docs)]
 
-use std::io;
+#[cfg(feature = "alloc")]
+use super::huffman::WriteHuffmanTree;
+use super::{
+    fits_bits, fixed_bits, fixed_scale, io, reverse_within, signed_fits, BigEndian, BitQueue,
+    ByteOrder, Endianness, Numeric, SignedNumeric,
+};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 
-use super::{huffman::WriteHuffmanTree, BitQueue, Endianness, Numeric, SignedNumeric};
-
-/// For writing bit values to an underlying stream in a given endianness.
+/// Returns the minimum number of bits needed to write
+/// the given unsigned value, which is 0 for a value of 0.
 ///
-/// Because this only writes whole bytes to the underlying stream,
-/// it is important that output is byte-aligned before the bitstream
-/// writer's lifetime ends.
-/// **Partial bytes will be lost** if the writer is disposed of
-/// before they can be written.
-pub struct BitWriter<W: io::Write, E: Endianness> {
-    writer: W,
-    bitqueue: BitQueue<E, u8>,
+/// # Example
+/// ```
+/// use bitstream_io::bits_required;
+/// assert_eq!(bits_required(0), 0);
+/// assert_eq!(bits_required(1), 1);
+/// assert_eq!(bits_required(5), 3);
+/// assert_eq!(bits_required(u32::MAX), 32);
+/// ```
+#[inline]
+pub fn bits_required(value: u32) -> u32 {
+    32 - value.leading_zeros()
 }
 
-impl<W: io::Write, E: Endianness> BitWriter<W, E> {
-    /// Wraps a BitWriter around something that implements `Write`
-    pub fn new(writer: W) -> BitWriter<W, E> {
-        BitWriter {
-            writer,
-            bitqueue: BitQueue::new(),
-        }
-    }
-
-    /// Wraps a BitWriter around something that implements `Write`
-    /// with the given endianness.
-    pub fn endian(writer: W, _endian: E) -> BitWriter<W, E> {
-        BitWriter {
-            writer,
-            bitqueue: BitQueue::new(),
-        }
-    }
-
-    /// Unwraps internal writer and disposes of BitWriter.
-    /// Any unwritten partial bits are discarded.
-    #[inline]
-    pub fn into_writer(self) -> W {
-        self.writer
-    }
+/// Returns the minimum number of bits needed to write
+/// the given signed value in twos-complement, including its sign bit,
+/// which is 1 for a value of 0.
+///
+/// # Example
+/// ```
+/// use bitstream_io::bits_required_signed;
+/// assert_eq!(bits_required_signed(0), 1);
+/// assert_eq!(bits_required_signed(-1), 1);
+/// assert_eq!(bits_required_signed(1), 2);
+/// assert_eq!(bits_required_signed(-128), 8);
+/// assert_eq!(bits_required_signed(128), 9);
+/// ```
+#[inline]
+pub fn bits_required_signed(value: i32) -> u32 {
+    let magnitude = if value < 0 { !value } else { value };
+    33 - magnitude.leading_zeros()
+}
 
+/// A trait for anything that can write a variable number of
+/// potentially un-aligned values to an output stream
+pub trait BitWrite {
     /// Writes a single bit to the stream.
     /// `true` indicates 1, `false` indicates 0
     ///
@@ -122,7 +128,7 @@ impl<W: io::Write, E: Endianness> BitWriter<W, E> {
     /// # Examples
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{BigEndian, BitWriter};
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
     /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
     /// writer.write_bit(true).unwrap();
     /// writer.write_bit(false).unwrap();
@@ -137,7 +143,7 @@ impl<W: io::Write, E: Endianness> BitWriter<W, E> {
     ///
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{LittleEndian, BitWriter};
+    /// use bitstream_io::{LittleEndian, BitWriter, BitWrite};
     /// let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
     /// writer.write_bit(true).unwrap();
     /// writer.write_bit(true).unwrap();
@@ -149,14 +155,7 @@ impl<W: io::Write, E: Endianness> BitWriter<W, E> {
     /// writer.write_bit(true).unwrap();
     /// assert_eq!(writer.into_writer(), [0b10110111]);
     /// ```
-    pub fn write_bit(&mut self, bit: bool) -> io::Result<()> {
-        self.bitqueue.push(1, if bit { 1 } else { 0 });
-        if self.bitqueue.is_full() {
-            write_byte(&mut self.writer, self.bitqueue.pop(8))
-        } else {
-            Ok(())
-        }
-    }
+    fn write_bit(&mut self, bit: bool) -> io::Result<()>;
 
     /// Writes an unsigned value to the stream using the given
     /// number of bits.
@@ -172,7 +171,7 @@ impl<W: io::Write, E: Endianness> BitWriter<W, E> {
     /// # Examples
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{BigEndian, BitWriter};
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
     /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
     /// writer.write(1, 0b1).unwrap();
     /// writer.write(2, 0b01).unwrap();
@@ -182,7 +181,7 @@ impl<W: io::Write, E: Endianness> BitWriter<W, E> {
     ///
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{LittleEndian, BitWriter};
+    /// use bitstream_io::{LittleEndian, BitWriter, BitWrite};
     /// let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
     /// writer.write(1, 0b1).unwrap();
     /// writer.write(2, 0b11).unwrap();
@@ -192,7 +191,7 @@ impl<W: io::Write, E: Endianness> BitWriter<W, E> {
     ///
     /// ```
     /// use std::io::{Write, sink};
-    /// use bitstream_io::{BigEndian, BitWriter};
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
     /// let mut w = BitWriter::endian(sink(), BigEndian);
     /// assert!(w.write(9, 0u8).is_err());    // can't write  u8 in 9 bits
     /// assert!(w.write(17, 0u16).is_err());  // can't write u16 in 17 bits
@@ -203,31 +202,9 @@ impl<W: io::Write, E: Endianness> BitWriter<W, E> {
     /// assert!(w.write(3, 8).is_err());      // can't write   8 in 3 bits
     /// assert!(w.write(4, 16).is_err());     // can't write  16 in 4 bits
     /// ```
-    pub fn write<U>(&mut self, bits: u32, value: U) -> io::Result<()>
+    fn write<U>(&mut self, bits: u32, value: U) -> io::Result<()>
     where
-        U: Numeric,
-    {
-        if bits > U::bits_size() {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "excessive bits for type written",
-            ))
-        } else if (bits < U::bits_size()) && (value >= (U::one() << bits)) {
-            Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "excessive value for bits written",
-            ))
-        } else if bits < self.bitqueue.remaining_len() {
-            self.bitqueue.push(bits, value.to_u8());
-            Ok(())
-        } else {
-            let mut acc = BitQueue::from_value(value, bits);
-            write_unaligned(&mut self.writer, &mut acc, &mut self.bitqueue)?;
-            write_aligned(&mut self.writer, &mut acc)?;
-            self.bitqueue.push(acc.len(), acc.value().to_u8());
-            Ok(())
-        }
-    }
+        U: Numeric;
 
     /// Writes a twos-complement signed value to the stream
     /// with the given number of bits.
@@ -237,13 +214,14 @@ impl<W: io::Write, E: Endianness> BitWriter<W, E> {
     /// Passes along any I/O error from the underlying stream.
     /// Returns an error if the input type is too small
     /// to hold the given number of bits.
-    /// Returns an error if the value is too large
-    /// to fit the given number of bits.
+    /// Returns an error if the value is outside the range
+    /// of a twos-complement number with the given number of bits,
+    /// in which case nothing is written.
     ///
     /// # Examples
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{BigEndian, BitWriter};
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
     /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
     /// writer.write_signed(4, -5).unwrap();
     /// writer.write_signed(4, 7).unwrap();
@@ -252,53 +230,38 @@ impl<W: io::Write, E: Endianness> BitWriter<W, E> {
     ///
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{LittleEndian, BitWriter};
+    /// use bitstream_io::{LittleEndian, BitWriter, BitWrite};
     /// let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
     /// writer.write_signed(4, 7).unwrap();
     /// writer.write_signed(4, -5).unwrap();
     /// assert_eq!(writer.into_writer(), [0b10110111]);
     /// ```
-    #[inline]
-    pub fn write_signed<S>(&mut self, bits: u32, value: S) -> io::Result<()>
+    fn write_signed<S>(&mut self, bits: u32, value: S) -> io::Result<()>
     where
-        S: SignedNumeric,
-    {
-        E::write_signed(self, bits, value)
-    }
+        S: SignedNumeric;
 
-    /// Writes the entirety of a byte buffer to the stream.
-    /// If the stream is already byte-aligned, it will
-    /// map to a faster `write_all` call.  Otherwise it will
-    /// write bytes individually in 8-bit increments.
+    /// Writes a 32-bit IEEE 754 floating point value to the stream,
+    /// with its bits ordered by the stream's endianness.
     ///
     /// # Errors
     ///
     /// Passes along any I/O error from the underlying stream.
     ///
     /// # Example
-    ///
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{BigEndian, BitWriter};
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
     /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
-    /// writer.write(8, 0x66).unwrap();
-    /// writer.write(8, 0x6F).unwrap();
-    /// writer.write(8, 0x6F).unwrap();
-    /// writer.write_bytes(b"bar").unwrap();
-    /// assert_eq!(writer.into_writer(), b"foobar");
+    /// writer.write_f32(1.5).unwrap();
+    /// assert_eq!(writer.into_writer(), [0x3F, 0xC0, 0x00, 0x00]);
     /// ```
-    pub fn write_bytes(&mut self, buf: &[u8]) -> io::Result<()> {
-        if self.byte_aligned() {
-            self.writer.write_all(buf)
-        } else {
-            for b in buf {
-                self.write(8, *b)?;
-            }
-            Ok(())
-        }
+    #[inline]
+    fn write_f32(&mut self, value: f32) -> io::Result<()> {
+        self.write(32, value.to_bits())
     }
 
-    /// Writes Huffman code for the given symbol to the stream.
+    /// Writes a 64-bit IEEE 754 floating point value to the stream,
+    /// with its bits ordered by the stream's endianness.
     ///
     /// # Errors
     ///
@@ -307,29 +270,40 @@ impl<W: io::Write, E: Endianness> BitWriter<W, E> {
     /// # Example
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{BigEndian, BitWriter};
-    /// use bitstream_io::huffman::compile_write_tree;
-    /// let tree = compile_write_tree(
-    ///     vec![('a', vec![0]),
-    ///          ('b', vec![1, 0]),
-    ///          ('c', vec![1, 1, 0]),
-    ///          ('d', vec![1, 1, 1])]).unwrap();
-    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
-    /// writer.write_huffman(&tree, 'b').unwrap();
-    /// writer.write_huffman(&tree, 'c').unwrap();
-    /// writer.write_huffman(&tree, 'd').unwrap();
-    /// assert_eq!(writer.into_writer(), [0b10110111]);
+    /// use bitstream_io::{LittleEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
+    /// writer.write_f64(-1.5).unwrap();
+    /// assert_eq!(writer.into_writer(), [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF8, 0xBF]);
     /// ```
-    pub fn write_huffman<T>(&mut self, tree: &WriteHuffmanTree<E, T>, symbol: T) -> io::Result<()>
-    where
-        T: Ord + Copy,
-    {
-        for &(bits, value) in tree.get(&symbol) {
-            self.write(bits, value)?;
-        }
-        Ok(())
+    #[inline]
+    fn write_f64(&mut self, value: f64) -> io::Result<()> {
+        self.write(64, value.to_bits())
     }
 
+    /// Writes the entirety of a byte buffer to the stream.
+    /// If the stream is already byte-aligned, it will
+    /// map to a faster `write_all` call.  Otherwise each byte
+    /// is shifted into place behind the partial byte
+    /// and written in chunks.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write(8, 0x66).unwrap();
+    /// writer.write(8, 0x6F).unwrap();
+    /// writer.write(8, 0x6F).unwrap();
+    /// writer.write_bytes(b"bar").unwrap();
+    /// assert_eq!(writer.into_writer(), b"foobar");
+    /// ```
+    fn write_bytes(&mut self, buf: &[u8]) -> io::Result<()>;
+
     /// Writes `value` number of 1 bits to the stream
     /// and then writes a 0 bit.  This field is variably-sized.
     ///
@@ -340,7 +314,7 @@ impl<W: io::Write, E: Endianness> BitWriter<W, E> {
     /// # Examples
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{BigEndian, BitWriter};
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
     /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
     /// writer.write_unary0(0).unwrap();
     /// writer.write_unary0(3).unwrap();
@@ -350,35 +324,46 @@ impl<W: io::Write, E: Endianness> BitWriter<W, E> {
     ///
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{LittleEndian, BitWriter};
+    /// use bitstream_io::{LittleEndian, BitWriter, BitWrite};
     /// let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
     /// writer.write_unary0(0).unwrap();
     /// writer.write_unary0(3).unwrap();
     /// writer.write_unary0(10).unwrap();
     /// assert_eq!(writer.into_writer(), [0b11101110, 0b01111111]);
     /// ```
-    pub fn write_unary0(&mut self, value: u32) -> io::Result<()> {
-        match value {
-            0 => self.write_bit(false),
-            bits @ 1..=31 => self
-                .write(value, (1u32 << bits) - 1)
-                .and_then(|()| self.write_bit(false)),
-            32 => self
-                .write(value, 0xFFFF_FFFFu32)
-                .and_then(|()| self.write_bit(false)),
-            bits @ 32..=63 => self
-                .write(value, (1u64 << bits) - 1)
-                .and_then(|()| self.write_bit(false)),
-            64 => self
-                .write(value, 0xFFFF_FFFF_FFFF_FFFFu64)
-                .and_then(|()| self.write_bit(false)),
-            mut bits => {
-                while bits > 64 {
-                    self.write(64, 0xFFFF_FFFF_FFFF_FFFFu64)?;
-                    bits -= 64;
-                }
-                self.write_unary0(bits)
-            }
+    fn write_unary0(&mut self, value: u32) -> io::Result<()>;
+
+    /// Writes `value` number of 1 bits to the stream
+    /// and then writes a 0 bit, like `write_unary0`,
+    /// but only if `value` is no larger than `max`.
+    /// This guards encoders against emitting enormous codes
+    /// for values computed in error.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underyling stream.
+    /// Returns an error of kind `InvalidInput` if `value`
+    /// exceeds `max`, in which case nothing is written.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_unary0_limited(3, 7).unwrap();
+    /// assert!(writer.write_unary0_limited(8, 7).is_err());
+    /// writer.write_unary0_limited(7, 7).unwrap();
+    /// writer.byte_align().unwrap();
+    /// assert_eq!(writer.into_writer(), [0b1110_1111, 0b1110_0000]);
+    /// ```
+    fn write_unary0_limited(&mut self, value: u32, max: u32) -> io::Result<()> {
+        if value <= max {
+            self.write_unary0(value)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive value for unary code",
+            ))
         }
     }
 
@@ -392,7 +377,7 @@ impl<W: io::Write, E: Endianness> BitWriter<W, E> {
     /// # Example
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{BigEndian, BitWriter};
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
     /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
     /// writer.write_unary1(0).unwrap();
     /// writer.write_unary1(3).unwrap();
@@ -402,104 +387,1129 @@ impl<W: io::Write, E: Endianness> BitWriter<W, E> {
     ///
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{LittleEndian, BitWriter};
+    /// use bitstream_io::{LittleEndian, BitWriter, BitWrite};
     /// let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
     /// writer.write_unary1(0).unwrap();
     /// writer.write_unary1(3).unwrap();
     /// writer.write_unary1(10).unwrap();
     /// assert_eq!(writer.into_writer(), [0b00010001, 0b10000000]);
     /// ```
-    pub fn write_unary1(&mut self, value: u32) -> io::Result<()> {
-        match value {
-            0 => self.write_bit(true),
-            1..=32 => self.write(value, 0u32).and_then(|()| self.write_bit(true)),
-            33..=64 => self.write(value, 0u64).and_then(|()| self.write_bit(true)),
-            mut bits => {
-                while bits > 64 {
-                    self.write(64, 0u64)?;
-                    bits -= 64;
-                }
-                self.write_unary1(bits)
-            }
-        }
-    }
+    fn write_unary1(&mut self, value: u32) -> io::Result<()>;
 
-    /// Returns true if the stream is aligned at a whole byte.
+    /// Writes an unsigned Exp-Golomb code to the stream,
+    /// such as H.264's `ue(v)`,
+    /// consisting of a count of leading 0 bits, a 1 bit,
+    /// and then that many bits of value.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
     ///
     /// # Example
     /// ```
-    /// use std::io::{Write, sink};
-    /// use bitstream_io::{BigEndian, BitWriter};
-    /// let mut writer = BitWriter::endian(sink(), BigEndian);
-    /// assert_eq!(writer.byte_aligned(), true);
-    /// writer.write(1, 0).unwrap();
-    /// assert_eq!(writer.byte_aligned(), false);
-    /// writer.write(7, 0).unwrap();
-    /// assert_eq!(writer.byte_aligned(), true);
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_exp_golomb(0).unwrap();
+    /// writer.write_exp_golomb(1).unwrap();
+    /// writer.write_exp_golomb(2).unwrap();
+    /// writer.write_exp_golomb(3).unwrap();
+    /// writer.byte_align().unwrap();
+    /// assert_eq!(writer.into_writer(), [0b1_010_011_0, 0b0100_0000]);
     /// ```
-    #[inline(always)]
-    pub fn byte_aligned(&self) -> bool {
-        self.bitqueue.is_empty()
+    fn write_exp_golomb(&mut self, value: u32) -> io::Result<()> {
+        let value = u64::from(value) + 1;
+        let zeros = 63 - value.leading_zeros();
+        self.write_unary1(zeros)?;
+        self.write(zeros, value - (1 << zeros))
     }
 
-    /// Pads the stream with 0 bits until it is aligned at a whole byte.
-    /// Does nothing if the stream is already aligned.
+    /// Writes a signed Exp-Golomb code to the stream,
+    /// such as H.264's `se(v)`,
+    /// in which the values 0, 1, -1, 2, -2, ...
+    /// map to the unsigned codes 0, 1, 2, 3, 4, ...
     ///
     /// # Errors
     ///
-    /// Passes along any I/O error from the underyling stream.
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidInput` for `i32::MIN`,
+    /// whose code is too large for a `u32`,
+    /// in which case nothing is written.
     ///
     /// # Example
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{BigEndian, BitWriter};
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
     /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
-    /// writer.write(1, 0).unwrap();
+    /// writer.write_exp_golomb_signed(0).unwrap();
+    /// writer.write_exp_golomb_signed(1).unwrap();
+    /// writer.write_exp_golomb_signed(-1).unwrap();
+    /// writer.write_exp_golomb_signed(2).unwrap();
     /// writer.byte_align().unwrap();
-    /// writer.write(8, 0xFF).unwrap();
-    /// assert_eq!(writer.into_writer(), [0x00, 0xFF]);
+    /// assert_eq!(writer.into_writer(), [0b1_010_011_0, 0b0100_0000]);
     /// ```
-    pub fn byte_align(&mut self) -> io::Result<()> {
-        while !self.byte_aligned() {
-            self.write_bit(false)?;
+    fn write_exp_golomb_signed(&mut self, value: i32) -> io::Result<()> {
+        let value = i64::from(value);
+        let code = if value > 0 { 2 * value - 1 } else { -2 * value };
+        if code <= i64::from(u32::MAX) {
+            self.write_exp_golomb(code as u32)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive value for Exp-Golomb code",
+            ))
         }
-        Ok(())
     }
 
-    /// Consumes writer and returns any un-written partial byte
-    /// as a `(bits, value)` tuple.
+    /// Writes an Elias delta code to the stream,
+    /// consisting of the value's length in bits as an Elias gamma code
+    /// followed by the value's bits after its leading 1.
+    /// Values start at 1.
     ///
-    /// # Examples
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidInput` for 0,
+    /// which has no code, in which case nothing is written.
+    ///
+    /// # Example
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{BigEndian, BitWriter};
-    /// let mut data = Vec::new();
-    /// let (bits, value) = {
-    ///     let mut writer = BitWriter::endian(&mut data, BigEndian);
-    ///     writer.write(15, 0b1010_0101_0101_101).unwrap();
-    ///     writer.into_unwritten()
-    /// };
-    /// assert_eq!(data, [0b1010_0101]);
-    /// assert_eq!(bits, 7);
-    /// assert_eq!(value, 0b0101_101);
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// for value in [1, 2, 3, 4, 8].iter() {
+    ///     writer.write_elias_delta(*value).unwrap();
+    /// }
+    /// writer.byte_align().unwrap();
+    /// assert_eq!(writer.into_writer(), [0b1_0100_010, 0b1_01100_00, 0b100000_00]);
     /// ```
+    fn write_elias_delta(&mut self, value: u32) -> io::Result<()> {
+        if value == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no Elias delta code for zero",
+            ));
+        }
+        let len = 32 - value.leading_zeros();
+        let zeros = 31 - len.leading_zeros();
+        self.write_unary1(zeros)?;
+        self.write(zeros, len - (1 << zeros))?;
+        self.write(len - 1, value - (1 << (len - 1)))
+    }
+
+    /// Writes a Rice code to the stream with the given parameter,
+    /// such as those in FLAC residuals,
+    /// consisting of the value's quotient by `2 ^ k` written
+    /// as by `write_unary1` followed by a `k`-bit remainder.
     ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `k` is larger than 32,
+    /// in which case nothing is written.
+    ///
+    /// # Example
     /// ```
     /// use std::io::Write;
-    /// use bitstream_io::{BigEndian, BitWriter};
-    /// let mut data = Vec::new();
-    /// let (bits, value) = {
-    ///     let mut writer = BitWriter::endian(&mut data, BigEndian);
-    ///     writer.write(8, 0b1010_0101).unwrap();
-    ///     writer.into_unwritten()
-    /// };
-    /// assert_eq!(data, [0b1010_0101]);
-    /// assert_eq!(bits, 0);
-    /// assert_eq!(value, 0);
-    /// ```
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_rice(2, 3).unwrap();
+    /// writer.write_rice(2, 8).unwrap();
+    /// writer.write_rice(2, 0).unwrap();
+    /// writer.byte_align().unwrap();
+    /// assert_eq!(writer.into_writer(), [0b1_11_001_00, 0b1_00_00000]);
+    /// ```
+    fn write_rice(&mut self, k: u32, value: u32) -> io::Result<()> {
+        if k > 32 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits for type written",
+            ));
+        }
+        let value = u64::from(value);
+        self.write_unary1((value >> k) as u32)?;
+        self.write(k, (value & ((1 << k) - 1)) as u32)
+    }
+
+    /// Writes a signed Rice code to the stream with the given parameter,
+    /// in which the values 0, -1, 1, -2, 2, ...
+    /// map to the unsigned values 0, 1, 2, 3, 4, ... as in FLAC.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `k` is larger than 32,
+    /// in which case nothing is written.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_rice_signed(2, -2).unwrap();
+    /// writer.write_rice_signed(2, 4).unwrap();
+    /// writer.write_rice_signed(2, 0).unwrap();
+    /// writer.byte_align().unwrap();
+    /// assert_eq!(writer.into_writer(), [0b1_11_001_00, 0b1_00_00000]);
+    /// ```
+    #[inline]
+    fn write_rice_signed(&mut self, k: u32, value: i32) -> io::Result<()> {
+        self.write_rice(k, ((value << 1) ^ (value >> 31)) as u32)
+    }
+
+    /// Zig-zag encodes a signed value, as in protobuf,
+    /// and writes it as an unsigned value with the given number of bits,
+    /// so that the values 0, -1, 1, -2, 2, ...
+    /// map to the values 0, 1, 2, 3, 4, ...
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `bits` is larger than 32
+    /// or if the encoded value is too large to fit that many bits.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// for value in [0, -1, 1, -2].iter() {
+    ///     writer.write_zigzag(3, *value).unwrap();
+    /// }
+    /// writer.byte_align().unwrap();
+    /// assert_eq!(writer.into_writer(), [0b000_001_01, 0b0_011_0000]);
+    /// ```
+    #[inline]
+    fn write_zigzag(&mut self, bits: u32, value: i32) -> io::Result<()> {
+        self.write(bits, ((value << 1) ^ (value >> 31)) as u32)
+    }
+
+    /// Writes an unsigned value to the stream in Gray code
+    /// with the given number of bits,
+    /// in which successive values differ by a single bit.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `bits` is larger than 32
+    /// or if the value is too large to fit that many bits.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// for value in 0..5 {
+    ///     writer.write_gray(3, value).unwrap();
+    /// }
+    /// writer.byte_align().unwrap();
+    /// assert_eq!(writer.into_writer(), [0b000_001_01, 0b1_010_110_0]);
+    /// ```
+    #[inline]
+    fn write_gray(&mut self, bits: u32, value: u32) -> io::Result<()> {
+        self.write(bits, value ^ (value >> 1))
+    }
+
+    /// Writes an unsigned value to the stream with the given number of bits
+    /// in reverse order, as in some RFID and barcode encodings.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if `bits` is larger than 32
+    /// or if the value is too large to fit that many bits.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_reversed(4, 0b0001).unwrap();
+    /// writer.write_reversed(4, 0b0110).unwrap();
+    /// assert_eq!(writer.into_writer(), [0b1000_0110]);
+    /// ```
+    fn write_reversed(&mut self, bits: u32, value: u32) -> io::Result<()> {
+        if bits > 32 {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits for type written",
+            ))
+        } else if bits < 32 && !fits_bits(bits, value) {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive value for bits written",
+            ))
+        } else {
+            self.write(bits, reverse_within(bits, value))
+        }
+    }
+
+    /// Writes a signed fixed-point number in Q format,
+    /// such as Q1.15, with the given number of integer bits
+    /// (including the sign bit) and fractional bits,
+    /// rounding the value to the nearest representable step.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error if the total number of bits is larger than 64
+    /// or if the value is not representable in the given format,
+    /// in which case nothing is written.
+    ///
+    /// # Example
+    /// ```
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_fixed(1, 15, 0.5).unwrap();
+    /// writer.write_fixed(1, 15, -1.0).unwrap();
+    /// writer.write_fixed(2, 2, -0.5).unwrap();
+    /// writer.byte_align().unwrap();
+    /// assert!(writer.write_fixed(1, 15, 1.0).is_err());
+    /// assert_eq!(writer.into_writer(), [0x40, 0x00, 0x80, 0x00, 0xE0]);
+    /// ```
+    fn write_fixed(&mut self, int_bits: u32, frac_bits: u32, value: f64) -> io::Result<()> {
+        let bits = fixed_bits(int_bits, frac_bits)?;
+        let scaled = value / fixed_scale(frac_bits);
+        let limit = bits.checked_sub(1).map_or(0.0, |b| (1u64 << b) as f64);
+        // rounds half away from zero, without relying on std
+        let rounded = if scaled < 0.0 {
+            scaled - 0.5
+        } else {
+            scaled + 0.5
+        };
+        if rounded > -limit - 1.0 && rounded < limit {
+            self.write_signed(bits, rounded as i64)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive value for fixed-point format",
+            ))
+        }
+    }
+
+    /// Writes Huffman code for the given symbol to the stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidInput` if the symbol
+    /// is not in the tree.
+    ///
+    /// # Example
+    /// ```
+    /// use bitstream_io::{BigEndian, BitCounter, BitWrite};
+    /// use bitstream_io::huffman::compile_write_tree;
+    /// let tree = compile_write_tree::<BigEndian, char>(
+    ///     vec![('a', vec![0]),
+    ///          ('b', vec![1, 0]),
+    ///          ('c', vec![1, 1])]).unwrap();
+    /// let mut counter = BitCounter::new();
+    /// counter.write_huffman(&tree, 'b').unwrap();
+    /// counter.write_huffman(&tree, 'a').unwrap();
+    /// assert_eq!(counter.written(), 3);
+    /// assert!(counter.write_huffman(&tree, 'z').is_err());
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn write_huffman<E, T>(&mut self, tree: &WriteHuffmanTree<E, T>, symbol: T) -> io::Result<()>
+    where
+        E: Endianness,
+        T: Ord + Copy,
+    {
+        match tree.try_get(&symbol) {
+            Some(code) => {
+                for &(bits, value) in code {
+                    self.write(bits, value)?;
+                }
+                Ok(())
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "symbol not in Huffman tree",
+            )),
+        }
+    }
+
+    /// Returns true if the stream is aligned at a whole byte.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Write, sink};
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(sink(), BigEndian);
+    /// assert_eq!(writer.byte_aligned(), true);
+    /// writer.write(1, 0).unwrap();
+    /// assert_eq!(writer.byte_aligned(), false);
+    /// writer.write(7, 0).unwrap();
+    /// assert_eq!(writer.byte_aligned(), true);
+    /// ```
+    fn byte_aligned(&self) -> bool;
+
+    /// Pads the stream with 0 bits until it is aligned at a whole byte.
+    /// Does nothing if the stream is already aligned.
+    /// `BitWriter` may pad with 1 bits instead, as set by `set_padding`.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underyling stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write(1, 0).unwrap();
+    /// writer.byte_align().unwrap();
+    /// writer.write(8, 0xFF).unwrap();
+    /// assert_eq!(writer.into_writer(), [0x00, 0xFF]);
+    /// ```
+    fn byte_align(&mut self) -> io::Result<()>;
+}
+
+/// For writing bit values to an underlying stream in a given endianness.
+///
+/// Because this only writes whole bytes to the underlying stream,
+/// it is important that output is byte-aligned before the bitstream
+/// writer's lifetime ends.
+/// **Partial bytes will be lost** if the writer is disposed of
+/// before they can be written.
+/// Calling `byte_align` or `flush` beforehand ensures they are not.
+/// Padding is not performed on drop because any error
+/// from writing the final byte could not be reported.
+pub struct BitWriter<W: io::Write, E: Endianness> {
+    writer: W,
+    bitqueue: BitQueue<E, u8>,
+    bits_written: u64,
+    padding: bool,
+}
+
+impl<W: io::Write, E: Endianness> BitWriter<W, E> {
+    /// Wraps a BitWriter around something that implements `Write`
+    pub fn new(writer: W) -> BitWriter<W, E> {
+        BitWriter {
+            writer,
+            bitqueue: BitQueue::new(),
+            bits_written: 0,
+            padding: false,
+        }
+    }
+
+    /// Wraps a BitWriter around something that implements `Write`
+    /// with the given endianness.
+    pub fn endian(writer: W, _endian: E) -> BitWriter<W, E> {
+        BitWriter {
+            writer,
+            bitqueue: BitQueue::new(),
+            bits_written: 0,
+            padding: false,
+        }
+    }
+
+    /// Returns the total number of bits written to the stream
+    /// since the writer was constructed,
+    /// including any bits of the partial byte not yet written out.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write(3, 0b101u8).unwrap();
+    /// assert_eq!(writer.bits_written(), 3);
+    /// writer.byte_align().unwrap();
+    /// assert_eq!(writer.bits_written(), 8);
+    /// ```
+    #[inline]
+    pub fn bits_written(&self) -> u64 {
+        self.bits_written
+    }
+
+    /// Returns the number of padding bits `byte_align` would write,
+    /// or 0 if the stream is already byte-aligned.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// assert_eq!(writer.padding_bits(), 0);
+    /// writer.write(3, 0b101u8).unwrap();
+    /// assert_eq!(writer.padding_bits(), 5);
+    /// writer.write(5, 0b10111u8).unwrap();
+    /// assert_eq!(writer.padding_bits(), 0);
+    /// ```
+    #[inline]
+    pub fn padding_bits(&self) -> u32 {
+        self.bitqueue.remaining_len() % 8
+    }
+
+    /// Sets the bit which `byte_align`, `flush` and `into_bytes`
+    /// pad partial bytes with, for formats whose stuffing is 1 bits.
+    /// The default is 0 bits.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.set_padding(true);
+    /// writer.write(3, 0b010u8).unwrap();
+    /// writer.byte_align().unwrap();
+    /// assert_eq!(writer.into_writer(), [0b010_11111]);
+    /// ```
+    #[inline]
+    pub fn set_padding(&mut self, bit: bool) {
+        self.padding = bit;
+    }
+
+    /// Writes padding bits until `bits_written` reaches `total_bits`,
+    /// such as to fill out a fixed-size frame.
+    /// Pads with 0 bits unless set otherwise by `set_padding`.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidInput` if more than
+    /// `total_bits` have already been written,
+    /// in which case nothing is written.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write(4, 0b1011u8).unwrap();
+    /// writer.pad_to(24).unwrap();
+    /// assert_eq!(writer.bits_written(), 24);
+    /// assert!(writer.pad_to(16).is_err());
+    /// assert_eq!(writer.into_writer(), [0b1011_0000, 0, 0]);
+    /// ```
+    pub fn pad_to(&mut self, total_bits: u64) -> io::Result<()> {
+        let mut remaining = total_bits.checked_sub(self.bits_written).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits already written for padding",
+            )
+        })?;
+        while remaining > 0 {
+            let bits = remaining.min(32) as u32;
+            let fill = if self.padding {
+                u32::MAX >> (32 - bits)
+            } else {
+                0
+            };
+            self.write(bits, fill)?;
+            remaining -= u64::from(bits);
+        }
+        Ok(())
+    }
+
+    /// Pads any partial byte with padding bits, writes it,
+    /// and flushes the underlying stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write(12, 0xABCu16).unwrap();
+    /// writer.flush().unwrap();
+    /// assert_eq!(writer.into_writer(), [0xAB, 0xC0]);
+    /// ```
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.byte_align()?;
+        self.writer.flush()
+    }
+
+    /// Writes an unsigned value to the stream using the given
+    /// number of bits, without checking that the value fits.
+    ///
+    /// This is for hot paths where the caller guarantees that
+    /// `bits` is no larger than the value's type and that
+    /// the value has no bits set beyond `bits`.
+    /// Violating either is checked only in debug builds,
+    /// and otherwise produces an unspecified stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_unchecked(4, 0b1011u8).unwrap();
+    /// writer.write_unchecked(12, 0b1110_0101_1010u16).unwrap();
+    /// assert_eq!(writer.into_writer(), [0b1011_1110, 0b0101_1010]);
+    /// ```
+    pub fn write_unchecked<U>(&mut self, bits: u32, value: U) -> io::Result<()>
+    where
+        U: Numeric,
+    {
+        debug_assert!(bits <= U::bits_size());
+        debug_assert!(bits == U::bits_size() || fits_bits(bits, value));
+
+        if bits < self.bitqueue.remaining_len() {
+            self.bitqueue.push(bits, value.to_u8());
+        } else {
+            let mut acc = BitQueue::from_value(value, bits);
+            write_unaligned(&mut self.writer, &mut acc, &mut self.bitqueue)?;
+            write_aligned(&mut self.writer, &mut acc)?;
+            self.bitqueue.push(acc.len(), acc.value().to_u8());
+        }
+        self.bits_written += u64::from(bits);
+        Ok(())
+    }
+
+    /// Unwraps internal writer and disposes of BitWriter.
+    /// Any unwritten partial bits are discarded.
+    #[inline]
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    /// Writes Huffman code for the given symbol to the stream.
+    ///
+    /// # Errors
+    ///
+    /// Passes along any I/O error from the underlying stream.
+    /// Returns an error of kind `InvalidInput` if the symbol
+    /// is not in the tree.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter};
+    /// use bitstream_io::huffman::compile_write_tree;
+    /// let tree = compile_write_tree(
+    ///     vec![('a', vec![0]),
+    ///          ('b', vec![1, 0]),
+    ///          ('c', vec![1, 1, 0]),
+    ///          ('d', vec![1, 1, 1])]).unwrap();
+    /// let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    /// writer.write_huffman(&tree, 'b').unwrap();
+    /// writer.write_huffman(&tree, 'c').unwrap();
+    /// writer.write_huffman(&tree, 'd').unwrap();
+    /// assert_eq!(writer.into_writer(), [0b10110111]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn write_huffman<T>(&mut self, tree: &WriteHuffmanTree<E, T>, symbol: T) -> io::Result<()>
+    where
+        T: Ord + Copy,
+    {
+        BitWrite::write_huffman(self, tree, symbol)
+    }
+
+    /// Consumes writer and returns any un-written partial byte
+    /// as a `(bits, value)` tuple.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut data = Vec::new();
+    /// let (bits, value) = {
+    ///     let mut writer = BitWriter::endian(&mut data, BigEndian);
+    ///     writer.write(15, 0b1010_0101_0101_101).unwrap();
+    ///     writer.into_unwritten()
+    /// };
+    /// assert_eq!(data, [0b1010_0101]);
+    /// assert_eq!(bits, 7);
+    /// assert_eq!(value, 0b0101_101);
+    /// ```
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut data = Vec::new();
+    /// let (bits, value) = {
+    ///     let mut writer = BitWriter::endian(&mut data, BigEndian);
+    ///     writer.write(8, 0b1010_0101).unwrap();
+    ///     writer.into_unwritten()
+    /// };
+    /// assert_eq!(data, [0b1010_0101]);
+    /// assert_eq!(bits, 0);
+    /// assert_eq!(value, 0);
+    /// ```
+    #[inline(always)]
+    pub fn into_unwritten(self) -> (u32, u8) {
+        (self.bitqueue.len(), self.bitqueue.value())
+    }
+}
+
+impl<'a, E: Endianness> BitWriter<&'a mut [u8], E> {
+    /// Builds a writer which fills the given buffer,
+    /// without allocating.
+    /// Once the buffer is full, writing further bytes fails
+    /// with an error of kind `WriteZero`,
+    /// and `bits_written` remains as it was before the failed write.
+    ///
+    /// # Example
+    /// ```
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut buf = [0; 2];
+    /// let mut writer = BitWriter::<_, BigEndian>::from_slice(&mut buf);
+    /// writer.write(12, 0xABCu16).unwrap();
+    /// writer.write(4, 0xDu8).unwrap();
+    /// assert!(writer.write(8, 0xFFu8).is_err());
+    /// assert_eq!(writer.bits_written(), 16);
+    /// assert_eq!(buf, [0xAB, 0xCD]);
+    /// ```
+    #[inline]
+    pub fn from_slice(buf: &'a mut [u8]) -> BitWriter<&'a mut [u8], E> {
+        BitWriter::new(buf)
+    }
+
+    /// Returns the number of complete bytes written to the buffer,
+    /// discarding any partial byte.
+    ///
+    /// # Example
+    /// ```
+    /// use bitstream_io::{LittleEndian, BitWriter, BitWrite};
+    /// let mut buf = [0; 4];
+    /// let mut writer = BitWriter::<_, LittleEndian>::from_slice(&mut buf);
+    /// writer.write(20, 0xABCDEu32).unwrap();
+    /// assert_eq!(writer.into_bytes_written(), 2);
+    /// assert_eq!(&buf[0..2], [0xDE, 0xBC]);
+    /// ```
+    #[inline]
+    pub fn into_bytes_written(self) -> usize {
+        (self.bits_written / 8) as usize
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<E: Endianness> BitWriter<Vec<u8>, E> {
+    /// Builds a writer which accumulates its output in memory,
+    /// for use with `into_bytes`.
+    ///
+    /// # Example
+    /// ```
+    /// use bitstream_io::{BigEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::<_, BigEndian>::to_vec();
+    /// writer.write(4, 0xAu8).unwrap();
+    /// assert_eq!(writer.into_bytes(), [0xA0]);
+    /// ```
+    #[inline]
+    pub fn to_vec() -> BitWriter<Vec<u8>, E> {
+        BitWriter::new(Vec::new())
+    }
+
+    /// Pads any partial byte with padding bits
+    /// and returns all the bytes written.
+    /// Unlike `into_writer`, no partial bits are lost.
+    ///
+    /// # Example
+    /// ```
+    /// use bitstream_io::{LittleEndian, BitWriter, BitWrite};
+    /// let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
+    /// writer.write(12, 0xABCu16).unwrap();
+    /// assert_eq!(writer.into_bytes(), [0xBC, 0x0A]);
+    /// ```
+    pub fn into_bytes(mut self) -> Vec<u8> {
+        if !self.bitqueue.is_empty() {
+            let bits = self.bitqueue.remaining_len();
+            let fill = if self.padding { 0xFF >> (8 - bits) } else { 0 };
+            self.bitqueue.push(bits, fill);
+            self.writer.push(self.bitqueue.pop(8));
+        }
+        self.writer
+    }
+}
+
+impl<W: io::Write, E: Endianness> BitWrite for BitWriter<W, E> {
+    fn write_bit(&mut self, bit: bool) -> io::Result<()> {
+        self.bitqueue.push(1, if bit { 1 } else { 0 });
+        if self.bitqueue.is_full() {
+            write_byte(&mut self.writer, self.bitqueue.pop(8))?;
+        }
+        self.bits_written += 1;
+        Ok(())
+    }
+
+    fn write<U>(&mut self, bits: u32, value: U) -> io::Result<()>
+    where
+        U: Numeric,
+    {
+        if bits > U::bits_size() {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits for type written",
+            ))
+        } else if (bits < U::bits_size()) && !fits_bits(bits, value) {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive value for bits written",
+            ))
+        } else {
+            self.write_unchecked(bits, value)
+        }
+    }
+
+    #[inline]
+    fn write_signed<S>(&mut self, bits: u32, value: S) -> io::Result<()>
+    where
+        S: SignedNumeric,
+    {
+        E::write_signed(self, bits, value)
+    }
+
+    fn write_bytes(&mut self, buf: &[u8]) -> io::Result<()> {
+        if self.byte_aligned() {
+            self.writer.write_all(buf)?;
+        } else {
+            /*shift each byte into place behind the partial byte,
+            writing up to 64 bytes at a time*/
+            let bits = 8 - self.bitqueue.len();
+            let mut chunk = [0; 64];
+            for input in buf.chunks(chunk.len()) {
+                for (output, &b) in chunk.iter_mut().zip(input) {
+                    let mut next = BitQueue::from_value(b, 8);
+                    let mut byte = self.bitqueue;
+                    byte.push(bits, next.pop(bits));
+                    *output = byte.value();
+                    self.bitqueue = next;
+                }
+                self.writer.write_all(&chunk[0..input.len()])?;
+            }
+        }
+        self.bits_written += buf.len() as u64 * 8;
+        Ok(())
+    }
+
+    fn write_unary0(&mut self, value: u32) -> io::Result<()> {
+        match value {
+            0 => self.write_bit(false),
+            bits @ 1..=31 => self
+                .write(value, (1u32 << bits) - 1)
+                .and_then(|()| self.write_bit(false)),
+            32 => self
+                .write(value, 0xFFFF_FFFFu32)
+                .and_then(|()| self.write_bit(false)),
+            bits @ 32..=63 => self
+                .write(value, (1u64 << bits) - 1)
+                .and_then(|()| self.write_bit(false)),
+            64 => self
+                .write(value, 0xFFFF_FFFF_FFFF_FFFFu64)
+                .and_then(|()| self.write_bit(false)),
+            mut bits => {
+                while bits > 64 {
+                    self.write(64, 0xFFFF_FFFF_FFFF_FFFFu64)?;
+                    bits -= 64;
+                }
+                self.write_unary0(bits)
+            }
+        }
+    }
+
+    fn write_unary1(&mut self, value: u32) -> io::Result<()> {
+        match value {
+            0 => self.write_bit(true),
+            1..=32 => self.write(value, 0u32).and_then(|()| self.write_bit(true)),
+            33..=64 => self.write(value, 0u64).and_then(|()| self.write_bit(true)),
+            mut bits => {
+                while bits > 64 {
+                    self.write(64, 0u64)?;
+                    bits -= 64;
+                }
+                self.write_unary1(bits)
+            }
+        }
+    }
+
     #[inline(always)]
-    pub fn into_unwritten(self) -> (u32, u8) {
-        (self.bitqueue.len(), self.bitqueue.value())
+    fn byte_aligned(&self) -> bool {
+        self.bitqueue.is_empty()
+    }
+
+    fn byte_align(&mut self) -> io::Result<()> {
+        while !self.byte_aligned() {
+            self.write_bit(self.padding)?;
+        }
+        Ok(())
+    }
+}
+
+/// For writing bits in a given order within each byte
+/// while splitting multi-byte values in a separately chosen byte order,
+/// such as little-endian bytes of most significant bits first.
+///
+/// A value of `bits` bits is written as 8-bit groups,
+/// with any partial group last, each written in the endianness's bit order.
+/// The combinations of `BigEndian` with `ByteOrder::Big`
+/// and `LittleEndian` with `ByteOrder::Little`
+/// write the same as an ordinary `BitWriter`.
+///
+/// # Example
+/// ```
+/// use std::io::Write;
+/// use bitstream_io::{BigEndian, BitWrite, ByteOrder, OrderedBitWriter};
+/// let mut writer = OrderedBitWriter::new(Vec::new(), BigEndian, ByteOrder::Little);
+/// writer.write(16, 0x1234u16).unwrap();
+/// writer.write(4, 0b1011u8).unwrap();
+/// writer.byte_align().unwrap();
+/// assert_eq!(writer.into_bitwriter().into_writer(), [0x34, 0x12, 0b1011_0000]);
+/// ```
+pub struct OrderedBitWriter<W: io::Write, E: Endianness> {
+    writer: BitWriter<W, E>,
+    byte_order: ByteOrder,
+}
+
+impl<W: io::Write, E: Endianness> OrderedBitWriter<W, E> {
+    /// Wraps a writer with the given bit order within bytes
+    /// and byte order within values
+    #[inline]
+    pub fn new(writer: W, _bit_order: E, byte_order: ByteOrder) -> OrderedBitWriter<W, E> {
+        OrderedBitWriter {
+            writer: BitWriter::new(writer),
+            byte_order,
+        }
+    }
+
+    /// Returns the byte order of multi-byte values
+    #[inline]
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+
+    /// Unwraps the inner bitstream writer
+    #[inline]
+    pub fn into_bitwriter(self) -> BitWriter<W, E> {
+        self.writer
+    }
+
+    /// Writes a value as groups of up to 8 bits,
+    /// writing its most significant group with the given closure
+    /// and the remaining groups as unsigned values
+    fn write_groups<N, F>(&mut self, bits: u32, value: N, most_significant: F) -> io::Result<()>
+    where
+        N: Numeric,
+        F: FnOnce(&mut BitWriter<W, E>, u32, N) -> io::Result<()>,
+    {
+        use core::cmp::min;
+
+        // keeps the low bits of each group
+        let low = |value: N, group: u32| value.to_u8() & (0xFF >> (8 - group));
+
+        match self.byte_order {
+            ByteOrder::Big => {
+                let group = min(8, bits);
+                let mut remaining = bits - group;
+                most_significant(&mut self.writer, group, value >> remaining)?;
+                while remaining > 0 {
+                    let group = min(8, remaining);
+                    remaining -= group;
+                    self.writer.write(group, low(value >> remaining, group))?;
+                }
+            }
+            ByteOrder::Little => {
+                let mut shift = 0;
+                while bits - shift > 8 {
+                    self.writer.write(8, low(value >> shift, 8))?;
+                    shift += 8;
+                }
+                most_significant(&mut self.writer, bits - shift, value >> shift)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: io::Write, E: Endianness> BitWrite for OrderedBitWriter<W, E> {
+    #[inline]
+    fn write_bit(&mut self, bit: bool) -> io::Result<()> {
+        self.writer.write_bit(bit)
+    }
+
+    fn write<U>(&mut self, bits: u32, value: U) -> io::Result<()>
+    where
+        U: Numeric,
+    {
+        if bits > U::bits_size() {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits for type written",
+            ))
+        } else if (bits < U::bits_size()) && !fits_bits(bits, value) {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive value for bits written",
+            ))
+        } else if bits == 0 {
+            Ok(())
+        } else {
+            self.write_groups(bits, value, |w, group, value| w.write(group, value.to_u8()))
+        }
+    }
+
+    fn write_signed<S>(&mut self, bits: u32, value: S) -> io::Result<()>
+    where
+        S: SignedNumeric,
+    {
+        if bits > S::bits_size() {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits for type written",
+            ))
+        } else if !signed_fits(bits, value) {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive value for bits written",
+            ))
+        } else if bits == 0 {
+            Ok(())
+        } else {
+            self.write_groups(bits, value, |w, group, value| w.write_signed(group, value))
+        }
+    }
+
+    #[inline]
+    fn write_bytes(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.writer.write_bytes(buf)
+    }
+
+    #[inline]
+    fn write_unary0(&mut self, value: u32) -> io::Result<()> {
+        self.writer.write_unary0(value)
+    }
+
+    #[inline]
+    fn write_unary1(&mut self, value: u32) -> io::Result<()> {
+        self.writer.write_unary1(value)
+    }
+
+    #[inline]
+    fn byte_aligned(&self) -> bool {
+        self.writer.byte_aligned()
+    }
+
+    #[inline]
+    fn byte_align(&mut self) -> io::Result<()> {
+        self.writer.byte_align()
+    }
+}
+
+/// For counting the number of bits written but generating no output.
+///
+/// This is useful for sizing output ahead of time,
+/// by running an encoder generic over `BitWrite` against a counter
+/// before running it against a real `BitWriter`.
+///
+/// # Example
+/// ```
+/// use bitstream_io::{BitCounter, BitWrite};
+/// let mut counter = BitCounter::new();
+/// counter.write(3, 0b101u8).unwrap();
+/// counter.write_unary0(4).unwrap();
+/// assert_eq!(counter.written(), 8);
+/// counter.write_bit(true).unwrap();
+/// counter.byte_align().unwrap();
+/// assert_eq!(counter.written(), 16);
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct BitCounter {
+    bits: u64,
+}
+
+impl BitCounter {
+    /// Creates a new counter with no bits written
+    #[inline]
+    pub fn new() -> BitCounter {
+        BitCounter { bits: 0 }
+    }
+
+    /// Returns the total number of bits written
+    #[inline]
+    pub fn written(&self) -> u64 {
+        self.bits
+    }
+
+    /// Returns the number of padding bits `byte_align` would count,
+    /// or 0 if the stream is already byte-aligned,
+    /// the same as `BitWriter::padding_bits`.
+    ///
+    /// # Example
+    /// ```
+    /// use bitstream_io::{BitCounter, BitWrite};
+    /// let mut counter = BitCounter::new();
+    /// counter.write(3, 0b101u8).unwrap();
+    /// assert_eq!(counter.padding_bits(), 5);
+    /// counter.write(5, 0b10111u8).unwrap();
+    /// assert_eq!(counter.padding_bits(), 0);
+    /// ```
+    #[inline]
+    pub fn padding_bits(&self) -> u32 {
+        ((8 - self.bits % 8) % 8) as u32
+    }
+}
+
+impl BitWrite for BitCounter {
+    #[inline]
+    fn write_bit(&mut self, _bit: bool) -> io::Result<()> {
+        self.bits += 1;
+        Ok(())
+    }
+
+    fn write<U>(&mut self, bits: u32, value: U) -> io::Result<()>
+    where
+        U: Numeric,
+    {
+        if bits > U::bits_size() {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive bits for type written",
+            ))
+        } else if (bits < U::bits_size()) && !fits_bits(bits, value) {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "excessive value for bits written",
+            ))
+        } else {
+            self.bits += u64::from(bits);
+            Ok(())
+        }
+    }
+
+    #[inline]
+    fn write_signed<S>(&mut self, bits: u32, value: S) -> io::Result<()>
+    where
+        S: SignedNumeric,
+    {
+        BigEndian::write_signed(self, bits, value)
+    }
+
+    #[inline]
+    fn write_bytes(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.bits += buf.len() as u64 * 8;
+        Ok(())
+    }
+
+    #[inline]
+    fn write_unary0(&mut self, value: u32) -> io::Result<()> {
+        self.bits += u64::from(value) + 1;
+        Ok(())
+    }
+
+    #[inline]
+    fn write_unary1(&mut self, value: u32) -> io::Result<()> {
+        self.bits += u64::from(value) + 1;
+        Ok(())
+    }
+
+    #[inline]
+    fn byte_aligned(&self) -> bool {
+        self.bits % 8 == 0
+    }
+
+    #[inline]
+    fn byte_align(&mut self) -> io::Result<()> {
+        self.bits = (self.bits + 7) / 8 * 8;
+        Ok(())
     }
 }
 
@@ -525,7 +1535,7 @@ where
     if rem.is_empty() {
         Ok(())
     } else {
-        use std::cmp::min;
+        use core::cmp::min;
         let bits_to_transfer = min(8 - rem.len(), acc.len());
         rem.push(bits_to_transfer, acc.pop(bits_to_transfer).to_u8());
         if rem.len() == 8 {
@@ -1,3 +1,5 @@
+#![allow(clippy::same_item_push)]
+
 extern crate bitstream_io;
 use bitstream_io::huffman::{compile_read_tree, compile_write_tree, HuffmanTreeError};
 
@@ -104,7 +106,7 @@ fn test_huffman_values() {
 #[test]
 fn test_lengthy_huffman_values() {
     use bitstream_io::huffman::{compile_read_tree, compile_write_tree};
-    use bitstream_io::{BitReader, BitWriter, BE, LE};
+    use bitstream_io::{BitReader, BitWrite, BitWriter, BE, LE};
     use std::io::Cursor;
 
     let max_bits = 70;
@@ -155,3 +157,128 @@ fn test_lengthy_huffman_values() {
         }
     }
 }
+
+#[test]
+fn test_huffman_roundtrip() {
+    use bitstream_io::huffman::WriteHuffmanTree;
+    use bitstream_io::{
+        BigEndian, BitCounter, BitReader, BitWrite, BitWriter, Endianness, LittleEndian, BE, LE,
+    };
+    use std::io::{Cursor, ErrorKind};
+
+    fn encode<W: BitWrite>(w: &mut W, tree: &WriteHuffmanTree<impl Endianness, char>, s: &str) {
+        for c in s.chars() {
+            w.write_huffman(tree, c).unwrap();
+        }
+    }
+
+    let spec = vec![
+        ('a', vec![0]),
+        ('b', vec![1, 0]),
+        ('c', vec![1, 1, 0]),
+        ('d', vec![1, 1, 1]),
+    ];
+    let message = "abacabad";
+
+    let write_tree = compile_write_tree::<BE, char>(spec.clone()).unwrap();
+    let read_tree = compile_read_tree::<BE, char>(spec.clone()).unwrap();
+    let mut counter = BitCounter::new();
+    encode(&mut counter, &write_tree, message);
+    assert_eq!(counter.written(), 14);
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    encode(&mut writer, &write_tree, message);
+    assert_eq!(
+        writer.write_huffman(&write_tree, 'e').unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    writer.byte_align().unwrap();
+    let data = writer.into_writer();
+    assert_eq!(data, [0b0100_1100, 0b1001_1100]);
+    let mut reader = BitReader::endian(Cursor::new(&data), BigEndian);
+    let decoded: String = message
+        .chars()
+        .map(|_| reader.read_huffman(&read_tree).unwrap())
+        .collect();
+    assert_eq!(decoded, message);
+
+    let write_tree = compile_write_tree::<LE, char>(spec.clone()).unwrap();
+    let read_tree = compile_read_tree::<LE, char>(spec).unwrap();
+    let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
+    encode(&mut writer, &write_tree, message);
+    writer.byte_align().unwrap();
+    let data = writer.into_writer();
+    let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    let decoded: String = message
+        .chars()
+        .map(|_| reader.read_huffman(&read_tree).unwrap())
+        .collect();
+    assert_eq!(decoded, message);
+}
+
+#[test]
+fn test_huffman_canonical() {
+    use bitstream_io::huffman::{canonical_codes, compile_read_tree_from_lengths};
+    use bitstream_io::{BitReader, BitWrite, BitWriter, LittleEndian, LE};
+    use std::io::Cursor;
+
+    fn bits(code: u32, len: u32) -> Vec<u8> {
+        (0..len).rev().map(|i| ((code >> i) & 1) as u8).collect()
+    }
+
+    // DEFLATE's fixed literal/length table
+    let lengths: Vec<(u16, u8)> = (0..288)
+        .map(|symbol| match symbol {
+            0..=143 => (symbol, 8),
+            144..=255 => (symbol, 9),
+            256..=279 => (symbol, 7),
+            _ => (symbol, 8),
+        })
+        .collect();
+    let codes = canonical_codes(&lengths).unwrap();
+    assert_eq!(codes.len(), 288);
+    for (symbol, code) in codes.iter() {
+        let expected = match *symbol {
+            s @ 0..=143 => bits(0b0011_0000 + u32::from(s), 8),
+            s @ 144..=255 => bits(0b1_1001_0000 + u32::from(s - 144), 9),
+            s @ 256..=279 => bits(u32::from(s - 256), 7),
+            s => bits(0b1100_0000 + u32::from(s - 280), 8),
+        };
+        assert_eq!(code, &expected, "symbol {}", symbol);
+    }
+
+    let write_tree = compile_write_tree::<LE, u16>(codes).unwrap();
+    let read_tree = compile_read_tree_from_lengths::<LE, u16>(&lengths).unwrap();
+    let message = [0, 143, 144, 255, 256, 279, 280, 287, 65];
+    let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
+    for symbol in message.iter() {
+        writer.write_huffman(&write_tree, *symbol).unwrap();
+    }
+    writer.byte_align().unwrap();
+    let data = writer.into_writer();
+    // symbol 0 is 00110000, packed starting from the least significant bit
+    assert_eq!(data[0], 0b0000_1100);
+    let mut reader = BitReader::endian(Cursor::new(&data), LittleEndian);
+    for symbol in message.iter() {
+        assert_eq!(reader.read_huffman(&read_tree).unwrap(), *symbol);
+    }
+
+    // unused symbols receive no code
+    assert_eq!(
+        canonical_codes(&[('a', 0), ('b', 1), ('c', 0), ('d', 1)]).unwrap(),
+        vec![('b', vec![0]), ('d', vec![1])]
+    );
+
+    // too many short codes, or too few
+    assert_eq!(
+        canonical_codes(&[('a', 1), ('b', 1), ('c', 1)]).unwrap_err(),
+        HuffmanTreeError::Oversubscribed
+    );
+    assert_eq!(
+        canonical_codes(&[('a', 1), ('b', 2), ('c', 2), ('d', 2)]).unwrap_err(),
+        HuffmanTreeError::Oversubscribed
+    );
+    assert!(matches!(
+        compile_read_tree_from_lengths::<LE, char>(&[('a', 1), ('b', 2)]),
+        Err(HuffmanTreeError::MissingLeaf)
+    ));
+}
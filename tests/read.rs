@@ -6,6 +6,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#![allow(clippy::bool_assert_comparison, clippy::unusual_byte_groupings)]
+
 extern crate bitstream_io;
 use std::io::Cursor;
 
@@ -59,7 +61,7 @@ fn test_read_queue_le() {
 
 #[test]
 fn test_reader_be() {
-    use bitstream_io::{BigEndian, BitReader};
+    use bitstream_io::{BigEndian, BitRead, BitReader};
 
     let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
 
@@ -156,7 +158,7 @@ fn test_reader_be() {
 
 #[test]
 fn test_edge_cases_be() {
-    use bitstream_io::{BigEndian, BitReader};
+    use bitstream_io::{BigEndian, BitRead, BitReader};
 
     let data: Vec<u8> = vec![
         0, 0, 0, 0, 255, 255, 255, 255, 128, 0, 0, 0, 127, 255, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -231,7 +233,7 @@ fn test_reader_huffman_be() {
 
 #[test]
 fn test_reader_le() {
-    use bitstream_io::{BitReader, LittleEndian};
+    use bitstream_io::{BitRead, BitReader, LittleEndian};
 
     let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
 
@@ -328,7 +330,7 @@ fn test_reader_le() {
 
 #[test]
 fn test_edge_cases_le() {
-    use bitstream_io::{BitReader, LittleEndian};
+    use bitstream_io::{BitRead, BitReader, LittleEndian};
 
     let data: Vec<u8> = vec![
         0, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 128, 255, 255, 255, 127, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -401,7 +403,7 @@ fn test_reader_huffman_le() {
 
 #[test]
 fn test_reader_io_errors_be() {
-    use bitstream_io::{BigEndian, BitReader};
+    use bitstream_io::{BigEndian, BitRead, BitReader};
     use std::io::ErrorKind;
 
     let actual_data: [u8; 1] = [0xB1];
@@ -474,7 +476,7 @@ fn test_reader_io_errors_be() {
 
 #[test]
 fn test_reader_io_errors_le() {
-    use bitstream_io::{BitReader, LittleEndian};
+    use bitstream_io::{BitRead, BitReader, LittleEndian};
     use std::io::ErrorKind;
 
     let actual_data: [u8; 1] = [0xB1];
@@ -547,7 +549,7 @@ fn test_reader_io_errors_le() {
 
 #[test]
 fn test_reader_bits_errors() {
-    use bitstream_io::{BigEndian, BitReader, LittleEndian};
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
     use std::io::ErrorKind;
     let actual_data = [0u8; 10];
 
@@ -617,3 +619,3638 @@ fn test_reader_bits_errors() {
         ErrorKind::InvalidInput
     );
 }
+
+#[test]
+fn test_reader_generic() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    fn read_header<R: BitRead>(r: &mut R) -> (u8, bool, [u8; 2]) {
+        let value = r.read::<u8>(3).unwrap();
+        assert!(!r.byte_aligned());
+        r.byte_align();
+        let aligned = r.byte_aligned();
+        let mut buf = [0; 2];
+        r.read_bytes(&mut buf).unwrap();
+        (value, aligned, buf)
+    }
+
+    let actual_data: [u8; 3] = [0xB1, 0xED, 0x3B];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(read_header(&mut r), (0b101, true, [0xED, 0x3B]));
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(read_header(&mut r), (0b001, true, [0xED, 0x3B]));
+}
+
+#[test]
+fn test_reader_wide_fields() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let data: [u8; 11] = [
+        0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0, 0x11, 0x22, 0x33,
+    ];
+
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read::<u64>(36).unwrap(), 0x1_2345_6789);
+    assert_eq!(r.read::<u64>(40).unwrap(), 0xAB_CDEF_0112);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0x233);
+
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    assert_eq!(r.read::<u64>(40).unwrap(), 0x9A_7856_3412);
+    assert_eq!(r.read::<u64>(40).unwrap(), 0x22_11F0_DEBC);
+    assert_eq!(r.read::<u8>(8).unwrap(), 0x33);
+}
+
+#[test]
+fn test_reader_signed_minimums() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let data = [0x80, 0, 0, 0, 0, 0, 0, 0];
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read_signed::<i8>(8).unwrap(), -128);
+
+    for bits in 2..=64 {
+        let minimum = -1i64 << (bits - 1);
+
+        let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+        assert_eq!(r.read_signed::<i64>(bits).unwrap(), minimum);
+
+        let le_data = (1u64 << (bits - 1)).to_le_bytes();
+        let mut r = BitReader::endian(Cursor::new(&le_data), LittleEndian);
+        assert_eq!(r.read_signed::<i64>(bits).unwrap(), minimum);
+    }
+}
+
+#[test]
+fn test_reader_signed_wide_negative_one() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let data = [0xFF; 8];
+    for &bits in [33, 48, 64].iter() {
+        let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+        assert_eq!(r.read_signed::<i64>(bits).unwrap(), -1);
+        let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+        assert_eq!(r.read_signed::<i64>(bits).unwrap(), -1);
+    }
+
+    // a 48-bit field followed by a positive 16-bit field
+    let data = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE, 0x7F, 0xFF];
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read_signed::<i64>(48).unwrap(), -2);
+    assert_eq!(r.read_signed::<i64>(16).unwrap(), 0x7FFF);
+}
+
+#[test]
+fn test_reader_signed_full_width() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let data = [0x80, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF];
+
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read_signed::<i32>(32).unwrap(), i32::MIN);
+    assert_eq!(r.read_signed::<i32>(32).unwrap(), -1);
+
+    let data = [0x00, 0x00, 0x00, 0x80, 0xFF, 0xFF, 0xFF, 0xFF];
+
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    assert_eq!(r.read_signed::<i32>(32).unwrap(), i32::MIN);
+    assert_eq!(r.read_signed::<i32>(32).unwrap(), -1);
+}
+
+#[test]
+fn test_reader_signed_zero_bits() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let data = [0b1011_0111];
+
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read_signed::<i8>(0).unwrap(), 0);
+    assert!(r.byte_aligned());
+    assert_eq!(r.read::<u8>(8).unwrap(), 0b1011_0111);
+
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    assert_eq!(r.read_signed::<i8>(0).unwrap(), 0);
+    assert!(r.byte_aligned());
+    assert_eq!(r.read::<u8>(8).unwrap(), 0b1011_0111);
+}
+
+#[test]
+fn test_reader_peek() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.peek::<u32>(32).unwrap(), 0xB1ED3BC1);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b101);
+    assert_eq!(r.peek::<u16>(10).unwrap(), 0b1000111101);
+    assert_eq!(r.read::<u16>(10).unwrap(), 0b1000111101);
+    assert_eq!(r.peek::<u8>(3).unwrap(), 0b101);
+    r.skip(3).unwrap();
+    assert_eq!(
+        r.peek::<u32>(17).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(r.read::<u16>(16).unwrap(), 0b0011101111000001);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.peek::<u32>(32).unwrap(), 0xC13BEDB1);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b001);
+    assert_eq!(r.peek::<u16>(10).unwrap(), 0b01101_10110);
+    assert_eq!(r.read::<u16>(10).unwrap(), 0b01101_10110);
+}
+
+#[test]
+fn test_reader_position() {
+    use bitstream_io::huffman::compile_read_tree;
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 8] = [0xB1, 0xED, 0x3B, 0xC1, 0xFF, 0x0F, 0x00, 0x80];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.read::<u8>(3).unwrap();
+    r.skip(5).unwrap();
+    r.read_bytes(&mut [0; 2]).unwrap();
+    assert_eq!(r.position(), 3 + 5 + 16);
+    r.read_bit().unwrap();
+    r.read_signed::<i8>(4).unwrap();
+    assert_eq!(r.position(), 29);
+    r.byte_align();
+    assert_eq!(r.position(), 32);
+    assert_eq!(r.read_unary0().unwrap(), 8);
+    assert_eq!(r.position(), 41);
+    assert_eq!(r.read_unary1().unwrap(), 3);
+    assert_eq!(r.position(), 45);
+    r.skip(3).unwrap();
+    assert_eq!(r.read_unary1().unwrap(), 8);
+    assert_eq!(r.position(), 57);
+
+    let tree = compile_read_tree(vec![(0, vec![0]), (1, vec![1])]).unwrap();
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.read::<u16>(13).unwrap();
+    r.read_bytes(&mut [0; 2]).unwrap();
+    assert_eq!(r.position(), 29);
+    r.skip(19).unwrap();
+    assert_eq!(r.position(), 48);
+    assert_eq!(r.read_huffman(&tree).unwrap(), 0);
+    assert_eq!(r.position(), 49);
+
+    // failed reads still count the bits they drained from the stream
+    let short_data = [0xB1, 0xED, 0x3B];
+    let mut r = BitReader::endian(Cursor::new(&short_data), BigEndian);
+    r.read::<u8>(4).unwrap();
+    assert!(r.read::<u32>(32).is_err());
+    assert_eq!(r.position(), 24);
+    assert!(r.read_bit().is_err());
+
+    let mut r = BitReader::endian(Cursor::new(&short_data), BigEndian);
+    assert!(r.read_bytes(&mut [0; 4]).is_err());
+    assert_eq!(r.position(), 24);
+
+    let mut r = BitReader::endian(Cursor::new(&short_data), LittleEndian);
+    assert!(r.skip(40).is_err());
+    assert_eq!(r.position(), 24);
+}
+
+#[test]
+fn test_reader_unary_limited() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_unary_limited(0, 4).unwrap(), 1);
+    assert_eq!(r.read_unary_limited(0, 4).unwrap(), 2);
+    assert_eq!(r.read_unary_limited(1, 4).unwrap(), 2);
+    assert_eq!(r.read_unary_limited(1, 0).unwrap(), 0);
+    assert_eq!(r.read_unary_limited(0, 4).unwrap(), 2);
+    assert_eq!(
+        r.read_unary_limited(0, 1).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(r.position(), 14);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read_unary_limited(0, 4).unwrap(), 1);
+    assert_eq!(r.read_unary_limited(1, 4).unwrap(), 2);
+    assert_eq!(
+        r.read_unary_limited(0, 0).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+
+    let mut r = BitReader::endian(Cursor::new(&[0xFF]), BigEndian);
+    assert_eq!(
+        r.read_unary_limited(0, 10).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+}
+
+#[test]
+fn test_reader_unary0_max() {
+    use bitstream_io::{BigEndian, BitRead, BitReader};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_unary0_max(4).unwrap(), 1);
+    assert_eq!(r.read_unary0_max(4).unwrap(), 2);
+    assert_eq!(r.read_unary0_max(4).unwrap(), 0);
+    assert_eq!(r.read_unary0_max(4).unwrap(), 0);
+    assert_eq!(
+        r.read_unary0_max(4).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(r.position(), 11);
+    assert_eq!(r.read_unary0_max(3).unwrap(), 0);
+    assert_eq!(r.read_unary0_max(3).unwrap(), 2);
+    assert_eq!(r.position(), 15);
+    assert_eq!(
+        r.read_unary0_max(0).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(r.position(), 15);
+}
+
+#[test]
+fn test_reader_unary_max_vs_limited() {
+    use bitstream_io::{BigEndian, BitRead, BitReader};
+    use std::io::ErrorKind;
+
+    // three 1 bits, then a stop bit
+    let data = [0b1110_0000];
+
+    // read_unary_limited's max is inclusive
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read_unary_limited(0, 3).unwrap(), 3);
+    assert_eq!(r.position(), 4);
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(
+        r.read_unary_limited(0, 2).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(r.position(), 3);
+
+    // read_unary0_max's max is exclusive
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read_unary0_max(4).unwrap(), 3);
+    assert_eq!(r.position(), 4);
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(
+        r.read_unary0_max(3).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(r.position(), 3);
+}
+
+#[test]
+fn test_reader_bit_flags() {
+    use bitstream_io::{BigEndian, BitRead, BitReader};
+
+    let mut r = BitReader::endian(Cursor::new([0xA5]), BigEndian);
+    let bits: Vec<bool> = (0..8).map(|_| r.read_bit().unwrap()).collect();
+    assert_eq!(bits, [true, false, true, false, false, true, false, true]);
+
+    let mut r = BitReader::endian(Cursor::new([0xA5, 0xC3]), BigEndian);
+    r.read::<u8>(3).unwrap();
+    let mut flags = [false; 10];
+    r.read_bits(&mut flags).unwrap();
+    assert_eq!(
+        flags,
+        [false, false, true, false, true, true, true, false, false, false]
+    );
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b011);
+    assert!(r.read_bits(&mut [false; 1]).is_err());
+}
+
+#[test]
+fn test_reader_seek_bits() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut baseline = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    let mut expected = Vec::new();
+    for _ in 0..32 {
+        expected.push(baseline.read_bit().unwrap());
+    }
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    for &pos in &[13u64, 2, 27, 8, 0, 19] {
+        r.seek_bits(pos).unwrap();
+        assert_eq!(r.position(), pos);
+        for &bit in &expected[pos as usize..(pos as usize + 5).min(32)] {
+            assert_eq!(r.read_bit().unwrap(), bit);
+        }
+    }
+    r.seek_bits(29).unwrap();
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b001);
+    assert!(r.read_bit().is_err());
+
+    let mut baseline = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    baseline.skip(11).unwrap();
+    let expected: u16 = baseline.read(13).unwrap();
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.seek_bits(30).unwrap();
+    r.seek_bits(11).unwrap();
+    assert_eq!(r.read::<u16>(13).unwrap(), expected);
+    r.seek_bits(3).unwrap();
+    assert_eq!(r.read::<u8>(5).unwrap(), 0b10110);
+}
+
+#[test]
+fn test_reader_read_to_vec() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_to_vec(0).unwrap(), vec![]);
+    assert_eq!(r.read_to_vec(2).unwrap(), vec![0xB1, 0xED]);
+    r.read::<u8>(3).unwrap();
+    assert_eq!(r.read_to_vec(1).unwrap(), vec![0b11011110]);
+    assert!(r.read_to_vec(1).is_err());
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.read::<u8>(3).unwrap();
+    assert_eq!(r.read_to_vec(2).unwrap(), vec![0xB6, 0x7D]);
+}
+
+#[test]
+fn test_reader_max_alloc() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, DynamicEndianBitReader};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    // a count this large would abort the test if it were allocated
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian).with_max_alloc(2);
+    assert_eq!(
+        r.read_to_vec(usize::MAX).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(r.read_to_vec(3).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(r.position(), 0);
+    assert_eq!(r.read_to_vec(2).unwrap(), vec![0xB1, 0xED]);
+
+    let mut r = r.take(8);
+    assert_eq!(
+        r.read_to_vec(usize::MAX).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(r.read_to_vec(1).unwrap(), vec![0x3B]);
+
+    let mut r = match DynamicEndianBitReader::big_endian(Cursor::new(&actual_data)) {
+        DynamicEndianBitReader::Big(r) => DynamicEndianBitReader::Big(r.with_max_alloc(1)),
+        r => r,
+    };
+    assert_eq!(r.read_to_vec(2).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(r.read_to_vec(1).unwrap(), vec![0xB1]);
+}
+
+#[test]
+fn test_reader_byte_read() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, ByteRead, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 16] = [
+        0xB1, 0xED, 0x3B, 0xC1, 0xFF, 0x0F, 0x00, 0x80, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE,
+        0xF0,
+    ];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_u8().unwrap(), 0xB1);
+    assert_eq!(r.read_i8().unwrap(), -19);
+    assert_eq!(r.read_u16().unwrap(), 0x3BC1);
+    assert_eq!(r.read_i32().unwrap(), -15_794_048);
+    assert_eq!(r.read_u64().unwrap(), 0x123456789ABCDEF0);
+    assert!(r.read_u8().is_err());
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read_i16().unwrap(), -19_987);
+    r.read_bit().unwrap();
+    assert_eq!(r.read_u32().unwrap_err().kind(), ErrorKind::InvalidInput);
+    r.byte_align();
+    assert_eq!(r.read_u32().unwrap(), 0xC1FF0F00);
+    assert_eq!(r.read_i64().unwrap(), -9_218_247_941_278_745_378);
+}
+
+#[test]
+fn test_reader_bits_to_byte_align() {
+    use bitstream_io::{BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.bits_to_byte_align(), 0);
+    r.read::<u8>(3).unwrap();
+    assert_eq!(r.bits_to_byte_align(), 5);
+    r.read_bit().unwrap();
+    assert_eq!(r.bits_to_byte_align(), 4);
+    r.skip(4).unwrap();
+    assert_eq!(r.bits_to_byte_align(), 0);
+    r.read_unary0().unwrap();
+    assert_eq!(r.bits_to_byte_align() as u64, 8 - r.position() % 8);
+    r.byte_align();
+    assert_eq!(r.bits_to_byte_align(), 0);
+}
+
+#[test]
+fn test_reader_total_bytes_consumed() {
+    use bitstream_io::{BigEndian, BitRead, BitReader};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    let check = |r: &BitReader<_, _>| {
+        assert_eq!(
+            r.total_bytes_consumed() * 8,
+            r.position() + u64::from(r.bits_to_byte_align())
+        )
+    };
+    check(&r);
+    r.read_bit().unwrap();
+    assert_eq!(r.total_bytes_consumed(), 1);
+    check(&r);
+    r.read_unary0().unwrap();
+    check(&r);
+    r.read::<u16>(9).unwrap();
+    assert_eq!(r.total_bytes_consumed(), 2);
+    check(&r);
+    let mut buf = [0; 1];
+    r.read_bytes(&mut buf).unwrap();
+    assert_eq!(r.total_bytes_consumed(), 3);
+    check(&r);
+    r.byte_align();
+    assert_eq!(r.total_bytes_consumed(), 3);
+    check(&r);
+}
+
+#[test]
+fn test_reader_signed_prefixed() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, BitWrite, BitWriter, LittleEndian};
+    use std::io::ErrorKind;
+
+    let fields: [(u32, i64); 8] = [
+        (0, 0),
+        (1, -1),
+        (1, 0),
+        (2, 1),
+        (33, -1 << 32),
+        (48, -1),
+        (64, i64::MIN),
+        (64, i64::MAX),
+    ];
+
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    for &(width, value) in fields.iter() {
+        w.write(7, width).unwrap();
+        if width > 0 {
+            w.write_signed(width, value).unwrap();
+        }
+    }
+    w.write(7, 65u32).unwrap();
+    w.byte_align().unwrap();
+    let data = w.into_writer();
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    for &(_, value) in fields.iter() {
+        assert_eq!(r.read_signed_prefixed(7).unwrap(), value);
+    }
+    assert_eq!(
+        r.read_signed_prefixed(7).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+
+    let mut w = BitWriter::endian(Vec::new(), LittleEndian);
+    for &(width, value) in fields.iter() {
+        w.write(7, width).unwrap();
+        if width > 0 {
+            w.write_signed(width, value).unwrap();
+        }
+    }
+    w.byte_align().unwrap();
+    let data = w.into_writer();
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    for &(_, value) in fields.iter() {
+        assert_eq!(r.read_signed_prefixed(7).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_reader_signed_formats() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian, SignedFormat};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    for &(bits, twos, magnitude) in &[
+        (1, -1, 0),
+        (4, -5, -3),
+        (8, -79, -49),
+        (16, -19_987, -12_781),
+        (32, -1_309_852_735, -837_630_913),
+    ] {
+        let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+        assert_eq!(
+            r.read_signed_with(bits, SignedFormat::TwosComplement)
+                .unwrap(),
+            twos
+        );
+        let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+        assert_eq!(
+            r.read_signed_with(bits, SignedFormat::SignMagnitude)
+                .unwrap(),
+            magnitude
+        );
+    }
+
+    // -0 in sign-magnitude
+    let mut r = BitReader::endian(Cursor::new([0b1000_0000]), BigEndian);
+    assert_eq!(
+        r.read_signed_with(8, SignedFormat::SignMagnitude).unwrap(),
+        0
+    );
+    let mut r = BitReader::endian(Cursor::new([0b1000_0000]), LittleEndian);
+    assert_eq!(
+        r.read_signed_with(8, SignedFormat::SignMagnitude).unwrap(),
+        0
+    );
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(
+        r.read_signed_with(8, SignedFormat::TwosComplement).unwrap(),
+        -79
+    );
+    assert_eq!(
+        r.read_signed_with(8, SignedFormat::SignMagnitude).unwrap(),
+        -109
+    );
+    assert!(r.read_signed_with(33, SignedFormat::SignMagnitude).is_err());
+    assert_eq!(
+        r.read_signed_with(0, SignedFormat::SignMagnitude).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn test_reader_slice() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: &[u8] = &[0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(actual_data, BigEndian);
+    assert_eq!(r.read::<u8>(2).unwrap(), 0b10);
+    r.skip(6).unwrap();
+    assert_eq!(r.read_signed::<i16>(11).unwrap(), -151);
+    assert_eq!(r.position(), 19);
+    assert_eq!(r.read::<u8>(5).unwrap(), 0b11011);
+    r.skip(8).unwrap();
+    assert!(r.read_bit().is_err());
+
+    let mut r = BitReader::endian(actual_data, LittleEndian);
+    r.read::<u16>(16).unwrap();
+    assert_eq!(r.try_into_reader().ok(), Some(&[0x3B, 0xC1][..]));
+}
+
+#[test]
+fn test_reader_read_to_end() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: Vec<u8> = (0..200u8).collect();
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.read::<u8>(8).unwrap();
+    let mut buf = vec![0xFF];
+    assert_eq!(r.read_to_end(&mut buf).unwrap(), 199);
+    assert_eq!(buf[0], 0xFF);
+    assert_eq!(&buf[1..], &actual_data[1..]);
+    assert_eq!(r.position(), 1600);
+    assert_eq!(r.read_to_end(&mut buf).unwrap(), 0);
+
+    let mut expected = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    expected.skip(3).unwrap();
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.skip(3).unwrap();
+    let mut buf = Vec::new();
+    assert_eq!(r.read_to_end(&mut buf).unwrap(), 199);
+    for &b in &buf {
+        assert_eq!(b, expected.read::<u8>(8).unwrap());
+    }
+    assert_eq!(r.bits_to_byte_align(), 5);
+    assert_eq!(r.read::<u8>(5).unwrap(), expected.read::<u8>(5).unwrap());
+}
+
+#[test]
+fn test_reader_byte_align_checked() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 3] = [0b0000_0101, 0b1010_0000, 0xFF];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.byte_align_checked().unwrap();
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b101);
+    r.byte_align_checked().unwrap();
+    assert_eq!(r.read::<u8>(4).unwrap(), 0);
+    assert_eq!(
+        r.byte_align_checked().unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(r.bits_to_byte_align(), 4);
+    r.byte_align();
+    assert_eq!(r.read::<u8>(8).unwrap(), 0xFF);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert!(r.byte_align_checked().is_ok());
+    assert_eq!(r.read::<u8>(5).unwrap(), 0);
+    assert!(r.byte_align_checked().is_err());
+    assert_eq!(r.read::<u8>(4).unwrap(), 0b1011);
+    assert!(r.byte_align_checked().is_err());
+    assert_eq!(r.read::<u8>(2).unwrap(), 0b01);
+    assert!(r.byte_align_checked().is_ok());
+    assert_eq!(r.position(), 16);
+}
+
+#[test]
+fn test_reader_take() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian).take(20);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b101);
+    assert_eq!(r.read_unary0().unwrap(), 1);
+    assert_eq!(r.read_unary1().unwrap(), 2);
+    assert!(r.read_bit().unwrap());
+    r.skip(1).unwrap();
+    assert_eq!(r.bits_remaining(), 10);
+    assert_eq!(
+        r.read_bytes(&mut [0; 2]).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(r.read_signed::<i8>(8).unwrap(), -76);
+    assert_eq!(
+        r.read::<u8>(5).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(
+        r.read_unary0().unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(r.bits_remaining(), 0);
+    let mut r = r.into_inner();
+    assert_eq!(r.position(), 20);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0xBC1);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian).take(24);
+    r.read::<u8>(3).unwrap();
+    r.byte_align();
+    assert_eq!(r.bits_remaining(), 16);
+    let mut buf = [0; 2];
+    r.read_bytes(&mut buf).unwrap();
+    assert_eq!(buf, [0xED, 0x3B]);
+    assert!(r.read_bit().is_err());
+}
+
+#[test]
+fn test_reader_unaligned_bytes() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: Vec<u8> = (0..4096u32).map(|i| (i * 7 + i / 13) as u8).collect();
+
+    for offset in 1..8 {
+        let mut expected = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+        let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+        expected.skip(offset).unwrap();
+        r.skip(offset).unwrap();
+        let mut buf = vec![0; 4095];
+        r.read_bytes(&mut buf).unwrap();
+        for &b in &buf {
+            assert_eq!(b, expected.read::<u8>(8).unwrap());
+        }
+        assert_eq!(r.position(), expected.position());
+        assert_eq!(
+            r.read::<u8>(8 - offset).unwrap(),
+            expected.read::<u8>(8 - offset).unwrap()
+        );
+
+        let mut expected = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+        let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+        expected.skip(offset).unwrap();
+        r.skip(offset).unwrap();
+        let mut buf = vec![0; 4095];
+        r.read_bytes(&mut buf).unwrap();
+        for &b in &buf {
+            assert_eq!(b, expected.read::<u8>(8).unwrap());
+        }
+        assert_eq!(
+            r.read::<u8>(8 - offset).unwrap(),
+            expected.read::<u8>(8 - offset).unwrap()
+        );
+        assert!(r.read_bytes(&mut [0]).is_err());
+    }
+}
+
+#[test]
+fn test_reader_dynamic_endian() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, DynamicEndianBitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    fn read_fields<R: BitRead>(r: &mut R) -> (u8, i16, u32, u32) {
+        let a = r.read(3).unwrap();
+        let b = r.read_signed(10).unwrap();
+        let c = r.read_unary0().unwrap();
+        r.byte_align();
+        let d = r.read(8).unwrap();
+        (a, b, c, d)
+    }
+
+    let mut r = DynamicEndianBitReader::big_endian(Cursor::new(&actual_data));
+    assert!(r.is_big_endian());
+    assert_eq!(
+        read_fields(&mut r),
+        read_fields(&mut BitReader::endian(Cursor::new(&actual_data), BigEndian))
+    );
+    assert_eq!(r.position(), 24);
+
+    let mut r = DynamicEndianBitReader::little_endian(Cursor::new(&actual_data));
+    assert!(!r.is_big_endian());
+    assert_eq!(
+        read_fields(&mut r),
+        read_fields(&mut BitReader::endian(
+            Cursor::new(&actual_data),
+            LittleEndian
+        ))
+    );
+    assert!(r.read_bit().is_err());
+}
+
+#[test]
+fn test_reader_unary_stop_bit() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    for &stop_bit in &[0, 1] {
+        let mut expected = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+        let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+        for _ in 0..6 {
+            let value = if stop_bit == 0 {
+                expected.read_unary0().unwrap()
+            } else {
+                expected.read_unary1().unwrap()
+            };
+            assert_eq!(r.read_unary(stop_bit).unwrap(), value);
+        }
+
+        let mut expected = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+        let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+        for _ in 0..6 {
+            let value = if stop_bit == 0 {
+                expected.read_unary0().unwrap()
+            } else {
+                expected.read_unary1().unwrap()
+            };
+            assert_eq!(r.read_unary(stop_bit).unwrap(), value);
+        }
+    }
+}
+
+#[test]
+fn test_reader_exp_golomb() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, BitWrite, BitWriter};
+    use std::io::ErrorKind;
+
+    // ue(v) codes for 0 through 8, as in H.264 table 9-2
+    let codes: [(u32, u32, u32); 9] = [
+        (1, 0b1, 0),
+        (3, 0b010, 1),
+        (3, 0b011, 2),
+        (5, 0b00100, 3),
+        (5, 0b00101, 4),
+        (5, 0b00110, 5),
+        (5, 0b00111, 6),
+        (7, 0b0001000, 7),
+        (7, 0b0001001, 8),
+    ];
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    for &(bits, code, _) in codes.iter() {
+        w.write(bits, code).unwrap();
+    }
+    w.byte_align().unwrap();
+    let data = w.into_writer();
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    for &(_, _, value) in codes.iter() {
+        assert_eq!(r.read_exp_golomb().unwrap(), value);
+    }
+
+    // u32::MAX is the largest encodable value
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    w.write(32, 0u32).unwrap();
+    w.write_bit(true).unwrap();
+    w.write(32, 0u32).unwrap();
+    w.write(32, 0u32).unwrap();
+    w.write_bit(true).unwrap();
+    w.write(32, 1u32).unwrap();
+    w.byte_align().unwrap();
+    let data = w.into_writer();
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read_exp_golomb().unwrap(), u32::MAX);
+    assert_eq!(
+        r.read_exp_golomb().unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+
+    let mut r = BitReader::endian(Cursor::new([0u8; 5]), BigEndian);
+    assert_eq!(
+        r.read_exp_golomb().unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+}
+
+#[test]
+fn test_reader_exp_golomb_signed() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, BitWrite, BitWriter};
+    use std::io::ErrorKind;
+
+    // se(v) mapping of ue(v) codes 0 through 6, as in H.264 table 9-3
+    let codes: [(u32, u32, i32); 7] = [
+        (1, 0b1, 0),
+        (3, 0b010, 1),
+        (3, 0b011, -1),
+        (5, 0b00100, 2),
+        (5, 0b00101, -2),
+        (5, 0b00110, 3),
+        (5, 0b00111, -3),
+    ];
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    for &(bits, code, _) in codes.iter() {
+        w.write(bits, code).unwrap();
+    }
+    w.byte_align().unwrap();
+    let data = w.into_writer();
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    for &(_, _, value) in codes.iter() {
+        assert_eq!(r.read_exp_golomb_signed().unwrap(), value);
+    }
+
+    // ue(v) values u32::MAX - 2 through u32::MAX
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    for &low in [0xFFFF_FFFDu32, 0xFFFF_FFFE].iter() {
+        w.write(31, 0u32).unwrap();
+        w.write_bit(true).unwrap();
+        w.write(31, low - 0x7FFF_FFFF).unwrap();
+    }
+    w.write(32, 0u32).unwrap();
+    w.write_bit(true).unwrap();
+    w.write(32, 0u32).unwrap();
+    w.byte_align().unwrap();
+    let data = w.into_writer();
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read_exp_golomb_signed().unwrap(), i32::MAX);
+    assert_eq!(r.read_exp_golomb_signed().unwrap(), -i32::MAX);
+    assert_eq!(
+        r.read_exp_golomb_signed().unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn test_reader_leb128() {
+    use bitstream_io::{BitRead, BitReader, ByteRead, LittleEndian};
+    use std::io::ErrorKind;
+
+    let data: Vec<u8> = vec![
+        0x00, 0x7F, 0x80, 0x01, 0xE5, 0x8E, 0x26, // 0, 127, 128, 624485
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01, // u64::MAX
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02, // too large
+    ];
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    assert_eq!(r.read_uleb128().unwrap(), 0);
+    assert_eq!(r.read_uleb128().unwrap(), 127);
+    assert_eq!(r.read_uleb128().unwrap(), 128);
+    assert_eq!(r.read_uleb128().unwrap(), 624485);
+    assert_eq!(r.read_uleb128().unwrap(), u64::MAX);
+    assert_eq!(r.read_uleb128().unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let data: Vec<u8> = vec![
+        0x00, 0x7F, 0x80, 0x7F, 0x3F, 0x40, 0xC0, 0xBB, 0x78, // 0, -1, -128, 63, -64, -123456
+        0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7F, // i64::MIN
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, // i64::MAX
+        0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00, // overlong
+    ];
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    assert_eq!(r.read_sleb128().unwrap(), 0);
+    assert_eq!(r.read_sleb128().unwrap(), -1);
+    assert_eq!(r.read_sleb128().unwrap(), -128);
+    assert_eq!(r.read_sleb128().unwrap(), 63);
+    assert_eq!(r.read_sleb128().unwrap(), -64);
+    assert_eq!(r.read_sleb128().unwrap(), -123456);
+    assert_eq!(r.read_sleb128().unwrap(), i64::MIN);
+    assert_eq!(r.read_sleb128().unwrap(), i64::MAX);
+    assert_eq!(r.read_sleb128().unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let mut r = BitReader::endian(Cursor::new([0x01, 0x01]), LittleEndian);
+    r.read_bit().unwrap();
+    assert_eq!(
+        r.read_uleb128().unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn test_reader_checksum() {
+    use bitstream_io::{BitRead, BitReader, ChecksumReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut seen = Vec::new();
+    let mut r = BitReader::endian(
+        ChecksumReader::new(Cursor::new(&actual_data), |b| seen.push(b)),
+        LittleEndian,
+    );
+    r.read::<u8>(3).unwrap();
+    r.byte_align();
+    r.skip(9).unwrap();
+    let mut buf = [0; 1];
+    r.read_bytes(&mut buf).unwrap();
+    let (_, _) = r.into_reader().into_inner();
+    assert_eq!(seen, [0xB1, 0xED, 0x3B, 0xC1]);
+
+    let mut xor = 0;
+    {
+        let mut r = BitReader::endian(
+            ChecksumReader::new(Cursor::new(&actual_data), |b| xor ^= b),
+            LittleEndian,
+        );
+        r.read_unary1().unwrap();
+    }
+    assert_eq!(xor, 0xB1);
+}
+
+#[test]
+fn test_reader_transform() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, ByteRead, LittleEndian, TransformReader};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+    let keystream = |i: usize| (i as u8).wrapping_mul(0x35) ^ 0xA7;
+    let obfuscated: Vec<u8> = actual_data
+        .iter()
+        .enumerate()
+        .map(|(i, b)| b ^ keystream(i))
+        .collect();
+
+    let mut seen = Vec::new();
+    let mut r = BitReader::endian(
+        TransformReader::new(Cursor::new(&obfuscated), |b| {
+            let clear = b ^ keystream(seen.len());
+            seen.push(b);
+            clear
+        }),
+        BigEndian,
+    );
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b101);
+    assert_eq!(r.read::<u16>(9).unwrap(), 0b1_0001_1110);
+    r.skip(4).unwrap();
+    assert_eq!(r.read_u16().unwrap(), 0x3BC1);
+    assert!(r.read_bit().is_err());
+    let (_, _) = r.into_reader().into_inner();
+    assert_eq!(seen, obfuscated);
+
+    let mut r = BitReader::endian(
+        TransformReader::new(Cursor::new(&actual_data), |b| !b),
+        LittleEndian,
+    );
+    assert_eq!(r.read::<u32>(32).unwrap(), !0xC13B_EDB1);
+}
+
+#[test]
+fn test_reader_signed_overflow() {
+    use bitstream_io::{
+        BigEndian, BitRead, BitReader, BitWrite, BitWriter, LittleEndian, Overflow,
+    };
+
+    let values: [i16; 7] = [-2048, -129, -128, 0, 127, 128, 2047];
+
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    for &v in values.iter() {
+        w.write_signed(12, v).unwrap();
+    }
+    w.byte_align().unwrap();
+    let data = w.into_writer();
+
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    for &v in values.iter() {
+        assert_eq!(
+            r.read_signed_as::<i8>(12, Overflow::Saturating).unwrap(),
+            v.clamp(-128, 127) as i8
+        );
+    }
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    for &v in values.iter() {
+        assert_eq!(
+            r.read_signed_as::<i8>(12, Overflow::Wrapping).unwrap(),
+            v as i8
+        );
+    }
+
+    let mut w = BitWriter::endian(Vec::new(), LittleEndian);
+    w.write_signed(40, -(1i64 << 39)).unwrap();
+    w.write_signed(40, (1i64 << 39) - 1).unwrap();
+    let data = w.into_writer();
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    assert_eq!(
+        r.read_signed_as::<i32>(40, Overflow::Saturating).unwrap(),
+        i32::MIN
+    );
+    assert_eq!(
+        r.read_signed_as::<i64>(40, Overflow::Saturating).unwrap(),
+        (1i64 << 39) - 1
+    );
+    assert!(r.read_signed_as::<i8>(129, Overflow::Wrapping).is_err());
+}
+
+#[test]
+fn test_reader_read_into() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    let mut samples = [0u32; 5];
+    r.read_into(3, &mut samples).unwrap();
+    assert_eq!(samples, [0b101, 0b100, 0b011, 0b110, 0b110]);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    let mut samples = [0u32; 5];
+    r.read_into(3, &mut samples).unwrap();
+    assert_eq!(samples, [0b001, 0b110, 0b110, 0b110, 0b110]);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    let mut samples = [0u16; 3];
+    assert!(r.read_into(12, &mut samples).is_err());
+    assert_eq!(samples, [0xB1E, 0xD3B, 0]);
+}
+
+#[test]
+fn test_reader_align() {
+    use bitstream_io::{BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 8] = [0xB1, 0xED, 0x3B, 0xC1, 0xFF, 0x0F, 0x00, 0x80];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.read::<u16>(10).unwrap();
+    r.align(32).unwrap();
+    assert_eq!(r.position(), 32);
+    r.align(32).unwrap();
+    assert_eq!(r.position(), 32);
+    r.read_bit().unwrap();
+    r.align(3).unwrap();
+    assert_eq!(r.position(), 33);
+    r.align(16).unwrap();
+    assert_eq!(r.read::<u16>(16).unwrap(), 0x8000);
+    assert_eq!(r.align(0).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert!(r.align(8).is_ok());
+}
+
+#[test]
+fn test_reader_debug() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    struct Opaque<'a>(&'a [u8]);
+
+    impl<'a> std::io::Read for Opaque<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.0.read(buf)
+        }
+    }
+
+    let actual_data: [u8; 2] = [0xB1, 0xED];
+
+    let mut r = BitReader::endian(Opaque(&actual_data), BigEndian);
+    assert_eq!(format!("{:?}", r), "BitReader { position: 0, buffered:  }");
+    r.read::<u8>(2).unwrap();
+    assert_eq!(
+        format!("{:?}", r),
+        "BitReader { position: 2, buffered: 110001 }"
+    );
+
+    let mut r = BitReader::endian(Opaque(&actual_data), LittleEndian);
+    r.read::<u16>(10).unwrap();
+    assert_eq!(
+        format!("{:?}", r),
+        "BitReader { position: 10, buffered: 110111 }"
+    );
+}
+
+#[test]
+fn test_reader_truncated() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian, TruncatedRead};
+    use std::io::ErrorKind;
+
+    fn truncated(err: std::io::Error) -> (u64, u64) {
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let t = err
+            .get_ref()
+            .and_then(|e| e.downcast_ref::<TruncatedRead>())
+            .unwrap();
+        (t.requested(), t.received())
+    }
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.skip(5).unwrap();
+    assert_eq!(truncated(r.read::<u32>(32).unwrap_err()), (32, 27));
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.skip(3).unwrap();
+    assert_eq!(truncated(r.skip(40).unwrap_err()), (40, 29));
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    let mut buf = [0; 8];
+    assert_eq!(truncated(r.read_bytes(&mut buf).unwrap_err()), (64, 32));
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.skip(32).unwrap();
+    assert_eq!(truncated(r.read_bit().unwrap_err()), (1, 0));
+}
+
+#[test]
+fn test_reader_bytes_ref() {
+    use bitstream_io::{BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(&actual_data[..], LittleEndian);
+    assert_eq!(r.read_bytes_ref(0).unwrap(), &[]);
+    assert_eq!(r.read::<u8>(4).unwrap(), 0x1);
+    assert_eq!(
+        r.read_bytes_ref(1).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(r.read::<u8>(4).unwrap(), 0xB);
+    assert_eq!(r.read_bytes_ref(2).unwrap(), &[0xED, 0x3B]);
+    assert_eq!(
+        r.read_bytes_ref(2).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(r.read_bytes_ref(1).unwrap(), &[0xC1]);
+    assert_eq!(r.position(), 32);
+}
+
+#[test]
+fn test_reader_has_bits_remaining() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::BufReader;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert!(r.has_bits_remaining().unwrap());
+    assert_eq!(r.position(), 0);
+    r.skip(31).unwrap();
+    assert!(r.has_bits_remaining().unwrap());
+    assert_eq!(r.read_bit().unwrap(), true);
+    assert!(!r.has_bits_remaining().unwrap());
+
+    let mut r = BitReader::endian(BufReader::with_capacity(1, &actual_data[..]), LittleEndian);
+    let mut count = 0;
+    while r.has_bits_remaining().unwrap() {
+        r.read::<u8>(4).unwrap();
+        count += 1;
+    }
+    assert_eq!(count, 8);
+
+    let mut r = BitReader::endian(&[][..], BigEndian);
+    assert!(!r.has_bits_remaining().unwrap());
+}
+
+#[test]
+fn test_reader_enum() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Kind {
+        Data,
+        Ack,
+        Nack,
+        Reset,
+    }
+
+    let table = [
+        (0, Kind::Data),
+        (1, Kind::Ack),
+        (2, Kind::Nack),
+        (3, Kind::Reset),
+    ];
+    let actual_data: [u8; 1] = [0xB1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_enum(2, &table).unwrap(), Kind::Nack);
+    assert_eq!(r.read_enum(2, &table).unwrap(), Kind::Reset);
+    assert_eq!(r.read_enum(2, &table).unwrap(), Kind::Data);
+    assert_eq!(r.read_enum(2, &table).unwrap(), Kind::Ack);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read_enum(2, &table).unwrap(), Kind::Ack);
+    assert_eq!(r.read_enum(2, &table).unwrap(), Kind::Data);
+    assert_eq!(r.read_enum(2, &table).unwrap(), Kind::Reset);
+    assert_eq!(r.read_enum(2, &table).unwrap(), Kind::Nack);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(
+        r.read_enum(2, &table[0..2]).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(r.position(), 2);
+}
+
+#[test]
+fn test_reader_aligned_byte() {
+    use bitstream_io::{BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 2] = [0xB1, 0xED];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read_aligned_byte().unwrap(), 0xB1);
+    assert_eq!(r.read::<u8>(1).unwrap(), 1);
+    assert_eq!(
+        r.read_aligned_byte().unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(r.position(), 9);
+    r.byte_align();
+    assert_eq!(
+        r.read_aligned_byte().unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+}
+
+#[test]
+fn test_reader_state() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, Endianness, LittleEndian};
+
+    fn read_fields<R: BitRead>(r: &mut R) -> (u8, u16, bool, u32) {
+        (
+            r.read(3).unwrap(),
+            r.read(14).unwrap(),
+            r.read_bit().unwrap(),
+            r.read_unary1().unwrap(),
+        )
+    }
+
+    fn check<E: Endianness>(data: &[u8]) {
+        let mut straight = BitReader::<_, E>::new(data);
+        let mut expected = Vec::new();
+        for _ in 0..4 {
+            expected.push(read_fields(&mut straight));
+        }
+
+        let mut resumed = Vec::new();
+        let (mut reader, mut bits, mut value) = BitReader::<_, E>::new(data).into_state();
+        for _ in 0..4 {
+            let mut r = BitReader::<_, E>::from_state(reader, bits, value);
+            resumed.push(read_fields(&mut r));
+            let state = r.into_state();
+            reader = state.0;
+            bits = state.1;
+            value = state.2;
+        }
+        assert_eq!(resumed, expected);
+    }
+
+    let actual_data: [u8; 20] = [
+        0xB1, 0xED, 0x3B, 0xC1, 0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0, 0x01, 0x02, 0x03,
+        0x04, 0x55, 0xAA, 0x0F, 0xF0,
+    ];
+    check::<BigEndian>(&actual_data);
+    check::<LittleEndian>(&actual_data);
+}
+
+#[test]
+fn test_reader_by_ref() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, ByteRead, LittleEndian};
+
+    fn read_nibbles(mut r: impl BitRead) -> (u8, u8) {
+        (r.read(4).unwrap(), r.read(4).unwrap())
+    }
+
+    fn read_word(mut r: impl ByteRead) -> u16 {
+        r.read_u16().unwrap()
+    }
+
+    fn read_nested<R: BitRead>(r: &mut R) -> ((u8, u8), (u8, u8)) {
+        (read_nibbles(&mut *r), read_nibbles(r))
+    }
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(read_nibbles(&mut r), (0xB, 0x1));
+    assert_eq!(read_word(&mut r), 0xED3B);
+    assert_eq!(r.read::<u8>(8).unwrap(), 0xC1);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(read_nested(&mut r), ((0x1, 0xB), (0xD, 0xE)));
+    assert_eq!(r.position(), 16);
+}
+
+#[test]
+fn test_reader_continue_with() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::Read;
+
+    let packets: [&[u8]; 3] = [&[0xB1, 0xED], &[0x3B], &[0xC1]];
+
+    let mut r = BitReader::endian(packets[0], BigEndian);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0xB1E);
+    let (_, mut r) = r.continue_with(packets[1]);
+    assert_eq!(r.read::<u8>(8).unwrap(), 0xD3);
+    let (_, mut r) = r.continue_with(packets[2]);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0xBC1);
+    assert_eq!(r.position(), 32);
+
+    let mut r = BitReader::endian(packets[0], LittleEndian);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0xDB1);
+    let (_, mut r) = r.continue_with(packets[1].chain(packets[2]));
+    assert_eq!(r.read::<u32>(20).unwrap(), 0xC13BE);
+    assert!(r.read_bit().is_err());
+}
+
+#[test]
+fn test_reader_unary_peek_stop() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    // each stop bit is returned again by the following read
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    let mut expected = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    while let Ok(unary) = r.read_unary0_peek_stop() {
+        assert_eq!(expected.read_unary0().unwrap(), unary);
+        assert_eq!(r.position(), expected.position() - 1);
+        assert_eq!(r.read::<u8>(1).unwrap(), 0);
+    }
+    assert_eq!(r.position(), 32);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    let mut expected = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    while let Ok(unary) = r.read_unary1_peek_stop() {
+        assert_eq!(expected.read_unary1().unwrap(), unary);
+        assert_eq!(r.position(), expected.position() - 1);
+        assert_eq!(r.read_bit().unwrap(), true);
+    }
+
+    // a stop bit beginning a byte is left for a byte-aligned read
+    let mut r = BitReader::endian(Cursor::new([0xFF, 0x7F]), BigEndian);
+    assert_eq!(r.read_unary0_peek_stop().unwrap(), 8);
+    assert!(r.byte_aligned());
+    let mut buf = [0; 1];
+    r.read_bytes(&mut buf).unwrap();
+    assert_eq!(buf, [0x7F]);
+}
+
+#[test]
+fn test_reader_bits_iter() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let data = [0b1011_0010];
+
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    let bits: Vec<bool> = r.bits().map(|b| b.unwrap()).collect();
+    assert_eq!(
+        bits,
+        vec![true, false, true, true, false, false, true, false]
+    );
+    assert_eq!(r.bits().next().is_none(), true);
+
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    let bits: Vec<bool> = r.bits().take(4).map(|b| b.unwrap()).collect();
+    assert_eq!(bits, vec![false, true, false, false]);
+    assert_eq!(r.read::<u8>(4).unwrap(), 0b1011);
+}
+
+#[test]
+fn test_reader_f80() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    fn be(data: [u8; 10]) -> f64 {
+        BitReader::endian(Cursor::new(&data), BigEndian)
+            .read_f80()
+            .unwrap()
+    }
+
+    // AIFF COMM chunk sample rates
+    assert_eq!(be([0x40, 0x0E, 0xAC, 0x44, 0, 0, 0, 0, 0, 0]), 44100.0);
+    assert_eq!(be([0x40, 0x0E, 0xBB, 0x80, 0, 0, 0, 0, 0, 0]), 48000.0);
+    assert_eq!(be([0x3F, 0xFF, 0x80, 0, 0, 0, 0, 0, 0, 0]), 1.0);
+    assert_eq!(be([0xC0, 0x00, 0x80, 0, 0, 0, 0, 0, 0, 0]), -2.0);
+    assert_eq!(
+        be([0x3F, 0xFD, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAB]),
+        1.0 / 3.0
+    );
+    assert_eq!(be([0; 10]), 0.0);
+    assert!(be([0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0]).is_sign_negative());
+    assert_eq!(be([0x7F, 0xFF, 0x80, 0, 0, 0, 0, 0, 0, 0]), f64::INFINITY);
+    assert_eq!(
+        be([0xFF, 0xFF, 0x80, 0, 0, 0, 0, 0, 0, 0]),
+        f64::NEG_INFINITY
+    );
+    assert!(be([0x7F, 0xFF, 0xC0, 0, 0, 0, 0, 0, 0, 0]).is_nan());
+    assert_eq!(be([0x7F, 0xFE, 0x80, 0, 0, 0, 0, 0, 0, 0]), f64::INFINITY);
+    assert_eq!(be([0x00, 0x01, 0x80, 0, 0, 0, 0, 0, 0, 0]), 0.0);
+    // the smallest f64 subnormal
+    assert_eq!(
+        be([0x3B, 0xCD, 0x80, 0, 0, 0, 0, 0, 0, 0]),
+        f64::from_bits(1)
+    );
+
+    let data = [0, 0, 0, 0, 0, 0, 0x44, 0xAC, 0x0E, 0x40];
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    assert_eq!(r.read_f80().unwrap(), 44100.0);
+}
+
+#[test]
+fn test_reader_mark_rewind() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian, RewindReader};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    // mark, read 20 bits, rewind and re-read them
+    for skip in 0..8 {
+        let mut r = BitReader::endian(RewindReader::new(Cursor::new(&actual_data), 3), BigEndian);
+        r.skip(skip).unwrap();
+        r.mark();
+        let first = r.read::<u32>(20).unwrap();
+        r.rewind().unwrap();
+        assert_eq!(r.position(), u64::from(skip));
+        assert_eq!(r.read::<u32>(20).unwrap(), first);
+        r.rewind().unwrap();
+        assert_eq!(r.read::<u32>(20).unwrap(), first);
+        let mut c = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+        c.skip(skip + 20).unwrap();
+        assert_eq!(
+            r.read::<u32>(12 - skip).unwrap(),
+            c.read::<u32>(12 - skip).unwrap()
+        );
+    }
+
+    let mut r = BitReader::endian(
+        RewindReader::new(Cursor::new(&actual_data), 4),
+        LittleEndian,
+    );
+    assert_eq!(r.read::<u8>(4).unwrap(), 0x1);
+    r.mark();
+    assert_eq!(r.read::<u32>(20).unwrap(), 0x3BEDB);
+    r.rewind().unwrap();
+    assert_eq!(r.read::<u8>(4).unwrap(), 0xB);
+    // moving the mark forward while replaying
+    r.mark();
+    assert_eq!(r.read::<u32>(16).unwrap(), 0x3BED);
+    r.rewind().unwrap();
+    assert_eq!(r.read::<u32>(24).unwrap(), 0xC13BED);
+    assert!(r.read::<u8>(1).is_err());
+
+    // no mark set
+    let mut r = BitReader::endian(RewindReader::new(Cursor::new(&actual_data), 4), BigEndian);
+    assert!(r.rewind().is_err());
+
+    // window exceeded
+    let mut r = BitReader::endian(RewindReader::new(Cursor::new(&actual_data), 2), BigEndian);
+    r.mark();
+    assert_eq!(r.read::<u32>(17).unwrap(), 0x1_63DA);
+    assert!(r.rewind().is_err());
+    assert_eq!(r.read::<u16>(15).unwrap(), 0x3BC1);
+}
+
+#[test]
+fn test_reader_bytes_aligned() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+    let mut buf = [0; 2];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.read_bytes_aligned(&mut buf).unwrap();
+    assert_eq!(buf, [0xB1, 0xED]);
+    assert_eq!(r.read::<u8>(1).unwrap(), 0);
+    let err = r.read_bytes_aligned(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(r.position(), 17);
+    r.byte_align();
+    assert!(r.read_bytes_aligned(&mut buf[..1]).is_ok());
+    assert_eq!(buf[0], 0xC1);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.read_bytes_aligned(&mut buf).unwrap();
+    assert_eq!(buf, [0xB1, 0xED]);
+    assert_eq!(r.read::<u8>(1).unwrap(), 1);
+    assert!(r.read_bytes_aligned(&mut buf).is_err());
+
+    // a take adapter reports the alignment of its inner reader
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian).take(32);
+    r.skip(8).unwrap();
+    r.read_bytes_aligned(&mut buf).unwrap();
+    assert_eq!(buf, [0xED, 0x3B]);
+}
+
+#[test]
+fn test_reader_skip_to_end() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let data = vec![0xA5u8; 10_000];
+
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.skip_to_end().unwrap(), 80_000);
+    assert_eq!(r.position(), 80_000);
+    assert_eq!(r.skip_to_end().unwrap(), 0);
+
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    r.skip(13).unwrap();
+    assert_eq!(r.skip_to_end().unwrap(), 80_000 - 13);
+    assert!(r.read_bit().is_err());
+
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    r.read::<u8>(5).unwrap();
+    assert_eq!(r.skip_to_end().unwrap(), 80_000 - 5);
+    assert_eq!(r.position(), 80_000);
+}
+
+#[test]
+fn test_reader_length_prefixed() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    // 4-bit length of 5, followed by a 5-bit value
+    let data = [0b0101_1011, 0b0000_0000];
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read_length_prefixed(4).unwrap(), (5, 0b10110));
+    assert_eq!(r.position(), 9);
+    assert_eq!(r.read_length_prefixed(4).unwrap(), (0, 0));
+    assert_eq!(r.position(), 13);
+
+    let data = [0b1011_0101, 0b0000_0001];
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    assert_eq!(r.read_length_prefixed(4).unwrap(), (5, 0b11011));
+    assert_eq!(r.position(), 9);
+
+    // full-width values
+    let data = [0b1000_0011, 0xFF, 0xFF, 0xFF, 0b1111_1100];
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read_length_prefixed(6).unwrap(), (32, 0xFFFF_FFFF));
+
+    // lengths wider than 32 bits are rejected
+    let data = [0b1000_0100, 0x00];
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(
+        r.read_length_prefixed(6).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn test_reader_last_read_span() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.last_read_span(), (0, 0));
+    r.skip(6).unwrap();
+    assert_eq!(r.last_read_span(), (0, 0));
+    r.read::<u16>(10).unwrap();
+    assert_eq!(r.last_read_span(), (6, 16));
+    r.read::<u8>(2).unwrap();
+    assert_eq!(r.last_read_span(), (16, 18));
+    r.read_signed::<i8>(5).unwrap();
+    assert_eq!(r.last_read_span(), (18, 23));
+    r.read_bit().unwrap();
+    assert_eq!(r.last_read_span(), (18, 23));
+    let mut buf = [0; 1];
+    r.read_bytes(&mut buf).unwrap();
+    assert_eq!(r.last_read_span(), (24, 32));
+    assert!(r.read::<u8>(1).is_err());
+    assert!(r.read_signed::<i8>(2).is_err());
+    assert_eq!(r.last_read_span(), (24, 32));
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.read::<u32>(3).unwrap();
+    r.read_signed::<i32>(27).unwrap();
+    assert_eq!(r.last_read_span(), (3, 30));
+
+    // lookahead doesn't count as a read
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.read::<u8>(3).unwrap();
+    assert_eq!(r.peek::<u16>(12).unwrap(), 0b1000_1111_0110);
+    assert_eq!(r.last_read_span(), (0, 3));
+    assert!(r.peek::<u32>(32).is_err());
+    assert_eq!(r.last_read_span(), (0, 3));
+    r.find_sync(0x3B, 8, true).unwrap();
+    assert_eq!(r.position(), 16);
+    assert_eq!(r.last_read_span(), (0, 3));
+    assert!(r.find_sync(0xFF, 8, false).is_err());
+    assert_eq!(r.last_read_span(), (0, 3));
+}
+
+#[test]
+fn test_reader_bits_to_bytes() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, Justify, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    // a 12-bit field both ways
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    let mut out = vec![0xFF];
+    r.read_bits_to_bytes(12, Justify::Left, &mut out).unwrap();
+    assert_eq!(out, [0xFF, 0xB1, 0xE0]);
+    out.clear();
+    r.read_bits_to_bytes(12, Justify::Right, &mut out).unwrap();
+    assert_eq!(out, [0x0D, 0x3B]);
+    assert_eq!(r.position(), 24);
+
+    // bits are packed in stream order
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    let mut out = Vec::new();
+    r.read_bits_to_bytes(12, Justify::Left, &mut out).unwrap();
+    assert_eq!(out, [0x8D, 0xB0]);
+    out.clear();
+    r.read_bits_to_bytes(4, Justify::Right, &mut out).unwrap();
+    assert_eq!(out, [0x07]);
+
+    // whole bytes need no padding
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.skip(4).unwrap();
+    let mut left = Vec::new();
+    r.read_bits_to_bytes(24, Justify::Left, &mut left).unwrap();
+    assert_eq!(left, [0x1E, 0xD3, 0xBC]);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    let mut out = Vec::new();
+    r.read_bits_to_bytes(0, Justify::Right, &mut out).unwrap();
+    assert!(out.is_empty());
+
+    // a failed read appends nothing
+    assert!(r.read_bits_to_bytes(33, Justify::Left, &mut out).is_err());
+    assert!(out.is_empty());
+
+    // the allocation limit is checked before anything is read
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian).with_max_alloc(2);
+    assert_eq!(
+        r.read_bits_to_bytes(u32::MAX, Justify::Left, &mut out)
+            .unwrap_err()
+            .kind(),
+        std::io::ErrorKind::InvalidData
+    );
+    assert!(r.read_bits_to_bytes(17, Justify::Right, &mut out).is_err());
+    assert!(out.is_empty());
+    assert_eq!(r.position(), 0);
+
+    // a huge count only allocates as bytes arrive
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    let mut out = Vec::new();
+    assert_eq!(
+        r.read_bits_to_bytes(u32::MAX, Justify::Left, &mut out)
+            .unwrap_err()
+            .kind(),
+        std::io::ErrorKind::UnexpectedEof
+    );
+    assert!(out.is_empty());
+    assert!(out.capacity() <= 4096);
+
+    // and fields spanning several chunks are read whole
+    let data = (0..10_000u32).map(|i| i as u8).collect::<Vec<u8>>();
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    r.read_bits_to_bytes(80_000, Justify::Left, &mut out)
+        .unwrap();
+    assert_eq!(out, data);
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    r.skip(4).unwrap();
+    out.clear();
+    r.read_bits_to_bytes(79_996, Justify::Right, &mut out)
+        .unwrap();
+    assert_eq!(out, data);
+
+    // other readers pack bits the same way
+    use bitstream_io::ParityReader;
+    for &(skip, bits) in [(0, 12), (3, 13), (4, 24), (5, 8), (1, 7)].iter() {
+        for &justify in [Justify::Left, Justify::Right].iter() {
+            let mut plain = Vec::new();
+            let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+            r.skip(skip).unwrap();
+            r.read_bits_to_bytes(bits, justify, &mut plain).unwrap();
+            let mut wrapped = Vec::new();
+            let mut r = ParityReader::new(BitReader::endian(Cursor::new(&actual_data), BigEndian));
+            r.skip(skip).unwrap();
+            r.read_bits_to_bytes(bits, justify, &mut wrapped).unwrap();
+            assert_eq!(plain, wrapped);
+
+            let mut plain = Vec::new();
+            let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+            r.skip(skip).unwrap();
+            r.read_bits_to_bytes(bits, justify, &mut plain).unwrap();
+            let mut wrapped = Vec::new();
+            let mut r =
+                ParityReader::new(BitReader::endian(Cursor::new(&actual_data), LittleEndian));
+            r.skip(skip).unwrap();
+            r.read_bits_to_bytes(bits, justify, &mut wrapped).unwrap();
+            assert_eq!(plain, wrapped);
+        }
+    }
+    let mut r = ParityReader::new(BitReader::endian(Cursor::new(&actual_data), BigEndian));
+    let mut out = vec![0xFF];
+    assert!(r.read_bits_to_bytes(33, Justify::Right, &mut out).is_err());
+    assert_eq!(out, [0xFF]);
+}
+
+#[test]
+fn test_reader_expect() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian, UnexpectedValue};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert!(r.expect(12, 0xB1E).is_ok());
+    assert!(r.expect(0, 0).is_ok());
+    let err = r.expect(4, 0xC).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let unexpected = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<UnexpectedValue>()
+        .unwrap();
+    assert_eq!(unexpected.expected(), 0xC);
+    assert_eq!(unexpected.actual(), 0xD);
+    assert_eq!(err.to_string(), "expected 0xc, got 0xd");
+    // the mismatched field is still consumed
+    assert_eq!(r.position(), 16);
+    assert!(r.expect(17, 0).unwrap_err().kind() == ErrorKind::UnexpectedEof);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert!(r.expect(32, 0xC13BEDB1).is_ok());
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.expect(33, 0).unwrap_err().kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_reader_as_byte_reader() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::Read;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    let mut buf = [0; 2];
+    r.as_byte_reader().unwrap().read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [0xB1, 0xED]);
+    assert_eq!(r.position(), 16);
+    assert_eq!(r.read::<u8>(4).unwrap(), 0x3);
+    assert_eq!(
+        r.as_byte_reader().err().unwrap().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+    r.byte_align();
+    let mut rest = Vec::new();
+    r.as_byte_reader().unwrap().read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, [0xC1]);
+    assert_eq!(r.position(), 32);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read::<u16>(16).unwrap(), 0xEDB1);
+    let mut rest = Vec::new();
+    r.as_byte_reader().unwrap().read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, [0x3B, 0xC1]);
+}
+
+#[test]
+fn test_reader_sign_magnitude_strict() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian, SignedFormat};
+    use std::io::ErrorKind;
+
+    // sign=1, magnitude=0 under both policies
+    let mut r = BitReader::endian(Cursor::new([0b1000_1000]), BigEndian);
+    assert_eq!(
+        r.read_signed_with(4, SignedFormat::SignMagnitude).unwrap(),
+        0
+    );
+    assert_eq!(
+        r.read_signed_with(4, SignedFormat::SignMagnitudeStrict)
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(r.position(), 8);
+
+    let mut r = BitReader::endian(Cursor::new([0b1000_0000]), LittleEndian);
+    assert!(r
+        .read_signed_with(8, SignedFormat::SignMagnitudeStrict)
+        .is_err());
+
+    // other values read as with SignMagnitude
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+    for &bits in [1, 4, 8, 16, 32].iter() {
+        let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+        let lenient = r.read_signed_with(bits, SignedFormat::SignMagnitude);
+        let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+        let strict = r.read_signed_with(bits, SignedFormat::SignMagnitudeStrict);
+        if bits == 1 {
+            // a lone sign bit is always a zero magnitude
+            assert!(strict.is_err());
+        } else {
+            assert_eq!(strict.unwrap(), lenient.unwrap());
+        }
+    }
+
+    let mut r = BitReader::endian(Cursor::new([0b0000_0000]), BigEndian);
+    assert_eq!(
+        r.read_signed_with(8, SignedFormat::SignMagnitudeStrict)
+            .unwrap(),
+        0
+    );
+    assert_eq!(
+        r.read_signed_with(0, SignedFormat::SignMagnitudeStrict)
+            .unwrap(),
+        0
+    );
+    assert!(r
+        .read_signed_with(33, SignedFormat::SignMagnitudeStrict)
+        .is_err());
+}
+
+#[test]
+fn test_reader_assert_position() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian, PositionMismatch};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert!(r.assert_position(0).is_ok());
+    r.read::<u8>(3).unwrap();
+    r.read_signed::<i16>(10).unwrap();
+    r.read_bit().unwrap();
+    assert!(r.assert_position(14).is_ok());
+    let err = r.assert_position(16).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let mismatch = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<PositionMismatch>()
+        .unwrap();
+    assert_eq!(mismatch.expected(), 16);
+    assert_eq!(mismatch.actual(), 14);
+    r.byte_align();
+    assert!(r.assert_position(16).is_ok());
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.skip(31).unwrap();
+    assert!(r.assert_position(32).is_err());
+    assert!(r.assert_position(31).is_ok());
+}
+
+#[test]
+fn test_reader_interleaved() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, InterleavedBitReader};
+
+    let first: [u8; 2] = [0xB1, 0xED];
+    let second: [u8; 2] = [0x3B, 0xC1];
+
+    let mut r = InterleavedBitReader::new(
+        BitReader::endian(Cursor::new(&first[..]), BigEndian),
+        BitReader::endian(Cursor::new(&second[..]), BigEndian),
+    );
+    // a partial read from stream 0 leaves stream 1 aligned
+    assert_eq!(r.read_from::<u8>(0, 3).unwrap(), 0b101);
+    assert!(!r.stream(0).byte_aligned());
+    assert!(r.stream(1).byte_aligned());
+    assert_eq!(r.stream(1).position(), 0);
+    assert_eq!(r.read_from::<u8>(1, 8).unwrap(), 0x3B);
+    assert_eq!(r.read_from::<u8>(0, 5).unwrap(), 0b10001);
+    assert_eq!(r.read_from::<u8>(1, 1).unwrap(), 1);
+    assert!(r.stream(0).byte_aligned());
+    assert_eq!(r.stream(1).read_signed::<i8>(7).unwrap(), -63);
+    assert_eq!(r.read_from::<u8>(0, 8).unwrap(), 0xED);
+    assert!(r.read_from::<u8>(0, 1).is_err());
+    assert!(r.read_from::<u8>(1, 1).is_err());
+
+    let (r0, r1) = r.into_readers();
+    assert_eq!(r0.position(), 16);
+    assert_eq!(r1.position(), 16);
+}
+
+#[test]
+fn test_reader_padded() {
+    use bitstream_io::{BigEndian, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_padded(10).unwrap(), (0x2C7, 6));
+    assert_eq!(r.read_padded(2).unwrap(), (0b10, 4));
+    assert_eq!(r.read_padded(4).unwrap(), (0xD, 0));
+    assert_eq!(r.read_padded(0).unwrap(), (0, 0));
+    assert_eq!(r.read_padded(16).unwrap(), (0x3BC1, 0));
+    assert!(r.read_padded(1).is_err());
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read_padded(10).unwrap(), (0x1B1, 6));
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert!(r.read_padded(33).is_err());
+}
+
+#[test]
+fn test_reader_signed_checked() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian, ValueOutOfRange};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_signed_checked(4, -8, 7).unwrap(), -5);
+    assert_eq!(r.read_signed_checked(4, 1, 1).unwrap(), 1);
+    let err = r.read_signed_checked(8, -16, 16).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let range = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<ValueOutOfRange>()
+        .unwrap();
+    assert_eq!((range.value(), range.min(), range.max()), (-19, -16, 16));
+    // the field is still consumed
+    assert_eq!(r.position(), 16);
+    assert!(r.read_signed_checked(8, 0, 100).is_ok());
+    assert!(r.read_signed_checked(8, i32::MIN, 0).is_ok());
+    assert!(r.read_signed_checked(1, 0, 0).is_err());
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(
+        r.read_signed_checked(32, i32::MIN, i32::MAX).unwrap(),
+        -1_053_037_135
+    );
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert!(r.read_signed_checked(33, i32::MIN, i32::MAX).is_err());
+}
+
+#[test]
+fn test_reader_u128() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let data: Vec<u8> = (0x01..=0x11).collect();
+
+    // 96 bits land in the low bits of the u128
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read::<u128>(96).unwrap(), 0x0102_0304_0506_0708_090A_0B0C);
+
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    let value = r.read::<u128>(128).unwrap();
+    assert_eq!(value.to_be_bytes()[0], 0x01);
+    assert_eq!(value.to_be_bytes()[15], 0x10);
+    assert!(r.read::<u128>(129).is_err());
+
+    // unaligned full-width reads
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    r.skip(4).unwrap();
+    assert_eq!(
+        r.read::<u128>(128).unwrap(),
+        0x1020_3040_5060_7080_90A0_B0C0_D0E0_F101
+    );
+
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    let value = r.read::<u128>(128).unwrap();
+    assert_eq!(value.to_le_bytes()[0], 0x01);
+    assert_eq!(value.to_le_bytes()[15], 0x10);
+
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    r.skip(4).unwrap();
+    assert_eq!(
+        r.read::<u128>(128).unwrap(),
+        0x1100_F0E0_D0C0_B0A0_9080_7060_5040_3020
+    );
+    assert_eq!(r.read_signed::<i128>(4).unwrap(), 1);
+}
+
+#[test]
+fn test_reader_delta_samples() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, BitWrite, BitWriter, LittleEndian};
+
+    // base 100 followed by deltas 3, -7, 0, 15, -16
+    let mut w = BitWriter::endian(Vec::new(), LittleEndian);
+    w.write_signed(8, 100i32).unwrap();
+    for &delta in [3, -7, 0, 15, -16].iter() {
+        w.write_signed(5, delta).unwrap();
+    }
+    w.byte_align().unwrap();
+    let data = w.into_writer();
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    let mut samples = [0; 6];
+    r.read_delta_samples(8, 5, &mut samples).unwrap();
+    assert_eq!(samples, [100, 103, 96, 96, 111, 95]);
+
+    // sums wrap on overflow
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    w.write_signed(32, i32::MAX).unwrap();
+    w.write_signed(2, 1).unwrap();
+    w.write_signed(2, -2).unwrap();
+    w.byte_align().unwrap();
+    let data = w.into_writer();
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    let mut samples = [0; 3];
+    r.read_delta_samples(32, 2, &mut samples).unwrap();
+    assert_eq!(samples, [i32::MAX, i32::MIN, i32::MAX - 1]);
+
+    // an empty buffer reads nothing
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    r.read_delta_samples(32, 2, &mut []).unwrap();
+    assert_eq!(r.position(), 0);
+
+    // a short stream leaves earlier samples in place
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    let mut samples = [0; 6];
+    assert!(r.read_delta_samples(32, 2, &mut samples).is_err());
+    assert_eq!(samples[..3], [i32::MAX, i32::MIN, i32::MAX - 1]);
+}
+
+#[test]
+fn test_reader_reset_position() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian, RewindReader};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.read::<u8>(3).unwrap();
+    assert!(!r.byte_aligned());
+    r.reset_position();
+    assert_eq!(r.position(), 0);
+    assert!(!r.byte_aligned());
+    assert_eq!(r.read::<u8>(5).unwrap(), 0b10001);
+    assert_eq!(r.position(), 5);
+    assert!(r.byte_aligned());
+    r.skip(11).unwrap();
+    assert_eq!(r.position(), 16);
+    assert_eq!(r.total_bytes_consumed(), 3);
+    r.reset_position();
+    r.byte_align();
+    assert_eq!(r.position(), 5);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.reset_position();
+    assert_eq!(r.position(), 0);
+    assert!(r.byte_aligned());
+    r.read::<u16>(16).unwrap();
+    r.reset_position();
+    assert_eq!(r.read::<u16>(16).unwrap(), 0xC13B);
+    assert_eq!(r.position(), 16);
+
+    // seeking is relative to the start of the stream
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.skip(9).unwrap();
+    r.reset_position();
+    r.seek_bits(12).unwrap();
+    assert_eq!(r.position(), 12);
+
+    // rewinding to before a reset restarts at the mark
+    let mut r = BitReader::endian(RewindReader::new(Cursor::new(&actual_data), 4), BigEndian);
+    r.skip(4).unwrap();
+    r.mark();
+    r.skip(8).unwrap();
+    r.reset_position();
+    r.rewind().unwrap();
+    assert_eq!(r.position(), 0);
+    assert_eq!(r.read::<u8>(8).unwrap(), 0x1E);
+}
+
+#[test]
+fn test_reader_try_read() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    // full reads
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.try_read::<u8>(2).unwrap(), Some(0b10));
+    assert_eq!(r.try_read::<u16>(14).unwrap(), Some(0x31ED));
+    assert_eq!(r.try_read::<u32>(12).unwrap(), Some(0x3BC));
+    assert_eq!(r.position(), 28);
+    // mid-field EOF
+    assert_eq!(
+        r.try_read::<u8>(5).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.try_read::<u32>(32).unwrap(), Some(0xC13BEDB1));
+    // clean EOF
+    assert_eq!(r.try_read::<u32>(32).unwrap(), None);
+    assert_eq!(r.try_read::<u8>(1).unwrap(), None);
+    assert_eq!(r.try_read::<u8>(0).unwrap(), Some(0));
+    assert_eq!(r.position(), 32);
+
+    // EOF partway through a multi-byte field starting aligned
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.skip(16).unwrap();
+    assert_eq!(
+        r.try_read::<u32>(24).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(
+        r.try_read::<u8>(9).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(r.position(), 0);
+}
+
+#[test]
+fn test_reader_tagged() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, BitWrite, BitWriter, LittleEndian};
+    use std::io::ErrorKind;
+
+    let widths = [4, 8, 12, 16];
+    let fields = [(0, 0xA), (1, 0xB1), (2, 0xED3), (3, 0xBC1E), (0, 0)];
+
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    for &(tag, value) in fields.iter() {
+        w.write(2, tag).unwrap();
+        w.write(widths[tag as usize], value).unwrap();
+    }
+    let data = w.into_bytes();
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    for &field in fields.iter() {
+        assert_eq!(r.read_tagged(2, &widths).unwrap(), field);
+    }
+
+    let mut w = BitWriter::endian(Vec::new(), LittleEndian);
+    for &(tag, value) in fields.iter() {
+        w.write(2, tag).unwrap();
+        w.write(widths[tag as usize], value).unwrap();
+    }
+    let data = w.into_bytes();
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    for &field in fields.iter() {
+        assert_eq!(r.read_tagged(2, &widths).unwrap(), field);
+    }
+
+    // tag 3 has no width
+    let mut r = BitReader::endian(Cursor::new([0b1100_0000]), BigEndian);
+    assert_eq!(
+        r.read_tagged(2, &widths[..3]).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    let mut r = BitReader::endian(Cursor::new([0; 8]), BigEndian);
+    assert!(r.read_tagged(1, &[33]).is_err());
+}
+
+#[test]
+fn test_reader_skip_at_most() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.skip_at_most(0).unwrap(), 0);
+    assert_eq!(r.skip_at_most(3).unwrap(), 3);
+    assert_eq!(r.read::<u8>(5).unwrap(), 0b10001);
+    assert_eq!(r.skip_at_most(13).unwrap(), 13);
+    assert_eq!(r.position(), 21);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b011);
+    // more bits than remain
+    assert_eq!(r.skip_at_most(1000).unwrap(), 8);
+    assert_eq!(r.position(), 32);
+    assert_eq!(r.skip_at_most(7).unwrap(), 0);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.skip_at_most(4).unwrap(), 4);
+    assert_eq!(r.skip_at_most(u32::MAX).unwrap(), 28);
+    assert!(r.read_bit().is_err());
+
+    // a long stream skipped in several chunks
+    let data = vec![0u8; 1000];
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.skip_at_most(7000).unwrap(), 7000);
+    assert_eq!(r.skip_at_most(7000).unwrap(), 1000);
+    assert_eq!(r.position(), 8000);
+}
+
+#[test]
+fn test_reader_unstuffer() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, Unstuffer};
+    use std::io::ErrorKind;
+
+    // HDLC-style stuffing, with runs crossing byte boundaries
+    let actual_data: [u8; 5] = [0x0F, 0xBE, 0xFB, 0x0F, 0xA0];
+    let mut r = Unstuffer::new(BitReader::endian(Cursor::new(&actual_data), BigEndian), 5);
+    assert_eq!(r.read::<u8>(8).unwrap(), 0x0F);
+    assert_eq!(r.read::<u16>(16).unwrap(), 0xFFF8);
+    assert_eq!(r.read_unary0().unwrap(), 0);
+    assert_eq!(r.read_unary0().unwrap(), 6);
+    assert!(!r.byte_aligned());
+    assert_eq!(r.read::<u8>(4).unwrap(), 0);
+    assert!(r.byte_aligned());
+    let mut reader = r.into_reader();
+    assert!(reader.read_bit().is_err());
+
+    let mut r = Unstuffer::new(BitReader::endian(Cursor::new(&actual_data), BigEndian), 5);
+    let mut bytes = [0; 4];
+    r.read_bytes(&mut bytes).unwrap();
+    assert_eq!(bytes, [0x0F, 0xFF, 0xF8, 0x7E]);
+
+    let mut r = Unstuffer::new(BitReader::endian(Cursor::new(&actual_data), BigEndian), 5);
+    r.skip(8).unwrap();
+    assert_eq!(r.read_signed::<i16>(16).unwrap(), -8);
+
+    // a run which isn't followed by a 0 bit
+    let data = [0b1111_1100];
+    let mut r = Unstuffer::new(BitReader::endian(Cursor::new(&data), BigEndian), 5);
+    assert_eq!(r.read::<u8>(5).unwrap(), 0b11111);
+    assert_eq!(r.read_bit().unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_reader_zero_padded() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian, ZeroPadded};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(ZeroPadded::new(Cursor::new(&actual_data), 3), BigEndian);
+    assert_eq!(r.read::<u32>(28).unwrap(), 0xB1ED3BC);
+    assert_eq!(r.read::<u8>(8).unwrap(), 0x10);
+    assert_eq!(r.read::<u16>(16).unwrap(), 0);
+    assert_eq!(r.read::<u8>(4).unwrap(), 0);
+    assert_eq!(r.read_bit().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(r.into_reader().padding_remaining(), 0);
+
+    let mut r = BitReader::endian(ZeroPadded::new(Cursor::new(&actual_data), 2), LittleEndian);
+    let mut buf = [0xFF; 6];
+    r.read_bytes(&mut buf).unwrap();
+    assert_eq!(buf, [0xB1, 0xED, 0x3B, 0xC1, 0, 0]);
+    assert!(r.read_bit().is_err());
+
+    // no padding at all
+    let mut r = BitReader::endian(ZeroPadded::new(Cursor::new(&actual_data), 0), BigEndian);
+    r.skip(32).unwrap();
+    assert!(r.read_bit().is_err());
+}
+
+#[test]
+fn test_reader_logged() {
+    use bitstream_io::{BigEndian, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    let (value, pattern) = r.read_logged(6).unwrap();
+    assert_eq!(value, 0b101100);
+    assert_eq!(pattern.len(), 6);
+    assert_eq!(pattern.bits(), 0b101100);
+    assert_eq!(pattern.to_string(), "1011_00");
+    let (value, pattern) = r.read_logged(0).unwrap();
+    assert_eq!(value, 0);
+    assert!(pattern.is_empty());
+    assert_eq!(pattern.to_string(), "");
+    assert_eq!(r.last_read_span(), (6, 6));
+    let (value, pattern) = r.read_logged(10).unwrap();
+    assert_eq!(value, 0b01_1110_1101);
+    assert_eq!(pattern.to_string(), "0111_1011_01");
+    assert!(r.read_logged(33).is_err());
+    let (value, pattern) = r.read_logged(16).unwrap();
+    assert_eq!(value, 0x3BC1);
+    assert_eq!(pattern.to_string(), "0011_1011_1100_0001");
+    assert!(r.read_logged(1).is_err());
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    let (value, pattern) = r.read_logged(6).unwrap();
+    assert_eq!(value, 0b110001);
+    assert_eq!(pattern.to_string(), "1000_11");
+    assert_eq!(pattern.bits(), 0b100011);
+    let (value, pattern) = r.read_logged(26).unwrap();
+    assert_eq!(value, 0x304_EFB6);
+    assert_eq!(r.position(), 32);
+    assert_eq!(pattern.len(), 26);
+    let (_, pattern) = BitReader::endian(Cursor::new(&actual_data), LittleEndian)
+        .read_logged(32)
+        .unwrap();
+    assert_eq!(
+        pattern.to_string(),
+        "1000_1101_1011_0111_1101_1100_1000_0011"
+    );
+}
+
+#[test]
+fn test_reader_sub_reader() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b101);
+    let mut sub = r.sub_reader(13).unwrap();
+    assert_eq!(r.position(), 16);
+    assert_eq!(sub.read::<u16>(10).unwrap(), 0b10_0011_1101);
+    // over-reading the sub-reader leaves the parent alone
+    assert!(sub.read::<u8>(4).is_err());
+    assert_eq!(sub.read::<u8>(3).unwrap(), 0b101);
+    assert!(sub.read_bit().is_err());
+    assert_eq!(r.read::<u16>(16).unwrap(), 0x3BC1);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b001);
+    let mut sub = r.sub_reader(21).unwrap();
+    assert_eq!(sub.read::<u32>(21).unwrap(), 0x3BEDB1 >> 3);
+    assert!(sub.read_bit().is_err());
+    assert_eq!(r.read::<u8>(8).unwrap(), 0xC1);
+
+    // an empty sub-reader, and one longer than the stream
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert!(r.sub_reader(0).unwrap().read_bit().is_err());
+    assert!(r.sub_reader(33).is_err());
+
+    // the allocation limit is checked before anything is read
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian).with_max_alloc(2);
+    assert_eq!(
+        r.sub_reader(17).err().unwrap().kind(),
+        std::io::ErrorKind::InvalidData
+    );
+    assert_eq!(
+        r.sub_reader(u64::MAX).err().unwrap().kind(),
+        std::io::ErrorKind::InvalidData
+    );
+    assert_eq!(r.position(), 0);
+    assert_eq!(r.sub_reader(16).unwrap().read::<u16>(16).unwrap(), 0xB1ED);
+}
+
+#[test]
+fn test_reader_byte_read_24_48() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, ByteRead, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 18] = [
+        0x80, 0x00, 0x00, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x12, 0x34, 0x56, 0x80, 0x00, 0x00,
+        0x00, 0x00, 0x01,
+    ];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_i24().unwrap(), -8_388_608);
+    assert_eq!(r.read_i24().unwrap(), 8_388_607);
+    assert_eq!(r.read_i24().unwrap(), -1);
+    assert_eq!(r.read_i24().unwrap(), 0x12_3456);
+    assert_eq!(r.read_i48().unwrap(), -0x7FFF_FFFF_FFFF);
+    assert!(r.read_u24().is_err());
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_u24().unwrap(), 0x80_0000);
+    assert_eq!(r.read_u48().unwrap(), 0x7FFF_FFFF_FFFF);
+    assert_eq!(r.read_i48().unwrap(), 0x1234_5680_0000);
+    assert_eq!(r.read_u48().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    // still big-endian when the bit order isn't
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read_u24().unwrap(), 0x80_0000);
+    r.read_bit().unwrap();
+    assert_eq!(r.read_i24().unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(r.read_u48().unwrap_err().kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_reader_schema() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, FieldSpec, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+    let schema = vec![
+        FieldSpec::unsigned("kind", 3),
+        FieldSpec::signed("offset", 10),
+        FieldSpec::unsigned("length", 19),
+    ];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(
+        r.read_schema(&schema).unwrap(),
+        vec![
+            ("kind".to_string(), 5),
+            ("offset".to_string(), -451),
+            ("length".to_string(), 0x53BC1),
+        ]
+    );
+    assert!(r.read_schema(&schema).is_err());
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    let fields = r.read_schema(&schema).unwrap();
+    assert_eq!(fields[0], ("kind".to_string(), 1));
+    assert_eq!(fields[1], ("offset".to_string(), 438));
+    assert_eq!(fields[2], ("length".to_string(), 0x609DF));
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_schema(&[]).unwrap(), vec![]);
+    assert!(r.read_schema(&[FieldSpec::signed("huge", 65)]).is_err());
+    let data = [0xFF; 8];
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(
+        r.read_schema(&[FieldSpec::unsigned("huge", 64)])
+            .unwrap_err()
+            .kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn test_reader_zero_bits() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    /// A stream which may never be read from
+    struct Untouchable;
+
+    impl std::io::Read for Untouchable {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            panic!("underlying stream read")
+        }
+    }
+
+    let mut r = BitReader::endian(Untouchable, BigEndian);
+    assert_eq!(r.read::<u8>(0).unwrap(), 0);
+    assert_eq!(r.read::<u64>(0).unwrap(), 0);
+    assert_eq!(r.read_signed::<i8>(0).unwrap(), 0);
+    assert_eq!(r.read_signed::<i64>(0).unwrap(), 0);
+    r.skip(0).unwrap();
+    assert_eq!(r.position(), 0);
+
+    let mut r = BitReader::endian(Untouchable, LittleEndian);
+    assert_eq!(r.read::<u32>(0).unwrap(), 0);
+    assert_eq!(r.read_signed::<i32>(0).unwrap(), 0);
+    r.skip(0).unwrap();
+    assert_eq!(r.position(), 0);
+
+    // zero-bit reads at the end of a stream succeed and consume nothing
+    let actual_data: [u8; 1] = [0xB1];
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.skip(5).unwrap();
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b001);
+    assert_eq!(r.read::<u16>(0).unwrap(), 0);
+    assert_eq!(r.read_signed::<i16>(0).unwrap(), 0);
+    r.skip(0).unwrap();
+    assert_eq!(r.position(), 8);
+    assert!(r.read::<u8>(1).is_err());
+}
+
+#[test]
+fn test_reader_strings() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, ByteRead, LittleEndian};
+    use std::io::ErrorKind;
+
+    let data = b"WAVEfmt \xC3\xA9t\xC3\x28\xFF";
+
+    let mut r = BitReader::endian(Cursor::new(&data[..]), BigEndian);
+    assert_eq!(r.read_ascii(4).unwrap(), "WAVE");
+    assert_eq!(r.read_utf8(4).unwrap(), "fmt ");
+    assert_eq!(r.read_utf8(0).unwrap(), "");
+    assert_eq!(r.read_utf8(3).unwrap(), "ét");
+    // an invalid continuation byte
+    assert_eq!(r.read_utf8(2).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(r.position(), 104);
+    assert_eq!(r.read_ascii(1).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(
+        r.read_ascii(1).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+
+    let mut r = BitReader::endian(Cursor::new(&data[..]), LittleEndian);
+    assert_eq!(r.read_utf8(8).unwrap(), "WAVEfmt ");
+    // valid UTF-8 isn't necessarily ASCII
+    assert_eq!(r.read_ascii(3).unwrap_err().kind(), ErrorKind::InvalidData);
+    r.read_bit().unwrap();
+    assert_eq!(r.read_utf8(1).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(r.read_ascii(1).unwrap_err().kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_reader_reverse_bytes() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian, ReverseByteReader};
+    use std::io::Read;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    // bytes are taken as C1 3B ED B1
+    let mut r = BitReader::endian(ReverseByteReader::new(&actual_data), BigEndian);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b110);
+    assert_eq!(r.read::<u8>(5).unwrap(), 0b00001);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0x3BE);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0xDB1);
+    assert!(r.read_bit().is_err());
+
+    let mut r = BitReader::endian(ReverseByteReader::new(&actual_data), LittleEndian);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b001);
+    assert_eq!(r.read::<u32>(29).unwrap(), 0xB1ED_3BC1 >> 3);
+    let mut r = BitReader::endian(ReverseByteReader::new(&actual_data), LittleEndian);
+    assert_eq!(r.read::<u32>(32).unwrap(), 0xB1ED_3BC1);
+
+    let mut bytes = ReverseByteReader::new(&actual_data);
+    let mut buf = [0; 3];
+    assert_eq!(bytes.read(&mut buf).unwrap(), 3);
+    assert_eq!(buf, [0xC1, 0x3B, 0xED]);
+    assert_eq!(bytes.remaining(), [0xB1]);
+    assert_eq!(bytes.read(&mut buf).unwrap(), 1);
+    assert_eq!(buf[0], 0xB1);
+    assert_eq!(bytes.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn test_reader_bytes_partial() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::Read;
+
+    /// Delivers the first two bytes of its data, then the rest
+    struct Chunked<'a> {
+        chunks: Vec<&'a [u8]>,
+    }
+
+    impl<'a> Read for Chunked<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self.chunks.first_mut() {
+                Some(chunk) => {
+                    let read = chunk.read(buf)?;
+                    if chunk.is_empty() {
+                        self.chunks.remove(0);
+                    }
+                    Ok(read)
+                }
+                None => Ok(0),
+            }
+        }
+    }
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(
+        Chunked {
+            chunks: vec![&actual_data[0..2], &actual_data[2..4]],
+        },
+        BigEndian,
+    );
+    let mut buf = [0; 4];
+    assert_eq!(r.read_bytes_partial(&mut buf).unwrap(), 2);
+    assert_eq!(&buf[0..2], [0xB1, 0xED]);
+    assert_eq!(r.position(), 16);
+    assert_eq!(r.read_bytes_partial(&mut buf).unwrap(), 2);
+    assert_eq!(&buf[0..2], [0x3B, 0xC1]);
+    assert_eq!(r.read_bytes_partial(&mut buf).unwrap(), 0);
+
+    // an unaligned reader keeps its partial byte consistent
+    let mut r = BitReader::endian(
+        Chunked {
+            chunks: vec![&actual_data[0..2], &actual_data[2..4]],
+        },
+        LittleEndian,
+    );
+    assert_eq!(r.read::<u8>(4).unwrap(), 0x1);
+    assert_eq!(r.read_bytes_partial(&mut buf[0..1]).unwrap(), 1);
+    assert_eq!(buf[0], 0xDB);
+    assert_eq!(r.read_bytes_partial(&mut buf).unwrap(), 2);
+    assert_eq!(&buf[0..2], [0xBE, 0x13]);
+    assert_eq!(r.position(), 28);
+    assert_eq!(r.read_bytes_partial(&mut buf).unwrap(), 0);
+    assert_eq!(r.read::<u8>(4).unwrap(), 0xC);
+    assert!(r.read_bit().is_err());
+}
+
+#[test]
+fn test_reader_align_and_expect() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian, MagicMismatch};
+    use std::io::ErrorKind;
+
+    let data = [0xB1, b'R', b'I', b'F', b'F', 0x0F];
+
+    // the partial byte is discarded before the magic is read
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b101);
+    assert!(r.align_and_expect(b"RIFF").is_ok());
+    assert_eq!(r.read::<u8>(4).unwrap(), 0x0);
+
+    // an aligned reader discards nothing
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    assert!(r.align_and_expect(&[0xB1, b'R']).is_ok());
+    assert!(r.align_and_expect(b"").is_ok());
+    assert!(r.align_and_expect(b"IFF").is_ok());
+    assert_eq!(r.read::<u8>(4).unwrap(), 0xF);
+
+    // a mismatch reports both the expected and found bytes
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    r.skip(1).unwrap();
+    let err = r.align_and_expect(b"fLaC").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    let mismatch = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<MagicMismatch>()
+        .unwrap();
+    assert_eq!(mismatch.expected(), b"fLaC");
+    assert_eq!(mismatch.found(), b"RIFF");
+    assert_eq!(err.to_string(), "expected magic 664C6143, found 52494646");
+
+    // running out of data is an I/O error
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    r.skip(8).unwrap();
+    assert_eq!(
+        r.align_and_expect(b"RIFF\x0F\x00").unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+}
+
+#[test]
+fn test_reader_accumulator() {
+    use bitstream_io::{AccumulatorReader, BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    // filling buffers whole bytes, without consuming any bits
+    let mut r = AccumulatorReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.fill_accumulator(24).unwrap();
+    assert_eq!(r.accumulator(), (0xB1ED3B, 24));
+    r.fill_accumulator(20).unwrap();
+    assert_eq!(r.accumulator(), (0xB1ED3B, 24));
+    r.consume(5);
+    assert_eq!(r.accumulator(), (0x1ED3B, 19));
+    r.fill_accumulator(20).unwrap();
+    assert_eq!(r.accumulator(), (0x1ED3BC1, 27));
+    assert!(r.fill_accumulator(28).is_err());
+    assert_eq!(r.accumulator(), (0x1ED3BC1, 27));
+
+    // more bits than the accumulator can always hold
+    let zeroes = [0u8; 16];
+    let mut r = AccumulatorReader::endian(Cursor::new(&zeroes), BigEndian);
+    r.fill_accumulator(57).unwrap();
+    assert_eq!(r.accumulator(), (0, 64));
+    r.consume(64);
+    assert_eq!(
+        r.fill_accumulator(58).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+    assert_eq!(r.accumulator(), (0, 0));
+
+    let mut r = AccumulatorReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.fill_accumulator(24).unwrap();
+    assert_eq!(r.accumulator(), (0x3BEDB1, 24));
+    r.consume(5);
+    assert_eq!(r.accumulator(), (0x1DF6D, 19));
+
+    // consuming matches skipping
+    for skip in 0..=32 {
+        let mut r = AccumulatorReader::endian(Cursor::new(&actual_data), BigEndian);
+        let mut s = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+        r.fill_accumulator(skip.min(32)).unwrap();
+        r.consume(skip);
+        s.skip(skip).unwrap();
+        for _ in skip..32 {
+            assert_eq!(r.read_bit().unwrap(), s.read_bit().unwrap());
+        }
+        assert!(r.read_bit().is_err());
+
+        let mut r = AccumulatorReader::endian(Cursor::new(&actual_data), LittleEndian);
+        let mut s = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+        r.fill_accumulator(skip).unwrap();
+        r.consume(skip);
+        s.skip(skip).unwrap();
+        assert_eq!(r.byte_aligned(), s.byte_aligned());
+        if skip < 32 {
+            assert_eq!(
+                r.read::<u32>(32 - skip).unwrap(),
+                s.read::<u32>(32 - skip).unwrap()
+            );
+        }
+    }
+
+    // the usual reads drain the accumulator
+    let mut r = AccumulatorReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read::<u8>(2).unwrap(), 2);
+    assert_eq!(r.read_unary0().unwrap(), 2);
+    r.skip(3).unwrap();
+    assert_eq!(r.read_unary1().unwrap(), 0);
+    assert_eq!(r.read_signed::<i16>(12).unwrap(), -601);
+    r.fill_accumulator(8).unwrap();
+    assert_eq!(r.accumulator(), (0x3C1, 11));
+    r.byte_align();
+    let mut buf = [0; 1];
+    r.read_bytes(&mut buf).unwrap();
+    assert_eq!(buf, [0xC1]);
+    assert!(r.read_bit().is_err());
+}
+
+#[test]
+fn test_reader_peek_byte() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 2] = [0xB1, 0xED];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.peek_byte().unwrap(), 0xB1);
+    assert_eq!(r.peek_byte().unwrap(), 0xB1);
+    assert_eq!(r.position(), 0);
+    assert_eq!(r.read::<u8>(8).unwrap(), 0xB1);
+    assert_eq!(r.position(), 8);
+    assert_eq!(r.into_reader().position(), 1);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0x1);
+    assert_eq!(r.peek_byte().unwrap_err().kind(), ErrorKind::InvalidInput);
+    r.byte_align();
+    assert_eq!(r.peek_byte().unwrap(), 0xED);
+    assert_eq!(r.read::<u8>(8).unwrap(), 0xED);
+    assert_eq!(r.peek_byte().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_reader_byte_read_le_be() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, ByteRead, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 14] = [
+        0xB1, 0xED, 0xB1, 0xED, 0x12, 0x34, 0x56, 0x78, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+    ];
+
+    // byte order is the same for either bit order
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_u16_le().unwrap(), 0xEDB1);
+    assert_eq!(r.read_u16_be().unwrap(), 0xB1ED);
+    assert_eq!(r.read_u32_le().unwrap(), 0x7856_3412);
+    assert_eq!(r.read_u32_be().unwrap(), 0x0102_0304);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read_i16_le().unwrap(), -0x124F);
+    assert_eq!(r.read_i16_be().unwrap(), -0x4E13);
+    assert_eq!(r.read_i32_le().unwrap(), 0x7856_3412);
+    assert_eq!(r.read_i32_be().unwrap(), 0x0102_0304);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read_u64_le().unwrap(), 0x7856_3412_EDB1_EDB1);
+    assert_eq!(r.read_u16_be().unwrap(), 0x0102);
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_i64_be().unwrap(), -0x4E12_4E12_EDCB_A988);
+    assert_eq!(
+        r.read_i64_le().unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.read_bit().unwrap();
+    assert_eq!(r.read_u16_le().unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(r.read_i64_be().unwrap_err().kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_reader_with_length() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::<_, BigEndian>::with_length(Cursor::new(&actual_data), 20);
+    assert_eq!(r.bits_remaining(), 20);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0xB1E);
+    assert_eq!(r.bits_remaining(), 8);
+    let err = r.read::<u16>(9).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(err.to_string(), "read past end of limited stream");
+    assert_eq!(r.bits_remaining(), 8);
+    assert_eq!(r.read::<u8>(8).unwrap(), 0xD3);
+    assert!(r.read_bit().is_err());
+    assert_eq!(r.into_inner().position(), 20);
+
+    // a declared length past the end of the data
+    // still fails at the end of the stream
+    let mut r = BitReader::<_, LittleEndian>::with_length(Cursor::new(&actual_data), 40);
+    assert_eq!(r.read::<u32>(32).unwrap(), 0xC13B_EDB1);
+    assert_eq!(r.bits_remaining(), 8);
+    assert_eq!(r.read_bit().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_reader_find_sync() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    // a 12-bit sync word after some filler bytes
+    let actual_data: [u8; 7] = [0x12, 0x34, 0xFF, 0xF1, 0x50, 0xFF, 0xF0];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.find_sync(0xFFF, 12, true).unwrap();
+    assert_eq!(r.position(), 16);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0xFFF);
+    assert_eq!(r.read::<u8>(4).unwrap(), 0x1);
+
+    // bit granularity finds unaligned patterns
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.skip(3).unwrap();
+    r.find_sync(0b1101, 4, false).unwrap();
+    assert_eq!(r.position(), 10);
+    assert_eq!(r.read::<u8>(4).unwrap(), 0b1101);
+    r.find_sync(0xFFF, 12, false).unwrap();
+    assert_eq!(r.position(), 16);
+    r.skip(1).unwrap();
+    r.find_sync(0xFFF, 12, false).unwrap();
+    assert_eq!(r.position(), 40);
+
+    // byte granularity skips unaligned patterns
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.skip(17).unwrap();
+    r.find_sync(0xFFF, 12, true).unwrap();
+    assert_eq!(r.position(), 40);
+
+    // the pattern is in the stream's own bit order
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.find_sync(0x1FF, 12, false).unwrap();
+    assert_eq!(r.position(), 16);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0x1FF);
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.find_sync(0x50F1, 16, true).unwrap();
+    assert_eq!(r.position(), 24);
+
+    // a pattern not found runs off the end of the stream
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(
+        r.find_sync(0xABC, 12, false).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(
+        r.find_sync(0x10, 4, false).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        r.find_sync(0, 33, false).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(r.position(), 0);
+}
+
+#[test]
+fn test_reader_take_buffered() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b101);
+    assert_eq!(r.take_buffered(), (0b1_0001, 5));
+    assert!(r.byte_aligned());
+    assert_eq!(r.position(), 8);
+    assert_eq!(r.read::<u8>(8).unwrap(), 0xED);
+    assert_eq!(r.read::<u8>(4).unwrap(), 0x3);
+    let (value, bits) = r.take_buffered();
+    assert_eq!((value, bits), (0xB, 4));
+
+    // the taken bits may be put back
+    let mut r = BitReader::<_, BigEndian>::from_state(r.into_reader(), bits, value as u8);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0xBC1);
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b001);
+    assert_eq!(r.take_buffered(), (0b1_0110, 5));
+    assert!(r.byte_aligned());
+    assert_eq!(r.read::<u8>(8).unwrap(), 0xED);
+}
+
+#[test]
+fn test_reader_full_width() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 9] = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xB1, 0xED, 0x3B, 0xC1];
+
+    fn bit_by_bit<R: BitRead>(r: &mut R, little: bool) -> u32 {
+        (0..32).fold(0, |acc, i| {
+            let bit = u32::from(r.read_bit().unwrap());
+            if little {
+                acc | (bit << i)
+            } else {
+                (acc << 1) | bit
+            }
+        })
+    }
+
+    for offset in 0..=40 {
+        let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+        let mut baseline = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+        r.skip(offset).unwrap();
+        baseline.skip(offset).unwrap();
+        assert_eq!(r.read::<u32>(32).unwrap(), bit_by_bit(&mut baseline, false));
+        assert_eq!(r.position(), baseline.position());
+
+        let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+        let mut baseline = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+        r.skip(offset).unwrap();
+        baseline.skip(offset).unwrap();
+        assert_eq!(r.read::<u32>(32).unwrap(), bit_by_bit(&mut baseline, true));
+        assert_eq!(r.position(), baseline.position());
+    }
+
+    // all 1 bits, aligned and not
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read::<u32>(32).unwrap(), 0xFFFF_FFFF);
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.skip(7).unwrap();
+    assert_eq!(r.read::<u32>(32).unwrap(), 0xFFFF_FFFF);
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.skip(5).unwrap();
+    assert_eq!(r.read_signed::<i32>(32).unwrap(), -1);
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read::<u64>(64).unwrap(), 0xFFFF_FFFF_FFB1_ED3B);
+}
+
+#[test]
+fn test_reader_tuples() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    // a 5-6-5 pixel from two bytes
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(
+        r.read_tuple3(5, 6, 5).unwrap(),
+        (0b10110, 0b001111, 0b01101)
+    );
+    assert_eq!(r.position(), 16);
+    assert_eq!(
+        r.read_tuple4(2, 3, 0, 11).unwrap(),
+        (0b00, 0b111, 0, 0b011_1100_0001)
+    );
+    assert!(r.read_tuple3(1, 0, 0).is_err());
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(
+        r.read_tuple3(5, 6, 5).unwrap(),
+        (0b10001, 0b101101, 0b11101)
+    );
+
+    // a failed field leaves the earlier ones consumed
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert!(r.read_tuple4(8, 8, 33, 8).is_err());
+    assert_eq!(r.position(), 16);
+    assert_eq!(r.read::<u8>(8).unwrap(), 0x3B);
+}
+
+#[test]
+fn test_reader_saved_state() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian, ReaderState};
+    use std::io::{ErrorKind, Seek, SeekFrom};
+
+    let actual_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
+
+    for offset in 0..32 {
+        let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+        r.skip(offset).unwrap();
+        let state = r.save_state();
+        let mut c = Cursor::new(&actual_data);
+        c.seek(SeekFrom::Start(state.bytes_consumed())).unwrap();
+        let mut restored = BitReader::<_, BigEndian>::from_saved_state(c, state);
+        assert_eq!(restored.position(), r.position());
+        assert_eq!(restored.total_bytes_consumed(), r.total_bytes_consumed());
+        assert_eq!(
+            restored.read::<u32>(32 - offset).unwrap(),
+            r.read::<u32>(32 - offset).unwrap()
+        );
+        assert_eq!(restored.position(), 32);
+        assert!(restored.read_bit().is_err());
+    }
+
+    // positions relative to a reset origin survive restoring
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.skip(5).unwrap();
+    r.reset_position();
+    r.skip(6).unwrap();
+    let state = r.save_state();
+    assert_eq!(state.position(), 6);
+    let state = ReaderState::from_parts(
+        state.position(),
+        state.bytes_consumed(),
+        state.bits(),
+        state.value(),
+    )
+    .unwrap();
+    let mut restored = BitReader::<_, LittleEndian>::from_saved_state(&actual_data[2..], state);
+    assert_eq!(restored.position(), 6);
+    assert_eq!(
+        restored.read::<u32>(21).unwrap(),
+        r.read::<u32>(21).unwrap()
+    );
+    assert_eq!(restored.position(), 27);
+
+    // inconsistent parts are rejected
+    for &(position, bytes, bits, value) in &[(0, 1, 8, 0), (0, 1, 2, 4), (6, 1, 3, 0)] {
+        assert_eq!(
+            ReaderState::from_parts(position, bytes, bits, value)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidData
+        );
+    }
+    assert!(ReaderState::from_parts(5, 1, 3, 7).is_ok());
+}
+
+#[test]
+fn test_reader_slice_advance() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: Vec<u8> = (0..64u32).map(|i| (i * 37 + 11) as u8).collect();
+
+    // advancing matches the generic reader's skip
+    for first in [0, 3, 8, 13].iter().copied() {
+        for advance in (0..400).step_by(7) {
+            let mut r = BitReader::endian(&actual_data[..], BigEndian);
+            let mut generic = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+            r.skip(first).unwrap();
+            generic.skip(first).unwrap();
+            r.advance(advance).unwrap();
+            generic.skip(advance as u32).unwrap();
+            assert_eq!(r.position(), generic.position());
+            assert_eq!(r.total_bytes_consumed(), generic.total_bytes_consumed());
+            assert_eq!(r.read::<u16>(11).unwrap(), generic.read::<u16>(11).unwrap());
+        }
+    }
+
+    let mut r = BitReader::endian(&actual_data[..], LittleEndian);
+    let mut generic = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    r.advance(5).unwrap();
+    generic.skip(5).unwrap();
+    r.advance(3 + 8 * 10).unwrap();
+    generic.skip(3 + 8 * 10).unwrap();
+    let mut buf = [0; 4];
+    generic.read_bytes(&mut buf).unwrap();
+    assert_eq!(r.read_bytes_ref(4).unwrap(), buf);
+
+    // running out leaves the reader unchanged
+    let mut r = BitReader::endian(&actual_data[..], BigEndian);
+    r.skip(3).unwrap();
+    assert_eq!(
+        r.advance(64 * 8 - 2).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(r.position(), 3);
+    r.advance(64 * 8 - 4).unwrap();
+    assert!(r.read_bit().is_ok());
+    assert!(r.read_bit().is_err());
+}
+
+#[test]
+fn test_reader_mixed_radix() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    // 23 = 3 * 6 + 5, then 29 = 4 * 6 + 5, then 30 is too large
+    let actual_data: [u8; 2] = [0b10111_111, 0b01_11110_0];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_mixed_radix(5, &[5, 6]).unwrap(), [3, 5]);
+    assert_eq!(r.read_mixed_radix(5, &[5, 6]).unwrap(), [4, 5]);
+    assert_eq!(
+        r.read_mixed_radix(5, &[5, 6]).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+
+    // three digits, and the value is in the stream's own order
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read_mixed_radix(7, &[3, 4, 10]).unwrap(), [1, 2, 3]);
+    assert!(r.read_mixed_radix(0, &[]).unwrap().is_empty());
+    assert_eq!(r.read_mixed_radix(0, &[7]).unwrap(), [0]);
+
+    // a product of radices wider than any value read never fails
+    let mut r = BitReader::endian(Cursor::new([0xFF; 8]), BigEndian);
+    assert_eq!(
+        r.read_mixed_radix(64, &[u32::MAX, u32::MAX, 2]).unwrap(),
+        [0x8000_0000, 0x7FFF_FFFF, 1]
+    );
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(
+        r.read_mixed_radix(4, &[4, 0]).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn test_reader_signed_opt() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let actual_data: [u8; 4] = [0x00, 0x0B, 0x80, 0x00];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_signed_opt(12).unwrap(), None);
+    assert_eq!(r.read_signed_opt(4).unwrap(), Some(-5));
+    assert_eq!(r.read_signed_opt(1).unwrap(), Some(-1));
+    assert_eq!(r.read_signed_opt(15).unwrap(), None);
+    assert_eq!(r.position(), 32);
+    assert!(r.read_signed_opt(1).is_err());
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read_signed_opt(8).unwrap(), None);
+    assert_eq!(r.read_signed_opt(8).unwrap(), Some(11));
+    assert_eq!(r.read_signed_opt(16).unwrap(), Some(0x80));
+    assert_eq!(r.read_signed_opt(0).unwrap(), None);
+    assert!(r.read_signed_opt(33).is_err());
+}
+
+#[test]
+fn test_reader_bytes_as_u64() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    let actual_data: [u8; 12] = [
+        0xB1, 0xED, 0x3B, 0xC1, 0xFF, 0xFF, 0xFF, 0xFF, 0x80, 0x01, 0x02, 0x03,
+    ];
+
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    assert_eq!(r.read_bytes_as_u64(3).unwrap(), 0xB1_ED3B);
+    assert_eq!(r.read_bytes_as_u64(0).unwrap(), 0);
+    assert_eq!(r.read_bytes_as_u64(8).unwrap(), 0xC1FF_FFFF_FF80_0102);
+    assert_eq!(r.position(), 88);
+    assert_eq!(
+        r.read_bytes_as_u64(2).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+
+    // bytes are big-endian whatever the bit order, even unaligned
+    let mut r = BitReader::endian(Cursor::new(&actual_data), LittleEndian);
+    assert_eq!(r.read_bytes_as_u64(8).unwrap(), 0xB1ED_3BC1_FFFF_FFFF);
+    let mut r = BitReader::endian(Cursor::new(&actual_data), BigEndian);
+    r.skip(4).unwrap();
+    assert_eq!(r.read_bytes_as_u64(3).unwrap(), 0x1E_D3BC);
+    assert_eq!(
+        r.read_bytes_as_u64(9).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+}
+
+#[test]
+fn test_reader_empty_bytes() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    /// A stream with a single byte which may never be read past
+    struct OneByte(bool);
+
+    impl std::io::Read for OneByte {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            assert!(!self.0, "underlying stream read");
+            self.0 = true;
+            buf[0] = 0xB1;
+            Ok(1)
+        }
+    }
+
+    // aligned, at the end of the stream
+    let mut r = BitReader::endian(OneByte(false), BigEndian);
+    r.read_bytes(&mut []).unwrap();
+    assert_eq!(r.position(), 0);
+    assert_eq!(r.read::<u8>(8).unwrap(), 0xB1);
+    r.read_bytes(&mut []).unwrap();
+    assert_eq!(r.position(), 8);
+
+    // unaligned, with only the partial byte left
+    let mut r = BitReader::endian(OneByte(false), LittleEndian);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b001);
+    r.read_bytes(&mut []).unwrap();
+    assert_eq!(r.position(), 3);
+    assert_eq!(r.read::<u8>(5).unwrap(), 0b10110);
+
+    // through a limited reader
+    let mut r = BitReader::endian(OneByte(false), BigEndian).take(0);
+    r.read_bytes(&mut []).unwrap();
+    assert_eq!(r.bits_remaining(), 0);
+}
+
+#[test]
+fn test_reader_lazy_source() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::Read;
+
+    /// Expands (count, byte) pairs one output byte at a time,
+    /// like a streaming decompressor
+    struct RunExpander<R> {
+        compressed: R,
+        run: (u8, u8),
+        produced: usize,
+    }
+
+    impl<R: Read> Read for RunExpander<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.run.0 == 0 {
+                let mut pair = [0; 2];
+                match self.compressed.read_exact(&mut pair) {
+                    Ok(()) => self.run = (pair[0], pair[1]),
+                    Err(_) => return Ok(0),
+                }
+            }
+            match buf.first_mut() {
+                Some(byte) => {
+                    *byte = self.run.1;
+                    self.run.0 -= 1;
+                    self.produced += 1;
+                    Ok(1)
+                }
+                None => Ok(0),
+            }
+        }
+    }
+
+    let compressed: [u8; 6] = [3, 0xFF, 1, 0x00, 2, 0xA5];
+
+    // the reader owns the source and pulls only what it needs
+    let mut r = BitReader::endian(
+        RunExpander {
+            compressed: Cursor::new(&compressed),
+            run: (0, 0),
+            produced: 0,
+        },
+        BigEndian,
+    );
+    assert_eq!(r.read::<u16>(12).unwrap(), 0xFFF);
+    let expander = r.into_reader();
+    assert_eq!(expander.produced, 2);
+
+    // and the source may be handed to another reader to continue
+    let mut r = BitReader::endian(expander, BigEndian);
+    assert_eq!(r.read::<u32>(32).unwrap(), 0xFF00_A5A5);
+    assert!(r.read_bit().is_err());
+    assert_eq!(r.into_reader().produced, 6);
+
+    // a borrowed trait object works the same way
+    let mut expander = RunExpander {
+        compressed: Cursor::new(&compressed),
+        run: (0, 0),
+        produced: 0,
+    };
+    {
+        let source: &mut dyn Read = &mut expander;
+        let mut r = BitReader::endian(source, LittleEndian);
+        assert_eq!(r.read::<u8>(4).unwrap(), 0xF);
+    }
+    assert_eq!(expander.produced, 1);
+    let mut r = BitReader::endian(Box::new(expander) as Box<dyn Read>, LittleEndian);
+    assert_eq!(r.read::<u16>(16).unwrap(), 0xFFFF);
+    assert_eq!(r.read::<u8>(8).unwrap(), 0x00);
+}
+
+#[test]
+fn test_reader_run_lengths() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+
+    let data = [0b0000_0011, 0b0001_0000, 0b1111_0010, 0b0000_0000];
+
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read_run_length(6).unwrap(), 0);
+    let mut runs = r.run_lengths(6, true);
+    assert!(runs.color());
+    assert_eq!(runs.next().unwrap().unwrap(), (true, 49));
+    assert!(!runs.color());
+    assert_eq!(
+        runs.collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![(false, 3), (true, 50), (false, 0)]
+    );
+
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    assert_eq!(
+        r.run_lengths(8, false)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![(false, 3), (true, 16), (false, 242), (true, 0)]
+    );
+
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert!(r.run_lengths(33, false).next().unwrap().is_err());
+
+    // a length cut short is an error rather than the end of the runs
+    let data = [0x00, 0x05, 0x00];
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    let mut runs = r.run_lengths(16, false);
+    assert_eq!(runs.next().unwrap().unwrap(), (false, 5));
+    assert_eq!(
+        runs.next().unwrap().unwrap_err().kind(),
+        std::io::ErrorKind::UnexpectedEof
+    );
+
+    let data = [0b0001_0100, 0b0100_0000];
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    let mut runs = r.run_lengths(5, true);
+    assert_eq!(runs.next().unwrap().unwrap(), (true, 0b10100));
+    assert_eq!(runs.next().unwrap().unwrap(), (false, 0b00000));
+    assert_eq!(runs.next().unwrap().unwrap(), (true, 0b10000));
+    assert!(runs.next().is_none());
+}
+
+#[test]
+fn test_reader_byte_align_at_eof() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian};
+    use std::io::Read;
+
+    /// Yields its single byte, then panics if read again
+    struct OneByte(Option<u8>);
+
+    impl Read for OneByte {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let byte = self.0.take().expect("read after final byte");
+            buf[0] = byte;
+            Ok(1)
+        }
+    }
+
+    let mut r = BitReader::endian(OneByte(Some(0b1011_0001)), BigEndian);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b101);
+    r.byte_align();
+    assert!(r.byte_aligned());
+    r.byte_align();
+    assert_eq!(r.position(), 8);
+
+    let mut r = BitReader::endian(OneByte(Some(0b1011_0001)), LittleEndian);
+    assert_eq!(r.read::<u8>(7).unwrap(), 0b011_0001);
+    r.byte_align();
+    assert!(r.byte_aligned());
+    assert_eq!(r.position(), 8);
+
+    // through a bounded reader as well
+    let mut r = BitReader::endian(OneByte(Some(0b1011_0001)), BigEndian).take(8);
+    assert!(r.read_bit().unwrap());
+    r.byte_align();
+    assert!(r.byte_aligned());
+}
+
+#[test]
+fn test_reader_frames() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, FrameReader, LittleEndian};
+    use std::io::ErrorKind;
+
+    // 12-bit frame 0xA5F, then an 8-bit frame 0x3C, then 4 padding bits
+    let data = [0x00, 0xCA, 0x5F, 0x00, 0x83, 0xC0];
+    let mut frames = FrameReader::new(BitReader::endian(Cursor::new(&data), BigEndian), 12);
+    let mut frame = frames.next().unwrap().unwrap();
+    assert_eq!(frame.bits_remaining(), 12);
+    assert_eq!(frame.read::<u16>(12).unwrap(), 0xA5F);
+    let mut frame = frames.next().unwrap().unwrap();
+    assert_eq!(frame.read::<u8>(8).unwrap(), 0x3C);
+    assert!(frame.read_bit().is_err());
+    assert!(frames.next().is_none());
+    assert_eq!(frames.into_reader().position(), 44);
+
+    // 8-bit lengths, 4-bit frame 0xB, then a 2-bit frame 0b10
+    let data = [0x04, 0x2B, 0x20];
+    let mut frames = FrameReader::new(BitReader::endian(Cursor::new(&data), LittleEndian), 8);
+    let payloads = frames
+        .by_ref()
+        .map(|frame| {
+            let mut frame = frame?;
+            let bits = frame.bits_remaining() as u32;
+            frame.read::<u8>(bits)
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(payloads, vec![0xB, 0b10]);
+    assert!(frames.next().is_none());
+
+    // a payload cut short
+    let data = [0x00, 0xCA, 0x5F, 0x01, 0x03, 0xC0];
+    let mut frames = FrameReader::new(BitReader::endian(Cursor::new(&data), BigEndian), 12);
+    assert!(frames.next().unwrap().is_ok());
+    let err = frames.next().unwrap().err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    // a length cut short
+    let data = [0x00, 0x04, 0xF0, 0x00];
+    let mut frames = FrameReader::new(BitReader::endian(Cursor::new(&data), BigEndian), 16);
+    assert!(frames.next().unwrap().is_ok());
+    let err = frames.next().unwrap().err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+    // leftover bits which fill a length are read as a frame
+    let data = [0b11_101_000];
+    let mut frames = FrameReader::new(BitReader::endian(Cursor::new(&data), BigEndian), 2);
+    let mut frame = frames.next().unwrap().unwrap();
+    assert_eq!(frame.read::<u8>(3).unwrap(), 0b101);
+    let frame = frames.next().unwrap().unwrap();
+    assert_eq!(frame.bits_remaining(), 0);
+    assert!(frames.next().is_none());
+
+    // frames are subject to the reader's allocation limit
+    let data = [0xFF, 0xFF, 0xFF, 0xFF];
+    let reader = BitReader::endian(Cursor::new(&data), BigEndian).with_max_alloc(16);
+    let mut frames = FrameReader::new(reader, 32);
+    let err = frames.next().unwrap().err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+#[should_panic]
+fn test_reader_frames_zero_length_bits() {
+    use bitstream_io::{BigEndian, BitReader, FrameReader};
+
+    let data = [0x00];
+    FrameReader::new(BitReader::endian(Cursor::new(&data), BigEndian), 0);
+}
+
+#[test]
+#[should_panic]
+fn test_reader_frames_wide_length_bits() {
+    use bitstream_io::{BigEndian, BitReader, FrameReader};
+
+    let data = [0x00];
+    FrameReader::new(BitReader::endian(Cursor::new(&data), BigEndian), 33);
+}
+
+#[test]
+fn test_reader_atomic() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian, RewindReader};
+    use std::io::ErrorKind;
+
+    let data = [0xA5, 0x0F, 0x3C];
+
+    // a successful closure consumes its bits
+    let mut r = BitReader::endian(RewindReader::new(Cursor::new(&data), 4), BigEndian);
+    assert_eq!(
+        r.read_atomic(|r| Ok((r.read::<u8>(4)?, r.read::<u8>(8)?)))
+            .unwrap(),
+        (0xA, 0x50)
+    );
+    assert_eq!(r.position(), 12);
+
+    // a failing closure leaves the position unchanged
+    let err = r
+        .read_atomic(|r| {
+            r.read::<u8>(4)?;
+            r.read::<u16>(16)
+        })
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(r.position(), 12);
+    let err = r
+        .read_atomic(|r| {
+            r.skip(3)?;
+            Err::<(), _>(std::io::Error::new(ErrorKind::InvalidData, "bad field"))
+        })
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(r.position(), 12);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0xF3C);
+
+    let mut r = BitReader::endian(RewindReader::new(Cursor::new(&data), 4), LittleEndian);
+    assert_eq!(r.read::<u8>(4).unwrap(), 0x5);
+    assert!(r.read_atomic(|r| r.read::<u32>(24)).is_err());
+    assert_eq!(r.position(), 4);
+    assert_eq!(r.read::<u32>(20).unwrap(), 0x3C0FA);
+
+    // a closure consuming more than the window can't be undone
+    let mut r = BitReader::endian(RewindReader::new(Cursor::new(&data), 1), BigEndian);
+    let err = r
+        .read_atomic(|r| {
+            r.read::<u16>(16)?;
+            r.read::<u16>(16)
+        })
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_reader_parity() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, LittleEndian, ParityReader};
+
+    // 7 data bits with 4 ones, then an even parity bit of 0,
+    // then 6 data bits with 3 ones and an even parity bit of 1
+    let data = [
+        0b1011_0100,
+        0b1010_0111,
+        0b0001_0000,
+        0b0000_0000,
+        0b0000_0001,
+    ];
+    let mut r = ParityReader::new(BitReader::endian(Cursor::new(&data), BigEndian));
+    assert_eq!(r.read::<u8>(7).unwrap(), 0b1011_010);
+    assert!(!r.parity());
+    assert!(!r.read_bit().unwrap());
+    assert!(!r.parity());
+    assert_eq!(r.read_signed::<i8>(6).unwrap(), -23);
+    assert!(r.parity());
+    assert!(r.read_bit().unwrap());
+    assert!(!r.parity());
+
+    // unary codes, skips and whole bytes all count
+    r.reset_parity();
+    assert_eq!(r.read_unary0().unwrap(), 1);
+    assert!(r.parity());
+    r.skip(5).unwrap();
+    assert!(!r.parity());
+    let mut bytes = [0; 1];
+    r.read_bytes(&mut bytes).unwrap();
+    assert_eq!(bytes, [0b0000_0000]);
+    assert!(!r.parity());
+    assert_eq!(r.read_unary1().unwrap(), 9);
+    assert!(r.parity());
+    assert!(r.read_bit().is_err());
+
+    // aligning discards bits without counting them
+    let mut r = ParityReader::new(BitReader::endian(Cursor::new(&data), LittleEndian));
+    assert!(!r.read_bit().unwrap());
+    r.byte_align();
+    assert!(!r.parity());
+    assert_eq!(r.read::<u8>(8).unwrap(), 0b1010_0111);
+    assert!(r.parity());
+    assert_eq!(r.into_reader().position(), 16);
+}
+
+#[test]
+fn test_reader_crc() {
+    use bitstream_io::{BigEndian, BitRead, Crc16Reader, Crc32Reader, LittleEndian};
+    use std::io::ErrorKind;
+
+    // two FLAC-style frames, each followed by its CRC-16
+    let data = [0xA5, 0x0F, 0x3C, 0x2A, 0x4C, 0xA5, 0x0F, 0x3C, 0x2A, 0x4C];
+    let mut r = Crc16Reader::new(Cursor::new(&data), BigEndian, 0x8005, 0);
+    assert_eq!(r.read::<u8>(4).unwrap(), 0xA);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0x50F);
+    assert_eq!(r.read::<u8>(7).unwrap(), 0x1E);
+    r.verify_trailing(16).unwrap();
+    assert_eq!(r.crc(), 0);
+    r.skip(20).unwrap();
+    r.verify_trailing(16).unwrap();
+    assert!(r.read_bit().is_err());
+
+    // narrower widths than the CRC are rejected without reading
+    let mut r = Crc16Reader::new(Cursor::new(&data), BigEndian, 0x8005, 0);
+    r.skip(24).unwrap();
+    for &width in [0, 8, 12, 24].iter() {
+        assert_eq!(
+            r.verify_trailing(width).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+    r.verify_trailing(16).unwrap();
+
+    // a corrupted frame
+    let mut corrupted = data;
+    corrupted[1] ^= 0x10;
+    let mut r = Crc16Reader::new(Cursor::new(&corrupted), BigEndian, 0x8005, 0);
+    r.skip(24).unwrap();
+    let err = r.verify_trailing(16).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    // the next frame is checked afresh
+    r.skip(24).unwrap();
+    r.verify_trailing(16).unwrap();
+
+    // a little-endian stream stores its CRC low byte first
+    let data = [0x12, 0x34, 0x56, 0x78, 0x2B, 0x8A, 0x8A, 0xDF];
+    let mut r = Crc32Reader::new(Cursor::new(&data), LittleEndian, 0x04C1_1DB7, 0xFFFF_FFFF);
+    assert_eq!(r.read::<u32>(32).unwrap(), 0x7856_3412);
+    assert_eq!(r.crc(), 0xDF8A_8A2B);
+    r.verify_trailing(32).unwrap();
+
+    let mut r = Crc32Reader::new(Cursor::new(&data), BigEndian, 0x04C1_1DB7, 0xFFFF_FFFF);
+    r.skip(32).unwrap();
+    let err = r.verify_trailing(32).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "expected 0xdf8a8a2b, got 0x2b8a8adf");
+    r.reset_crc();
+    assert_eq!(r.crc(), 0xFFFF_FFFF);
+    for &width in [0, 8, 12, 16, 33].iter() {
+        assert_eq!(
+            r.verify_trailing(width).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+    assert_eq!(r.into_inner().position(), 8);
+}
+
+#[test]
+fn test_reader_four_orderings() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, ByteOrder, LittleEndian, OrderedBitReader};
+
+    let data = [0x12, 0xB4];
+
+    macro_rules! read_ordered {
+        ($bit_order:expr, $byte_order:expr) => {{
+            let mut r = OrderedBitReader::new(Cursor::new(&data), $bit_order, $byte_order);
+            (r.read::<u16>(12).unwrap(), r.read::<u8>(4).unwrap())
+        }};
+    }
+
+    // (bit order, byte order) => (12-bit value, trailing 4 bits)
+    let table = [
+        (read_ordered!(BigEndian, ByteOrder::Big), (0x12B, 0x4)),
+        (read_ordered!(BigEndian, ByteOrder::Little), (0xB12, 0x4)),
+        (read_ordered!(LittleEndian, ByteOrder::Big), (0x124, 0xB)),
+        (read_ordered!(LittleEndian, ByteOrder::Little), (0x412, 0xB)),
+    ];
+    for (read, expected) in table.iter() {
+        assert_eq!(read, expected);
+    }
+
+    // the matching combinations agree with plain readers
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0x12B);
+    let mut r = BitReader::endian(Cursor::new(&data), LittleEndian);
+    assert_eq!(r.read::<u16>(12).unwrap(), 0x412);
+}
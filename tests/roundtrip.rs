@@ -7,7 +7,7 @@
 // except according to those terms.
 
 extern crate bitstream_io;
-use bitstream_io::{BigEndian, BitReader, BitWriter, LittleEndian};
+use bitstream_io::{BigEndian, BitRead, BitReader, BitWrite, BitWriter, LittleEndian};
 use std::io::Cursor;
 
 macro_rules! define_roundtrip {
@@ -102,3 +102,660 @@ macro_rules! define_unary_roundtrip {
 
 define_unary_roundtrip!(test_unary_roundtrip_be, BigEndian);
 define_unary_roundtrip!(test_unary_roundtrip_le, LittleEndian);
+
+#[test]
+fn test_generic_roundtrip() {
+    fn write_fields<W: BitWrite>(w: &mut W) {
+        w.write(3, 0b101u8).unwrap();
+        w.write_signed(13, -1234i16).unwrap();
+        w.write_unary0(5).unwrap();
+        w.write_unary1(2).unwrap();
+        w.byte_align().unwrap();
+        w.write_bytes(b"ab").unwrap();
+    }
+
+    fn read_fields<R: BitRead>(r: &mut R) {
+        assert_eq!(r.read::<u8>(3).unwrap(), 0b101);
+        assert_eq!(r.read_signed::<i16>(13).unwrap(), -1234);
+        assert_eq!(r.read_unary0().unwrap(), 5);
+        assert_eq!(r.read_unary1().unwrap(), 2);
+        r.byte_align();
+        let mut buf = [0; 2];
+        r.read_bytes(&mut buf).unwrap();
+        assert_eq!(&buf, b"ab");
+    }
+
+    let mut output = Vec::new();
+    write_fields(&mut BitWriter::endian(&mut output, BigEndian));
+    read_fields(&mut BitReader::endian(Cursor::new(&output), BigEndian));
+
+    let mut output = Vec::new();
+    write_fields(&mut BitWriter::endian(&mut output, LittleEndian));
+    read_fields(&mut BitReader::endian(Cursor::new(&output), LittleEndian));
+}
+
+#[test]
+fn test_mixed_roundtrip_le() {
+    let mut output = Vec::new();
+    {
+        let mut writer = BitWriter::endian(&mut output, LittleEndian);
+        writer.write(3, 0b110u8).unwrap();
+        writer.write(13, 0x1ABCu16).unwrap();
+        writer.write_bytes(b"xyz").unwrap();
+        writer.write(3, 0b101u8).unwrap();
+        writer.byte_align().unwrap();
+    }
+    // the final partial byte is padded in its high bits
+    assert_eq!(output.last(), Some(&0b0000_0101));
+
+    let mut reader = BitReader::endian(Cursor::new(&output), LittleEndian);
+    assert_eq!(reader.read::<u8>(3).unwrap(), 0b110);
+    assert_eq!(reader.read::<u16>(13).unwrap(), 0x1ABC);
+    let mut buf = [0; 3];
+    reader.read_bytes(&mut buf).unwrap();
+    assert_eq!(&buf, b"xyz");
+    assert_eq!(reader.read::<u8>(3).unwrap(), 0b101);
+    assert_eq!(reader.read::<u8>(5).unwrap(), 0);
+}
+
+#[test]
+fn test_switched_endian_roundtrip() {
+    let mut output = Vec::new();
+    {
+        let mut writer = BitWriter::endian(&mut output, BigEndian);
+        writer.write(4, 0b1001u8).unwrap();
+        writer.write(20, 0xABCDEu32).unwrap();
+        writer.byte_align().unwrap();
+        let mut writer = BitWriter::endian(writer.into_writer(), LittleEndian);
+        writer.write(12, 0x123u16).unwrap();
+        writer.write_signed(7, -5i8).unwrap();
+        writer.byte_align().unwrap();
+    }
+    assert_eq!(output, [0x9A, 0xBC, 0xDE, 0x23, 0xB1, 0x07]);
+
+    let mut reader = BitReader::endian(Cursor::new(&output), BigEndian);
+    assert_eq!(reader.read::<u8>(4).unwrap(), 0b1001);
+    let mut reader = reader.try_into_endian(LittleEndian).unwrap_err();
+    assert_eq!(reader.read::<u32>(20).unwrap(), 0xABCDE);
+    let mut reader = reader.try_into_endian(LittleEndian).ok().unwrap();
+    assert_eq!(reader.position(), 24);
+    assert_eq!(reader.read::<u16>(12).unwrap(), 0x123);
+    assert_eq!(reader.read_signed::<i8>(7).unwrap(), -5);
+}
+
+macro_rules! define_float_roundtrip {
+    ($func_name:ident, $endianness:ident) => {
+        #[test]
+        fn $func_name() {
+            let f32s = [
+                0.0f32,
+                -0.0,
+                -1.5,
+                f32::MAX,
+                f32::MIN_POSITIVE,
+                f32::INFINITY,
+                // the smallest and largest subnormals
+                f32::from_bits(1),
+                f32::from_bits(0x007F_FFFF),
+            ];
+            let f64s = [
+                0.0f64,
+                -0.0,
+                -1.5,
+                f64::MAX,
+                f64::EPSILON,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::from_bits(1),
+                f64::from_bits(0x000F_FFFF_FFFF_FFFF),
+            ];
+
+            let mut output = Vec::new();
+            {
+                let mut writer = BitWriter::endian(&mut output, $endianness);
+                // start unaligned so values straddle byte boundaries
+                writer.write(3, 0b101u8).unwrap();
+                for &f in f32s.iter() {
+                    writer.write_f32(f).unwrap();
+                }
+                writer.write_f32(f32::NAN).unwrap();
+                for &f in f64s.iter() {
+                    writer.write_f64(f).unwrap();
+                }
+                writer.write_f64(f64::NAN).unwrap();
+                writer.byte_align().unwrap();
+            }
+
+            let mut reader = BitReader::endian(Cursor::new(&output), $endianness);
+            assert_eq!(reader.read::<u8>(3).unwrap(), 0b101);
+            for &f in f32s.iter() {
+                assert_eq!(reader.read_f32().unwrap().to_bits(), f.to_bits());
+            }
+            assert!(reader.read_f32().unwrap().is_nan());
+            for &f in f64s.iter() {
+                assert_eq!(reader.read_f64().unwrap().to_bits(), f.to_bits());
+            }
+            assert!(reader.read_f64().unwrap().is_nan());
+        }
+    };
+}
+
+define_float_roundtrip!(test_float_roundtrip_be, BigEndian);
+define_float_roundtrip!(test_float_roundtrip_le, LittleEndian);
+
+macro_rules! define_wide_roundtrip {
+    ($func_name:ident, $endianness:ident) => {
+        #[test]
+        fn $func_name() {
+            let mut output: Vec<u8> = Vec::new();
+            {
+                let mut writer = BitWriter::endian(&mut output, $endianness);
+                for bits in 1..=64u32 {
+                    let max = u64::MAX >> (64 - bits);
+                    writer.write(bits, max).unwrap();
+                    writer.write(bits, 0x5555_5555_5555_5555u64 & max).unwrap();
+                    writer.write(bits, 0u64).unwrap();
+                }
+                writer.byte_align().unwrap();
+            }
+            let mut reader = BitReader::endian(Cursor::new(&output), $endianness);
+            for bits in 1..=64u32 {
+                let max = u64::MAX >> (64 - bits);
+                assert_eq!(reader.read::<u64>(bits).unwrap(), max);
+                assert_eq!(
+                    reader.read::<u64>(bits).unwrap(),
+                    0x5555_5555_5555_5555u64 & max
+                );
+                assert_eq!(reader.read::<u64>(bits).unwrap(), 0);
+            }
+        }
+    };
+}
+
+define_wide_roundtrip!(test_wide_roundtrip_be, BigEndian);
+define_wide_roundtrip!(test_wide_roundtrip_le, LittleEndian);
+
+#[test]
+fn test_rice_roundtrip() {
+    use std::io::ErrorKind;
+
+    // FLAC residuals -1, 7, -20 with a Rice parameter of 4
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    for &residual in [-1, 7, -20].iter() {
+        writer.write_rice_signed(4, residual).unwrap();
+    }
+    writer.byte_align().unwrap();
+    let output = writer.into_writer();
+    assert_eq!(output, [0b1000_1111, 0b1000_1011, 0b1000_0000]);
+    let mut reader = BitReader::endian(Cursor::new(&output), BigEndian);
+    for &residual in [-1, 7, -20].iter() {
+        assert_eq!(reader.read_rice_signed(4).unwrap(), residual);
+    }
+
+    for &k in [0, 1, 5, 31, 32].iter() {
+        let values = [0, 1, 2, 100, u32::MAX >> (32 - k.max(1)), u32::MAX >> 1];
+        let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
+        for &value in values.iter().filter(|&&v| k >= 20 || v < 2048) {
+            writer.write_rice(k, value).unwrap();
+            writer.write_rice_signed(k, value as i32).unwrap();
+            writer.write_rice_signed(k, -(value as i32)).unwrap();
+        }
+        writer.byte_align().unwrap();
+        let output = writer.into_writer();
+        let mut reader = BitReader::endian(Cursor::new(&output), LittleEndian);
+        for &value in values.iter().filter(|&&v| k >= 20 || v < 2048) {
+            assert_eq!(reader.read_rice(k).unwrap(), value);
+            assert_eq!(reader.read_rice_signed(k).unwrap(), value as i32);
+            assert_eq!(reader.read_rice_signed(k).unwrap(), -(value as i32));
+        }
+    }
+
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    assert_eq!(
+        writer.write_rice(33, 0).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(writer.into_writer(), []);
+
+    // a quotient of 2 with 31 bits of remainder exceeds 32 bits
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    writer.write_unary1(2).unwrap();
+    writer.write(31, 0u32).unwrap();
+    writer.byte_align().unwrap();
+    let output = writer.into_writer();
+    let mut reader = BitReader::endian(Cursor::new(&output), BigEndian);
+    assert_eq!(
+        reader.read_rice(31).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn test_gray_roundtrip() {
+    // the 3-bit Gray sequence
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    for value in 0..8 {
+        writer.write_gray(3, value).unwrap();
+    }
+    writer.byte_align().unwrap();
+    let output = writer.into_writer();
+    let mut reader = BitReader::endian(Cursor::new(&output), BigEndian);
+    for &code in [0, 1, 3, 2, 6, 7, 5, 4].iter() {
+        assert_eq!(reader.read::<u32>(3).unwrap(), code);
+    }
+    let mut reader = BitReader::endian(Cursor::new(&output), BigEndian);
+    for value in 0..8 {
+        assert_eq!(reader.read_gray(3).unwrap(), value);
+    }
+
+    for &bits in [1, 7, 16, 31, 32].iter() {
+        let max = u32::MAX >> (32 - bits);
+        let values = [0, 1, max / 3, max - 1, max];
+        let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
+        for &value in values.iter() {
+            writer.write_gray(bits, value).unwrap();
+        }
+        writer.byte_align().unwrap();
+        let output = writer.into_writer();
+        let mut reader = BitReader::endian(Cursor::new(&output), LittleEndian);
+        for &value in values.iter() {
+            assert_eq!(reader.read_gray(bits).unwrap(), value);
+        }
+    }
+
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    assert!(writer.write_gray(3, 8).is_err());
+}
+
+#[test]
+fn test_bit_roundtrip() {
+    let bits: Vec<bool> = (0..8).rev().map(|i| (0xA5 >> i) & 1 == 1).collect();
+
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    for &bit in bits.iter() {
+        writer.write_bit(bit).unwrap();
+    }
+    let output = writer.into_writer();
+    assert_eq!(output, [0xA5]);
+    let mut reader = BitReader::endian(Cursor::new(&output), BigEndian);
+    for &bit in bits.iter() {
+        assert_eq!(reader.read_bit().unwrap(), bit);
+    }
+
+    let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
+    for &bit in bits.iter() {
+        writer.write_bit(bit).unwrap();
+    }
+    let output = writer.into_writer();
+    assert_eq!(output, [0xA5]);
+    let mut reader = BitReader::endian(Cursor::new(&output), LittleEndian);
+    for &bit in bits.iter() {
+        assert_eq!(reader.read_bit().unwrap(), bit);
+    }
+}
+
+#[test]
+fn test_unary_limited_roundtrip() {
+    use std::io::ErrorKind;
+
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    writer.write_unary0_limited(0, 20).unwrap();
+    writer.write_unary0_limited(20, 20).unwrap();
+    assert_eq!(
+        writer.write_unary0_limited(21, 20).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        writer.write_unary0_limited(u32::MAX, 0).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    writer.write_unary0_limited(5, 20).unwrap();
+    assert_eq!(writer.bits_written(), 1 + 21 + 6);
+    writer.byte_align().unwrap();
+    let output = writer.into_writer();
+
+    let mut reader = BitReader::endian(Cursor::new(&output), BigEndian);
+    assert_eq!(reader.read_unary0().unwrap(), 0);
+    assert_eq!(reader.read_unary0_max(21).unwrap(), 20);
+    assert_eq!(reader.read_unary0().unwrap(), 5);
+}
+
+#[test]
+fn test_exp_golomb_roundtrip() {
+    use std::io::ErrorKind;
+
+    // 0 is a single 1 bit
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    writer.write_exp_golomb(0).unwrap();
+    assert_eq!(writer.bits_written(), 1);
+    writer.byte_align().unwrap();
+    assert_eq!(writer.into_writer(), [0b1000_0000]);
+
+    let values: Vec<u32> = (0..300)
+        .chain(
+            [1 << 16, (1 << 31) - 1, 1 << 31, u32::MAX - 1, u32::MAX]
+                .iter()
+                .copied(),
+        )
+        .collect();
+    let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
+    for &value in values.iter() {
+        writer.write_exp_golomb(value).unwrap();
+    }
+    writer.byte_align().unwrap();
+    let output = writer.into_writer();
+    let mut reader = BitReader::endian(Cursor::new(&output), LittleEndian);
+    for &value in values.iter() {
+        assert_eq!(reader.read_exp_golomb().unwrap(), value);
+    }
+
+    let values: Vec<i32> = (-300..300)
+        .chain([i32::MIN + 1, i32::MAX - 1, i32::MAX].iter().copied())
+        .collect();
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    for &value in values.iter() {
+        writer.write_exp_golomb_signed(value).unwrap();
+    }
+    assert_eq!(
+        writer.write_exp_golomb_signed(i32::MIN).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    writer.byte_align().unwrap();
+    let output = writer.into_writer();
+    let mut reader = BitReader::endian(Cursor::new(&output), BigEndian);
+    for &value in values.iter() {
+        assert_eq!(reader.read_exp_golomb_signed().unwrap(), value);
+    }
+}
+
+#[test]
+fn test_zigzag_roundtrip() {
+    use std::io::ErrorKind;
+
+    // the mapping interleaves negative and positive values
+    let mut reader = BitReader::endian(Cursor::new([0x01, 0x23]), BigEndian);
+    for &value in &[0, -1, 1, -2] {
+        assert_eq!(reader.read_zigzag(4).unwrap(), value);
+    }
+
+    let values: Vec<i32> = (-300..300)
+        .chain([i32::MIN, i32::MAX].iter().copied())
+        .collect();
+    let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
+    for &value in values.iter() {
+        writer.write_zigzag(32, value).unwrap();
+    }
+    assert_eq!(
+        writer.write_zigzag(3, -5).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    let output = writer.into_writer();
+    let mut reader = BitReader::endian(Cursor::new(&output), LittleEndian);
+    for &value in values.iter() {
+        assert_eq!(reader.read_zigzag(32).unwrap(), value);
+    }
+
+    let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+    for value in -3..4 {
+        writer.write_zigzag(3, value).unwrap();
+    }
+    writer.byte_align().unwrap();
+    let output = writer.into_writer();
+    let mut reader = BitReader::endian(Cursor::new(&output), BigEndian);
+    for value in -3..4 {
+        assert_eq!(reader.read_zigzag(3).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_reversed_roundtrip() {
+    use std::io::ErrorKind;
+
+    let mut reader = BitReader::endian(Cursor::new([0b1000_0000]), BigEndian);
+    assert_eq!(reader.read_reversed(4).unwrap(), 0b0001);
+    assert_eq!(reader.read_reversed(0).unwrap(), 0);
+    let mut reader = BitReader::endian(Cursor::new([0b0000_0001]), LittleEndian);
+    assert_eq!(reader.read_reversed(4).unwrap(), 0b1000);
+
+    let values: [(u32, u32); 7] = [
+        (1, 1),
+        (4, 0b0001),
+        (5, 0b10110),
+        (12, 0xABC),
+        (0, 0),
+        (31, 0x1234_5678),
+        (32, 0x8000_0001),
+    ];
+    for &(bits, value) in values.iter() {
+        let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+        writer.write_reversed(bits, value).unwrap();
+        writer.byte_align().unwrap();
+        let output = writer.into_writer();
+        let mut reader = BitReader::endian(Cursor::new(&output), BigEndian);
+        assert_eq!(reader.read_reversed(bits).unwrap(), value);
+        let mut reader = BitReader::endian(Cursor::new(&output), BigEndian);
+        assert_eq!(
+            reader.read::<u32>(bits).unwrap(),
+            value.reverse_bits().checked_shr(32 - bits).unwrap_or(0)
+        );
+    }
+
+    let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
+    assert_eq!(
+        writer.write_reversed(4, 0b1_0000).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(
+        writer.write_reversed(33, 0).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert_eq!(writer.bits_written(), 0);
+}
+
+#[test]
+fn test_elias_delta_roundtrip() {
+    use std::io::ErrorKind;
+
+    // the canonical codes for 1 through 8
+    let codes: [(u32, &str); 8] = [
+        (1, "1"),
+        (2, "0100"),
+        (3, "0101"),
+        (4, "01100"),
+        (5, "01101"),
+        (6, "01110"),
+        (7, "01111"),
+        (8, "00100000"),
+    ];
+    for &(value, code) in codes.iter() {
+        let mut writer = BitWriter::endian(Vec::new(), BigEndian);
+        writer.write_elias_delta(value).unwrap();
+        assert_eq!(writer.bits_written(), code.len() as u64);
+        writer.byte_align().unwrap();
+        let output = writer.into_writer();
+        let mut reader = BitReader::endian(Cursor::new(&output), BigEndian);
+        let bits: String = (0..code.len())
+            .map(|_| if reader.read_bit().unwrap() { '1' } else { '0' })
+            .collect();
+        assert_eq!(bits, code);
+        let mut reader = BitReader::endian(Cursor::new(&output), BigEndian);
+        assert_eq!(reader.read_elias_delta().unwrap(), value);
+    }
+
+    let values: Vec<u32> = (1..300)
+        .chain([1 << 16, (1 << 31) - 1, 1 << 31, u32::MAX].iter().copied())
+        .collect();
+    let mut writer = BitWriter::endian(Vec::new(), LittleEndian);
+    for &value in values.iter() {
+        writer.write_elias_delta(value).unwrap();
+    }
+    assert_eq!(
+        writer.write_elias_delta(0).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    writer.byte_align().unwrap();
+    let output = writer.into_writer();
+    let mut reader = BitReader::endian(Cursor::new(&output), LittleEndian);
+    for &value in values.iter() {
+        assert_eq!(reader.read_elias_delta().unwrap(), value);
+    }
+
+    // lengths longer than 32 bits are rejected
+    let mut reader = BitReader::endian(Cursor::new([0b0000_0110, 0b0001_0000]), BigEndian);
+    assert_eq!(
+        reader.read_elias_delta().unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn test_fixed_roundtrip() {
+    // Q1.15
+    let data = [0x40, 0x00, 0x80, 0x00, 0x7F, 0xFF, 0xC0, 0x00];
+    let mut r = BitReader::endian(Cursor::new(&data), BigEndian);
+    assert_eq!(r.read_fixed(1, 15).unwrap(), 0.5);
+    assert_eq!(r.read_fixed(1, 15).unwrap(), -1.0);
+    assert_eq!(r.read_fixed(1, 15).unwrap(), 1.0 - 1.0 / 32768.0);
+    assert_eq!(r.read_fixed(1, 15).unwrap(), -0.5);
+
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    w.write_fixed(1, 15, 0.5).unwrap();
+    w.write_fixed(1, 15, -1.0).unwrap();
+    w.write_fixed(1, 15, 1.0 - 1.0 / 32768.0).unwrap();
+    w.write_fixed(1, 15, -0.5).unwrap();
+    assert!(w.write_fixed(1, 15, 1.0).is_err());
+    assert!(w.write_fixed(1, 15, -1.5).is_err());
+    assert!(w.write_fixed(1, 15, f64::NAN).is_err());
+    assert!(w.write_fixed(40, 40, 0.0).is_err());
+    assert_eq!(w.into_writer(), data);
+
+    // values are rounded to the nearest step
+    let mut w = BitWriter::endian(Vec::new(), LittleEndian);
+    w.write_fixed(4, 4, 1.03).unwrap();
+    w.write_fixed(4, 4, -1.03).unwrap();
+    w.write_fixed(8, 24, 3.25).unwrap();
+    w.write_fixed(0, 64, 0.25).unwrap();
+    let bytes = w.into_writer();
+    assert_eq!(&bytes[0..2], [0x10, 0xF0]);
+
+    let mut r = BitReader::endian(Cursor::new(&bytes), LittleEndian);
+    assert_eq!(r.read_fixed(4, 4).unwrap(), 1.0);
+    assert_eq!(r.read_fixed(4, 4).unwrap(), -1.0);
+    assert_eq!(r.read_fixed(8, 24).unwrap(), 3.25);
+    assert_eq!(r.read_fixed(0, 64).unwrap(), 0.25);
+    assert!(r.read_fixed(33, 32).is_err());
+}
+
+macro_rules! define_fixed_step_roundtrip {
+    ($func_name:ident, $endianness:ident) => {
+        #[test]
+        fn $func_name() {
+            // any in-range value comes back within one step
+            for &(int_bits, frac_bits) in [(1, 15), (4, 4), (8, 24), (2, 30)].iter() {
+                let step = 1.0 / (1u64 << frac_bits) as f64;
+                let max = (1u64 << (int_bits - 1)) as f64 - step;
+                let values = (-199..=199)
+                    .map(|i| f64::from(i) / 199.0 * max)
+                    .collect::<Vec<_>>();
+
+                let mut w = BitWriter::endian(Vec::new(), $endianness);
+                w.write(3, 0b101u8).unwrap();
+                for &v in values.iter() {
+                    w.write_fixed(int_bits, frac_bits, v).unwrap();
+                }
+                w.byte_align().unwrap();
+                let bytes = w.into_writer();
+
+                let mut r = BitReader::endian(Cursor::new(&bytes), $endianness);
+                assert_eq!(r.read::<u8>(3).unwrap(), 0b101);
+                for &v in values.iter() {
+                    let read = r.read_fixed(int_bits, frac_bits).unwrap();
+                    assert!((v - read).abs() <= step, "{} read back as {}", v, read);
+                }
+            }
+        }
+    };
+}
+
+define_fixed_step_roundtrip!(test_fixed_step_roundtrip_be, BigEndian);
+define_fixed_step_roundtrip!(test_fixed_step_roundtrip_le, LittleEndian);
+
+#[test]
+fn test_byte_order_roundtrip() {
+    use bitstream_io::{ByteOrder, OrderedBitReader, OrderedBitWriter};
+
+    fn roundtrip<E: bitstream_io::Endianness + Copy>(
+        bit_order: E,
+        byte_order: ByteOrder,
+        expected: [u8; 4],
+    ) {
+        let mut w = OrderedBitWriter::new(Vec::new(), bit_order, byte_order);
+        w.write(16, 0x1234u16).unwrap();
+        w.write(12, 0xABCu16).unwrap();
+        w.write(4, 0xDu8).unwrap();
+        assert_eq!(&w.into_bitwriter().into_writer()[2..], &expected[2..]);
+
+        let mut w = OrderedBitWriter::new(Vec::new(), bit_order, byte_order);
+        w.write(16, 0x1234u16).unwrap();
+        w.write(12, 0xABCu16).unwrap();
+        w.write(4, 0xDu8).unwrap();
+        w.write(0, 0u8).unwrap();
+        w.write(1, 1u8).unwrap();
+        w.write(20, 0xF00BAu32).unwrap();
+        w.write(64, 0x0123_4567_89AB_CDEFu64).unwrap();
+        w.write_signed(12, -1000i16).unwrap();
+        w.write_signed(8, -128i8).unwrap();
+        w.write_signed(32, -2i32).unwrap();
+        w.write_signed(3, 3i8).unwrap();
+        w.write_signed(0, 0i8).unwrap();
+        assert!(w.write(9, 0u8).is_err());
+        assert!(w.write(4, 16u8).is_err());
+        assert!(w.write_signed(4, 8i8).is_err());
+        assert!(w.write_signed(0, -1i8).is_err());
+        w.byte_align().unwrap();
+        let bytes = w.into_bitwriter().into_writer();
+        assert_eq!(&bytes[0..4], &expected);
+
+        let mut r = OrderedBitReader::new(Cursor::new(&bytes), bit_order, byte_order);
+        assert_eq!(r.byte_order(), byte_order);
+        assert_eq!(r.read::<u16>(16).unwrap(), 0x1234);
+        assert_eq!(r.read::<u16>(12).unwrap(), 0xABC);
+        assert_eq!(r.read::<u8>(4).unwrap(), 0xD);
+        assert_eq!(r.read::<u8>(0).unwrap(), 0);
+        assert_eq!(r.read::<u8>(1).unwrap(), 1);
+        assert_eq!(r.read::<u32>(20).unwrap(), 0xF00BA);
+        assert_eq!(r.read::<u64>(64).unwrap(), 0x0123_4567_89AB_CDEF);
+        assert_eq!(r.read_signed::<i16>(12).unwrap(), -1000);
+        assert_eq!(r.read_signed::<i8>(8).unwrap(), -128);
+        assert_eq!(r.read_signed::<i32>(32).unwrap(), -2);
+        assert_eq!(r.read_signed::<i8>(3).unwrap(), 3);
+        assert_eq!(r.read_signed::<i8>(0).unwrap(), 0);
+        assert!(r.read::<u8>(9).is_err());
+        r.byte_align();
+        assert!(r.read_bit().is_err());
+    }
+
+    roundtrip(BigEndian, ByteOrder::Big, [0x12, 0x34, 0xAB, 0xCD]);
+    roundtrip(BigEndian, ByteOrder::Little, [0x34, 0x12, 0xBC, 0xAD]);
+    roundtrip(LittleEndian, ByteOrder::Big, [0x12, 0x34, 0xAB, 0xDC]);
+    roundtrip(LittleEndian, ByteOrder::Little, [0x34, 0x12, 0xBC, 0xDA]);
+
+    // matching orders are the same as an ordinary reader
+    let data = [0xB1, 0xED, 0x3B, 0xC1, 0x7F, 0x80];
+    let mut ordered = OrderedBitReader::new(Cursor::new(&data), BigEndian, ByteOrder::Big);
+    let mut plain = BitReader::endian(Cursor::new(&data), BigEndian);
+    for &bits in &[3, 13, 9, 1, 17, 0, 5] {
+        assert_eq!(
+            ordered.read::<u32>(bits).unwrap(),
+            plain.read::<u32>(bits).unwrap()
+        );
+    }
+    let mut ordered = OrderedBitReader::new(Cursor::new(&data), LittleEndian, ByteOrder::Little);
+    let mut plain = BitReader::endian(Cursor::new(&data), LittleEndian);
+    for &bits in &[3, 13, 9, 1, 17, 5] {
+        assert_eq!(
+            ordered.read_signed::<i32>(bits).unwrap(),
+            plain.read_signed::<i32>(bits).unwrap()
+        );
+    }
+}
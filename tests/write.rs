@@ -6,6 +6,8 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#![allow(clippy::bool_assert_comparison, clippy::unusual_byte_groupings)]
+
 extern crate bitstream_io;
 
 #[test]
@@ -150,7 +152,7 @@ fn test_write_queue_edge_le() {
 
 #[test]
 fn test_writer_be() {
-    use bitstream_io::{BigEndian, BitWriter};
+    use bitstream_io::{BigEndian, BitWrite, BitWriter};
 
     let final_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
 
@@ -270,7 +272,7 @@ fn test_writer_be() {
 
 #[test]
 fn test_writer_edge_cases_be() {
-    use bitstream_io::{BigEndian, BitWriter};
+    use bitstream_io::{BigEndian, BitWrite, BitWriter};
 
     let final_data: Vec<u8> = vec![
         0, 0, 0, 0, 255, 255, 255, 255, 128, 0, 0, 0, 127, 255, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -306,7 +308,7 @@ fn test_writer_edge_cases_be() {
 #[test]
 fn test_writer_huffman_be() {
     use bitstream_io::huffman::compile_write_tree;
-    use bitstream_io::{BigEndian, BitWriter};
+    use bitstream_io::{BigEndian, BitWrite, BitWriter};
 
     let final_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
     let tree = compile_write_tree(vec![
@@ -339,7 +341,7 @@ fn test_writer_huffman_be() {
 
 #[test]
 fn test_writer_le() {
-    use bitstream_io::{BitWriter, LittleEndian};
+    use bitstream_io::{BitWrite, BitWriter, LittleEndian};
 
     let final_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
 
@@ -459,7 +461,7 @@ fn test_writer_le() {
 
 #[test]
 fn test_writer_edge_cases_le() {
-    use bitstream_io::{BitWriter, LittleEndian};
+    use bitstream_io::{BitWrite, BitWriter, LittleEndian};
 
     let final_data: Vec<u8> = vec![
         0, 0, 0, 0, 255, 255, 255, 255, 0, 0, 0, 128, 255, 255, 255, 127, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -495,7 +497,7 @@ fn test_writer_edge_cases_le() {
 #[test]
 fn test_writer_huffman_le() {
     use bitstream_io::huffman::compile_write_tree;
-    use bitstream_io::{BitWriter, LittleEndian};
+    use bitstream_io::{BitWrite, BitWriter, LittleEndian};
 
     let final_data: [u8; 4] = [0xB1, 0xED, 0x3B, 0xC1];
     let tree = compile_write_tree(vec![
@@ -553,7 +555,7 @@ impl std::io::Write for LimitedWriter {
 
 #[test]
 fn test_writer_io_errors_be() {
-    use bitstream_io::{BigEndian, BitWriter};
+    use bitstream_io::{BigEndian, BitWrite, BitWriter};
     use std::io::ErrorKind;
 
     /*individual bits*/
@@ -643,7 +645,7 @@ fn test_writer_io_errors_be() {
 
 #[test]
 fn test_writer_io_errors_le() {
-    use bitstream_io::{BitWriter, LittleEndian};
+    use bitstream_io::{BitWrite, BitWriter, LittleEndian};
     use std::io::ErrorKind;
 
     /*individual bits*/
@@ -733,7 +735,7 @@ fn test_writer_io_errors_le() {
 
 #[test]
 fn test_writer_bits_errors() {
-    use bitstream_io::{BigEndian, BitWriter, LittleEndian};
+    use bitstream_io::{BigEndian, BitWrite, BitWriter, LittleEndian};
     use std::io::{sink, ErrorKind};
 
     let mut w = BitWriter::endian(sink(), BigEndian);
@@ -884,3 +886,442 @@ fn test_writer_bits_errors() {
         ErrorKind::InvalidInput
     );
 }
+
+#[test]
+fn test_counter() {
+    use bitstream_io::{BigEndian, BitCounter, BitWrite, BitWriter, LittleEndian};
+
+    fn encode<W: BitWrite>(w: &mut W) {
+        w.write_bit(true).unwrap();
+        w.write(13, 0x1ABCu16).unwrap();
+        w.write_signed(7, -5i8).unwrap();
+        w.write_unary0(10).unwrap();
+        w.write_unary1(70).unwrap();
+        w.byte_align().unwrap();
+        w.write_bytes(b"abc").unwrap();
+        w.write(3, 0b101u8).unwrap();
+        w.byte_align().unwrap();
+    }
+
+    let mut counter = BitCounter::new();
+    encode(&mut counter);
+    assert!(counter.byte_aligned());
+
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    encode(&mut w);
+    assert_eq!(counter.written(), w.into_writer().len() as u64 * 8);
+
+    let mut w = BitWriter::endian(Vec::new(), LittleEndian);
+    encode(&mut w);
+    assert_eq!(counter.written(), w.into_writer().len() as u64 * 8);
+
+    let mut counter = BitCounter::new();
+    assert!(counter.write(9, 0u8).is_err());
+    assert!(counter.write(2, 4u8).is_err());
+    assert!(counter.write_signed(9, 0i8).is_err());
+    assert_eq!(counter.written(), 0);
+    counter.write(0, 0u8).unwrap();
+    assert!(counter.byte_aligned());
+    counter.write(5, 0u8).unwrap();
+    assert!(!counter.byte_aligned());
+    assert_eq!(counter.padding_bits(), 3);
+    counter.byte_align().unwrap();
+    assert_eq!(counter.written(), 8);
+    assert_eq!(counter.padding_bits(), 0);
+
+    // padding matches a real writer's
+    let mut counter = BitCounter::new();
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    for bits in [3, 5, 8, 1, 13, 7, 0, 2].iter().copied() {
+        counter.write(bits, 0u16).unwrap();
+        w.write(bits, 0u16).unwrap();
+        assert_eq!(counter.padding_bits(), w.padding_bits());
+    }
+}
+
+#[test]
+fn test_writer_long_unary() {
+    use bitstream_io::{BigEndian, BitWrite, BitWriter, LittleEndian};
+
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    w.write_unary0(1000).unwrap();
+    w.byte_align().unwrap();
+    let output = w.into_writer();
+    assert_eq!(output.len(), 126);
+    assert!(output[0..125].iter().all(|&b| b == 0xFF));
+    assert_eq!(output[125], 0b0000_0000);
+
+    let mut w = BitWriter::endian(Vec::new(), LittleEndian);
+    w.write_unary1(1000).unwrap();
+    w.byte_align().unwrap();
+    let output = w.into_writer();
+    assert_eq!(output.len(), 126);
+    assert!(output[0..125].iter().all(|&b| b == 0x00));
+    assert_eq!(output[125], 0b0000_0001);
+}
+
+#[test]
+fn test_writer_flush() {
+    use bitstream_io::{BigEndian, BitWrite, BitWriter, LittleEndian};
+    use std::io::BufWriter;
+
+    let mut output = Vec::new();
+    {
+        let mut w = BitWriter::endian(BufWriter::new(&mut output), BigEndian);
+        w.write(12, 0xFFFu16).unwrap();
+        w.flush().unwrap();
+        assert!(w.byte_aligned());
+        w.flush().unwrap();
+    }
+    assert_eq!(output, [0xFF, 0xF0]);
+
+    let mut w = BitWriter::endian(Vec::new(), LittleEndian);
+    w.write(12, 0xFFFu16).unwrap();
+    w.flush().unwrap();
+    assert_eq!(w.into_writer(), [0xFF, 0x0F]);
+}
+
+#[test]
+fn test_writer_unchecked() {
+    use bitstream_io::{BigEndian, BitWrite, BitWriter, LittleEndian};
+    use std::io::ErrorKind;
+
+    let fields: [(u32, u32); 6] = [
+        (2, 2),
+        (3, 6),
+        (5, 7),
+        (3, 5),
+        (19, 0x53BC1),
+        (32, 0xFFFF_FFFF),
+    ];
+
+    let mut checked = BitWriter::endian(Vec::new(), BigEndian);
+    let mut unchecked = BitWriter::endian(Vec::new(), BigEndian);
+    for &(bits, value) in fields.iter() {
+        checked.write(bits, value).unwrap();
+        unchecked.write_unchecked(bits, value).unwrap();
+    }
+    assert_eq!(checked.into_writer(), unchecked.into_writer());
+
+    let mut checked = BitWriter::endian(Vec::new(), LittleEndian);
+    let mut unchecked = BitWriter::endian(Vec::new(), LittleEndian);
+    for &(bits, value) in fields.iter() {
+        checked.write(bits, value).unwrap();
+        unchecked.write_unchecked(bits, value).unwrap();
+    }
+    assert_eq!(checked.into_writer(), unchecked.into_writer());
+
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    assert_eq!(
+        w.write(4, 0xFFu8).unwrap_err().kind(),
+        ErrorKind::InvalidInput
+    );
+    assert!(w.byte_aligned());
+    assert_eq!(w.into_writer(), []);
+}
+
+#[test]
+fn test_writer_unaligned_bytes() {
+    use bitstream_io::{BigEndian, BitRead, BitReader, BitWrite, BitWriter, LittleEndian};
+    use std::io::Cursor;
+
+    let data: Vec<u8> = (0..1024u32).map(|i| (i * 31 + i / 7) as u8).collect();
+
+    let mut expected = BitWriter::endian(Vec::new(), BigEndian);
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    expected.write(5, 0b10110u8).unwrap();
+    w.write(5, 0b10110u8).unwrap();
+    for &b in &data {
+        expected.write(8, b).unwrap();
+    }
+    w.write_bytes(&data).unwrap();
+    expected.write(3, 0b011u8).unwrap();
+    w.write(3, 0b011u8).unwrap();
+    let output = w.into_writer();
+    assert_eq!(output, expected.into_writer());
+
+    let mut r = BitReader::endian(Cursor::new(&output), BigEndian);
+    assert_eq!(r.read::<u8>(5).unwrap(), 0b10110);
+    let mut buf = vec![0; 1024];
+    r.read_bytes(&mut buf).unwrap();
+    assert_eq!(buf, data);
+    assert_eq!(r.read::<u8>(3).unwrap(), 0b011);
+
+    let mut expected = BitWriter::endian(Vec::new(), LittleEndian);
+    let mut w = BitWriter::endian(Vec::new(), LittleEndian);
+    expected.write(5, 0b10110u8).unwrap();
+    w.write(5, 0b10110u8).unwrap();
+    for &b in &data {
+        expected.write(8, b).unwrap();
+    }
+    w.write_bytes(&data).unwrap();
+    expected.write(3, 0b011u8).unwrap();
+    w.write(3, 0b011u8).unwrap();
+    assert_eq!(w.into_writer(), expected.into_writer());
+}
+
+#[test]
+fn test_writer_signed_range() {
+    use bitstream_io::{BigEndian, BitCounter, BitWrite, BitWriter, LittleEndian};
+    use std::io::ErrorKind;
+
+    fn check<W: BitWrite>(w: &mut W) {
+        for &bits in [1, 2, 10, 33, 48, 63].iter() {
+            let max = (1i64 << (bits - 1)) - 1;
+            let min = -1i64 << (bits - 1);
+            w.write_signed(bits, max).unwrap();
+            w.write_signed(bits, min).unwrap();
+            assert_eq!(
+                w.write_signed(bits, max + 1).unwrap_err().kind(),
+                ErrorKind::InvalidInput
+            );
+            assert_eq!(
+                w.write_signed(bits, min - 1).unwrap_err().kind(),
+                ErrorKind::InvalidInput
+            );
+        }
+        w.write_signed(64, i64::MAX).unwrap();
+        w.write_signed(64, i64::MIN).unwrap();
+        assert_eq!(
+            w.write_signed(10, -1000i32).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        w.write_signed(0, 0i8).unwrap();
+        w.write_signed(0, 0i64).unwrap();
+        assert_eq!(
+            w.write_signed(0, 1i8).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            w.write_signed(0, -1i8).unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+    }
+
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    check(&mut w);
+    w.byte_align().unwrap();
+    assert_eq!(w.into_writer().len(), 56);
+
+    let mut w = BitWriter::endian(Vec::new(), LittleEndian);
+    check(&mut w);
+    w.byte_align().unwrap();
+    assert_eq!(w.into_writer().len(), 56);
+
+    let mut c = BitCounter::new();
+    check(&mut c);
+    assert_eq!(c.written(), 2 * (1 + 2 + 10 + 33 + 48 + 63 + 64));
+}
+
+#[test]
+fn test_writer_bits_written() {
+    use bitstream_io::{BigEndian, BitWrite, BitWriter, LittleEndian};
+
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    w.write(3, 0b101u8).unwrap();
+    assert_eq!(w.padding_bits(), 5);
+    assert_eq!(w.bits_written(), 3);
+    w.write_bytes(b"ab").unwrap();
+    assert_eq!(w.padding_bits(), 5);
+    assert_eq!(w.bits_written(), 19);
+    w.write_unary0(20).unwrap();
+    w.write_signed(7, -3i8).unwrap();
+    assert_eq!(w.bits_written(), 47);
+    assert_eq!(w.padding_bits(), 1);
+    w.byte_align().unwrap();
+    assert_eq!(w.padding_bits(), 0);
+    assert_eq!(w.bits_written(), 48);
+    assert_eq!(w.into_writer().len(), 6);
+
+    let mut w = BitWriter::endian(Vec::new(), LittleEndian);
+    w.write(64, 0u64).unwrap();
+    w.write_bit(true).unwrap();
+    assert_eq!(w.bits_written(), 65);
+    assert_eq!(w.padding_bits(), 7);
+    assert!(w.write(9, 0x200u16).is_err());
+    assert_eq!(w.bits_written(), 65);
+}
+
+#[test]
+fn test_writer_into_bytes() {
+    use bitstream_io::{BigEndian, BitWrite, BitWriter, LittleEndian};
+
+    let mut w = BitWriter::<_, BigEndian>::to_vec();
+    w.write(4, 0xAu8).unwrap();
+    assert_eq!(w.into_bytes(), [0xA0]);
+
+    let mut w = BitWriter::<_, BigEndian>::to_vec();
+    w.write(16, 0xB1EDu16).unwrap();
+    assert_eq!(w.into_bytes(), [0xB1, 0xED]);
+
+    let mut w = BitWriter::<_, BigEndian>::to_vec();
+    w.write(15, 0x58F6u16).unwrap();
+    assert_eq!(w.into_bytes(), [0xB1, 0xEC]);
+
+    let mut w = BitWriter::<_, LittleEndian>::to_vec();
+    w.write(7, 0x31u8).unwrap();
+    assert_eq!(w.into_bytes(), [0x31]);
+
+    let w = BitWriter::<_, LittleEndian>::to_vec();
+    assert!(w.into_bytes().is_empty());
+
+    // existing bytes in the buffer are kept
+    let mut w = BitWriter::endian(vec![0xFF], BigEndian);
+    w.write_bit(true).unwrap();
+    assert_eq!(w.into_bytes(), [0xFF, 0x80]);
+}
+
+#[test]
+fn test_writer_padding() {
+    use bitstream_io::{BigEndian, BitWrite, BitWriter, LittleEndian};
+
+    // a 3-bit write aligned with 1 bits
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    w.set_padding(true);
+    w.write(3, 0b000u8).unwrap();
+    assert_eq!(w.padding_bits(), 5);
+    w.byte_align().unwrap();
+    assert_eq!(w.bits_written(), 8);
+    w.byte_align().unwrap();
+    w.write(8, 0u8).unwrap();
+    assert_eq!(w.into_writer(), [0b0001_1111, 0]);
+
+    let mut w = BitWriter::endian(Vec::new(), LittleEndian);
+    w.set_padding(true);
+    w.write(3, 0b000u8).unwrap();
+    w.flush().unwrap();
+    assert_eq!(w.into_writer(), [0b1111_1000]);
+
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    w.set_padding(true);
+    w.write(10, 0u16).unwrap();
+    assert_eq!(w.into_bytes(), [0, 0b0011_1111]);
+
+    let mut w = BitWriter::endian(Vec::new(), LittleEndian);
+    w.set_padding(true);
+    w.write(1, 0u8).unwrap();
+    assert_eq!(w.into_bytes(), [0b1111_1110]);
+
+    // switching back to the default
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    w.set_padding(true);
+    w.set_padding(false);
+    w.write(3, 0b111u8).unwrap();
+    w.byte_align().unwrap();
+    assert_eq!(w.into_writer(), [0b1110_0000]);
+}
+
+#[test]
+fn test_bits_required() {
+    use bitstream_io::{bits_required, bits_required_signed, BigEndian, BitWrite, BitWriter};
+
+    assert_eq!(bits_required(0), 0);
+    assert_eq!(bits_required(1), 1);
+    assert_eq!(bits_required(2), 2);
+    assert_eq!(bits_required(3), 2);
+    assert_eq!(bits_required(4), 3);
+    assert_eq!(bits_required(5), 3);
+    assert_eq!(bits_required(255), 8);
+    assert_eq!(bits_required(256), 9);
+    assert_eq!(bits_required(1 << 31), 32);
+    assert_eq!(bits_required(u32::MAX), 32);
+
+    assert_eq!(bits_required_signed(0), 1);
+    assert_eq!(bits_required_signed(-1), 1);
+    assert_eq!(bits_required_signed(1), 2);
+    assert_eq!(bits_required_signed(-2), 2);
+    assert_eq!(bits_required_signed(-3), 3);
+    assert_eq!(bits_required_signed(127), 8);
+    assert_eq!(bits_required_signed(-128), 8);
+    assert_eq!(bits_required_signed(128), 9);
+    assert_eq!(bits_required_signed(-129), 9);
+    assert_eq!(bits_required_signed(i32::MAX), 32);
+    assert_eq!(bits_required_signed(i32::MIN), 32);
+
+    // the widths given are sufficient, and one fewer is not
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    for &value in &[1u32, 5, 255, 256, u32::MAX] {
+        let bits = bits_required(value);
+        assert!(w.write(bits - 1, value).is_err());
+        w.write(bits, value).unwrap();
+    }
+    for &value in &[1i32, -2, 127, -128, 128, i32::MIN] {
+        let bits = bits_required_signed(value);
+        assert!(w.write_signed(bits - 1, value).is_err());
+        w.write_signed(bits, value).unwrap();
+    }
+}
+
+#[test]
+fn test_writer_from_slice() {
+    use bitstream_io::{BigEndian, BitWrite, BitWriter, LittleEndian};
+    use std::io::ErrorKind;
+
+    let mut buf = [0; 4];
+    let mut w = BitWriter::<_, BigEndian>::from_slice(&mut buf);
+    w.write(2, 2u32).unwrap();
+    w.write(3, 6u32).unwrap();
+    w.write(5, 7u32).unwrap();
+    w.write(3, 5u32).unwrap();
+    w.write(19, 0x53BC1u32).unwrap();
+    assert_eq!(w.bits_written(), 32);
+    // the buffer is full, so nothing more fits
+    assert_eq!(w.write(8, 0u8).unwrap_err().kind(), ErrorKind::WriteZero);
+    // a partial byte needs no room until it's complete
+    w.write_bit(true).unwrap();
+    assert_eq!(w.write(7, 0u8).unwrap_err().kind(), ErrorKind::WriteZero);
+    assert_eq!(w.bits_written(), 33);
+    assert_eq!(w.into_bytes_written(), 4);
+    assert_eq!(buf, [0xB1, 0xED, 0x3B, 0xC1]);
+
+    let mut buf = [0; 3];
+    let mut w = BitWriter::<_, LittleEndian>::from_slice(&mut buf);
+    w.write_bytes(&[1, 2]).unwrap();
+    assert_eq!(
+        w.write_bytes(&[3, 4]).unwrap_err().kind(),
+        ErrorKind::WriteZero
+    );
+    assert_eq!(w.bits_written(), 16);
+    assert_eq!(w.into_bytes_written(), 2);
+    assert_eq!(&buf[0..2], [1, 2]);
+
+    let mut empty = [];
+    let mut w = BitWriter::<_, BigEndian>::from_slice(&mut empty);
+    w.write(7, 0u8).unwrap();
+    assert!(w.write_bit(false).is_err());
+    assert_eq!(w.into_bytes_written(), 0);
+}
+
+#[test]
+fn test_writer_pad_to() {
+    use bitstream_io::{BigEndian, BitWrite, BitWriter, LittleEndian};
+    use std::io::ErrorKind;
+
+    // a 20-bit payload padded up to 32 bits
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    w.write(20, 0xB1ED3u32).unwrap();
+    w.pad_to(32).unwrap();
+    assert_eq!(w.bits_written(), 32);
+    w.pad_to(32).unwrap();
+    assert_eq!(w.bits_written(), 32);
+    assert_eq!(w.into_writer(), [0xB1, 0xED, 0x30, 0x00]);
+
+    let mut w = BitWriter::endian(Vec::new(), LittleEndian);
+    w.set_padding(true);
+    w.write(20, 0xB1ED3u32).unwrap();
+    w.pad_to(100).unwrap();
+    assert_eq!(w.bits_written(), 100);
+    assert_eq!(w.padding_bits(), 4);
+    w.set_padding(false);
+    w.byte_align().unwrap();
+    assert_eq!(
+        w.into_writer(),
+        [0xD3, 0x1E, 0xFB, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F]
+    );
+
+    // already past the target writes nothing
+    let mut w = BitWriter::endian(Vec::new(), BigEndian);
+    w.write(12, 0xFFFu16).unwrap();
+    assert_eq!(w.pad_to(11).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(w.bits_written(), 12);
+}